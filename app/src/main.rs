@@ -3,9 +3,9 @@ use gtk::glib;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::time::Duration;
 
 use engine::{EngineController, WebKitEngine};
+use memory::glue;
 use memory::pressure::{
     DefaultMemoryPressureSource, MemoryPressureMonitorConfig, MemoryPressureReceiver,
     MemoryPressureThresholds,
@@ -18,6 +18,7 @@ const APP_TITLE: &str = "OwL Browser";
 const DEFAULT_URI: &str = "https://example.com";
 
 fn main() -> glib::ExitCode {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
     let app = adw::Application::builder().application_id(APP_ID).build();
     app.connect_activate(build_ui);
     app.run()
@@ -28,6 +29,8 @@ fn build_ui(app: &adw::Application) {
     style_manager.set_color_scheme(adw::ColorScheme::Default);
 
     let engine = Rc::new(WebKitEngine::new());
+    // Probes and logs the capabilities once, up front.
+    engine.capabilities();
     let governor = Rc::new(ExecutionGovernor::new(Rc::clone(&engine)));
 
     let tab_manager = Rc::new(RefCell::new(BasicTabManager::new()));
@@ -58,24 +61,53 @@ fn build_ui(app: &adw::Application) {
         .default_height(800)
         .content(&root)
         .build();
+
+    let governor_for_focus = Rc::clone(&governor);
+    let manager_for_focus = Rc::clone(&tab_manager);
+    window.connect_is_active_notify(move |window| {
+        let Some(tab) = manager_for_focus.borrow().active_tab() else {
+            return;
+        };
+        if window.is_active() {
+            governor_for_focus.on_tab_focused(tab);
+        } else {
+            governor_for_focus.on_tab_blurred(tab);
+        }
+    });
+    // Minimizing only shows up in the toplevel surface state, which exists
+    // once the window is realized.
+    let governor_for_visibility = Rc::clone(&governor);
+    window.connect_realize(move |window| {
+        let Some(toplevel) = window.surface().and_downcast::<gtk::gdk::Toplevel>() else {
+            return;
+        };
+        let governor = Rc::clone(&governor_for_visibility);
+        toplevel.connect_state_notify(move |toplevel| {
+            let minimized = toplevel.state().contains(gtk::gdk::ToplevelState::MINIMIZED);
+            governor.set_window_visible(!minimized);
+        });
+    });
     window.present();
 
-    let pressure_receiver = Rc::new(MemoryPressureReceiver::start(
+    let pressure_receiver = MemoryPressureReceiver::start(
         DefaultMemoryPressureSource::default(),
         MemoryPressureThresholds::default(),
         MemoryPressureMonitorConfig::default(),
-    ));
-
-    let governor_for_poll = Rc::clone(&governor);
-    let pressure_for_poll = Rc::clone(&pressure_receiver);
-    glib::timeout_add_local(Duration::from_millis(250), move || {
-        if let Some(pressure) = pressure_for_poll.drain_latest() {
-            governor_for_poll.set_memory_pressure(pressure);
+    );
+    let pressure_source = RefCell::new(Some(glue::apply_to_governor(
+        Rc::clone(&governor),
+        pressure_receiver,
+        glue::DEFAULT_APPLY_INTERVAL,
+    )));
+    // Removing the source drops the receiver, which stops the monitor.
+    app.connect_shutdown(move |_| {
+        if let Some(source) = pressure_source.borrow_mut().take() {
+            source.remove();
         }
-        governor_for_poll.poll();
-        glib::ControlFlow::Continue
     });
 
+    schedule_poll(Rc::clone(&governor));
+
     create_tab(
         &engine,
         &tab_manager,
@@ -128,7 +160,11 @@ fn create_tab(
     uri: &str,
 ) -> TabId {
     let entry = manager.borrow_mut().create_tab();
-    let view = engine.create_view();
+    // A view keeps the memory limit it is created with, so size it for the
+    // tier the governor puts the new tab in: a tab opened into a blurred or
+    // minimized window does not start in the foreground.
+    let budget = governor.plan_for(entry.id, entry.state).budget;
+    let view = engine.create_view_for_budget(budget);
     view.set_hexpand(true);
     view.set_vexpand(true);
 
@@ -160,6 +196,14 @@ fn activate_tab(
     notify_governor(governor.as_ref(), &manager.borrow());
 }
 
+/// Polls the governor after the delay it recommends, then reschedules.
+fn schedule_poll(governor: Rc<ExecutionGovernor>) {
+    glib::timeout_add_local_once(governor.next_poll_delay(), move || {
+        governor.poll();
+        schedule_poll(governor);
+    });
+}
+
 fn notify_governor(governor: &dyn JSExecutionGovernor, manager: &BasicTabManager) {
     for tab in manager.tabs() {
         governor.on_tab_state_changed(tab.id, tab.state);
@@ -1,6 +1,7 @@
 const elements = {
   tabs: document.getElementById("tabs"),
   address: document.getElementById("address"),
+  addressSuggestions: document.getElementById("address-suggestions"),
   go: document.getElementById("go"),
   newTab: document.getElementById("new-tab"),
   home: document.getElementById("home"),
@@ -15,6 +16,13 @@ const elements = {
   paletteInput: document.getElementById("palette-input"),
   paletteResults: document.getElementById("palette-results"),
   tabMenu: document.getElementById("tab-menu"),
+  historyMenu: document.getElementById("history-menu"),
+  memoryIndicator: document.getElementById("memory-indicator"),
+  permissionPrompt: document.getElementById("permission-prompt"),
+  permissionText: document.getElementById("permission-text"),
+  permissionAllow: document.getElementById("permission-allow"),
+  permissionDeny: document.getElementById("permission-deny"),
+  permissionDismiss: document.getElementById("permission-dismiss"),
 };
 
 const state = {
@@ -27,8 +35,14 @@ const state = {
   defaultFavicon: null,
   tabMap: new Map(),
   tabQuery: "",
+  tabMatches: null,
   lastTabs: [],
+  tabsSeq: 0,
   lastActive: null,
+  bookmarks: [],
+  settings: null,
+  // Pending permission prompts, oldest first; only the first is shown.
+  permissions: [],
 };
 
 const menuState = {
@@ -36,6 +50,14 @@ const menuState = {
   tabId: null,
 };
 
+const HISTORY_PRESS_MS = 500;
+
+const historyMenuState = {
+  open: false,
+  pressTimer: null,
+  suppressClick: false,
+};
+
 const bridge = window.webkit?.messageHandlers?.owl;
 
 const send = (type, payload = {}) => {
@@ -51,6 +73,34 @@ const send = (type, payload = {}) => {
   }
 };
 
+const pendingRequests = new Map();
+let nextRequestId = 1;
+
+// Sends a command carrying a correlation id and resolves with its response.
+const request = (type, payload = {}) => {
+  if (!bridge) {
+    return Promise.reject(new Error(`Bridge not available: ${type}`));
+  }
+
+  const id = nextRequestId++;
+  return new Promise((resolve, reject) => {
+    pendingRequests.set(id, resolve);
+    try {
+      bridge.postMessage(JSON.stringify({ id, type, payload }));
+    } catch (error) {
+      pendingRequests.delete(id);
+      reject(error);
+    }
+  });
+};
+
+const resolveRequest = (id, payload) => {
+  const resolve = pendingRequests.get(id);
+  if (!resolve) return;
+  pendingRequests.delete(id);
+  resolve(payload);
+};
+
 const createActionButton = ({ className = "ghost", text, title, ariaLabel, onClick }) => {
   const button = document.createElement("button");
   button.className = className;
@@ -82,6 +132,7 @@ const buildRow = (node, depth) => {
     pinned: String(Boolean(node.is_pinned)),
     muted: String(Boolean(node.is_muted)),
     suspended: String(Boolean(node.is_suspended)),
+    private: String(Boolean(node.is_private)),
   });
 
   row.setAttribute("role", "treeitem");
@@ -199,16 +250,14 @@ const findActive = (nodes, id) => {
   return null;
 };
 
-const filterNodes = (nodes, query) => {
-  if (!query) return nodes;
-  const needle = query.toLowerCase();
+// Keeps nodes whose id is in `ids`, plus the ancestors of any match.
+const filterNodes = (nodes, ids) => {
+  if (!ids) return nodes;
   const filtered = [];
 
   for (const node of nodes) {
-    const title = node.title?.toLowerCase() || "";
-    const url = node.url?.toLowerCase() || "";
-    const matches = title.includes(needle) || url.includes(needle);
-    const children = node.children?.length ? filterNodes(node.children, query) : [];
+    const matches = ids.has(node.id);
+    const children = node.children?.length ? filterNodes(node.children, ids) : [];
 
     if (matches || children.length) {
       filtered.push({
@@ -238,9 +287,12 @@ const applyState = (newState) => {
   state.lastTabs = newState.tabs;
   state.lastActive = newState.active;
 
-  const filtered = filterNodes(state.lastTabs, state.tabQuery);
+  const filtered = filterNodes(state.lastTabs, state.tabMatches);
   renderTabs(filtered);
   closeTabMenu();
+  if (state.tabQuery) {
+    refreshTabMatches();
+  }
 
   const activeNode = state.lastActive ? findActive(state.lastTabs, state.lastActive) : null;
 
@@ -249,6 +301,57 @@ const applyState = (newState) => {
   }
 };
 
+// Asks the browser which tabs match the search box, then re-renders.
+const refreshTabMatches = () => {
+  const query = state.tabQuery;
+  if (!query) {
+    state.tabMatches = null;
+    renderTabs(state.lastTabs);
+    return;
+  }
+
+  request("tab.search", { query })
+    .then((payload) => {
+      if (query !== state.tabQuery) return;
+      state.tabMatches = new Set(payload?.ids || []);
+      renderTabs(filterNodes(state.lastTabs, state.tabMatches));
+    })
+    .catch((error) => console.warn("Tab search failed", error));
+};
+
+const applyFullState = (payload) => {
+  state.tabsSeq = payload.seq ?? 0;
+  applyState(payload);
+};
+
+const applyTabsPatch = (patch) => {
+  if (patch.seq !== state.tabsSeq + 1) {
+    send("state.resync");
+    return;
+  }
+  state.tabsSeq = patch.seq;
+
+  const nodes = new Map();
+  const collect = (list) => {
+    list.forEach((node) => {
+      nodes.set(node.id, node);
+      collect(node.children || []);
+    });
+  };
+  collect(state.lastTabs);
+
+  for (const change of patch.changes || []) {
+    const node = nodes.get(change.id);
+    if (!node) {
+      send("state.resync");
+      return;
+    }
+    Object.assign(node, change);
+  }
+
+  applyState({ tabs: state.lastTabs, active: patch.active });
+};
+
 const applyNavState = (nav) => {
   if (elements.navBack) {
     elements.navBack.disabled = !nav.can_go_back;
@@ -259,6 +362,11 @@ const applyNavState = (nav) => {
   }
 
   document.body.classList.toggle("is-loading", nav.is_loading);
+  if (nav.is_loading) {
+    document.body.classList.remove("load-timed-out");
+  } else if (nav.timed_out) {
+    document.body.classList.add("load-timed-out");
+  }
 
   if (elements.navReload) {
     if (nav.is_loading) {
@@ -319,6 +427,58 @@ const applyFaviconUpdate = (payload) => {
   });
 };
 
+const applyCrash = (payload) => {
+  const row = elements.tabs?.querySelector(`.tab[data-id="${payload?.id}"]`);
+  if (!row) return;
+
+  row.dataset.crashed = String(!payload.reloading);
+  if (!payload.reloading) {
+    row.title = "This page crashed. Reload to try again.";
+  }
+};
+
+const applyMemoryState = (payload) => {
+  const indicator = elements.memoryIndicator;
+  if (!indicator) return;
+
+  const level = payload?.level || "low";
+  indicator.dataset.level = level;
+  indicator.hidden = level === "low";
+  indicator.textContent = level === "severe" ? "Memory low" : "Memory tight";
+  indicator.title = `Memory pressure: ${level}. Background tabs are being throttled.`;
+};
+
+const PERMISSION_LABELS = {
+  geolocation: "know your location",
+  notifications: "show notifications",
+  media: "use your camera or microphone",
+};
+
+const renderPermissionPrompt = () => {
+  const prompt = elements.permissionPrompt;
+  if (!prompt) return;
+
+  const current = state.permissions[0];
+  prompt.hidden = !current;
+  if (!current || !elements.permissionText) return;
+
+  const what = PERMISSION_LABELS[current.kind] || current.kind;
+  elements.permissionText.textContent = `${current.origin} wants to ${what}.`;
+};
+
+const applyPermission = (payload) => {
+  if (!payload?.id) return;
+  state.permissions.push(payload);
+  renderPermissionPrompt();
+};
+
+const respondToPermission = (allow, dismissed = false) => {
+  const current = state.permissions.shift();
+  if (!current) return;
+  send("permission.respond", { id: current.id, allow, dismissed });
+  renderPermissionPrompt();
+};
+
 const syncCollapsedTabTitles = (collapsed) => {
   if (!elements.tabs) return;
 
@@ -359,12 +519,120 @@ const navigateFromAddress = () => {
   }
 };
 
+const TIMER_CLAMPS = ["off", "balanced", "aggressive"];
+const NEW_TAB_PAGES = ["home", "blank", "last_session"];
+
+// Sends a partial settings update; the host answers with `state.settings`.
+const changeSettings = (changes) => {
+  request("settings.set", { changes }).then((result) => {
+    if (!result?.ok) {
+      console.warn("Settings rejected", result?.error);
+    }
+  });
+};
+
+const MINIMUM_FONT_SIZES = [0, 9, 12, 14];
+
+// Sends a partial accessibility update; see `changeSettings`.
+const changeAccessibility = (changes) => {
+  request("settings.a11y.set", changes).then((result) => {
+    if (!result?.ok) {
+      console.warn("Accessibility settings rejected", result?.error);
+    }
+  });
+};
+
+const stepFontSize = (delta) => {
+  const current = state.settings?.accessibility?.default_font_size ?? 16;
+  changeAccessibility({ default_font_size: Math.min(72, Math.max(6, current + delta)) });
+};
+
+const cycleSetting = (key, values, fallback) => {
+  const current = values.indexOf(state.settings?.[key] ?? fallback);
+  changeSettings({ [key]: values[(current + 1) % values.length] });
+};
+
 const COMMANDS = [
   { id: "new-tab", label: "New Tab", run: () => send("tab.create") },
+  {
+    id: "new-private-tab",
+    label: "New Private Tab",
+    run: () => send("tab.create", { private: true })
+  },
+  { id: "dedupe", label: "Close Duplicate Tabs", run: () => send("tab.dedupe") },
+  { id: "collapse-all", label: "Collapse All Tabs", run: () => send("tab.collapse_all") },
+  { id: "expand-all", label: "Expand All Tabs", run: () => send("tab.expand_all") },
+  {
+    id: "suspend-all",
+    label: "Suspend All Background Tabs",
+    run: () => send("tab.suspend_all")
+  },
+  { id: "wake-all", label: "Wake All Tabs", run: () => send("tab.wake_all") },
   { id: "back", label: "Go Back", run: () => send("nav.back") },
   { id: "forward", label: "Go Forward", run: () => send("nav.forward") },
   { id: "reload", label: "Reload", run: () => send("nav.reload") },
   { id: "home", label: "Go Home", run: () => send("nav.home") },
+  { id: "bookmark", label: "Bookmark This Page", run: () => send("bookmark.add") },
+  { id: "reader", label: "Toggle Reader Mode", run: () => send("nav.reader.toggle") },
+  { id: "zoom-in", label: "Zoom In", run: () => send("nav.zoom", { step: 1 }) },
+  { id: "zoom-out", label: "Zoom Out", run: () => send("nav.zoom", { step: -1 }) },
+  { id: "zoom-reset", label: "Reset Zoom", run: () => send("nav.zoom", { step: 0 }) },
+  {
+    id: "zoom-text-only",
+    label: "Toggle Text-Only Zoom",
+    run: () => changeSettings({ zoom_text_only: !state.settings?.zoom_text_only })
+  },
+  {
+    id: "high-contrast",
+    label: "Toggle High Contrast Pages",
+    run: () =>
+      changeAccessibility({ high_contrast: !state.settings?.accessibility?.high_contrast })
+  },
+  { id: "text-larger", label: "Larger Page Text", run: () => stepFontSize(2) },
+  { id: "text-smaller", label: "Smaller Page Text", run: () => stepFontSize(-2) },
+  {
+    id: "minimum-font-size",
+    label: "Cycle Minimum Font Size (Off, 9, 12, 14 px)",
+    run: () => {
+      const current = MINIMUM_FONT_SIZES.indexOf(
+        state.settings?.accessibility?.minimum_font_size ?? 0
+      );
+      changeAccessibility({
+        minimum_font_size: MINIMUM_FONT_SIZES[(current + 1) % MINIMUM_FONT_SIZES.length]
+      });
+    }
+  },
+  {
+    id: "proxy-system",
+    label: "Use System Proxy Settings",
+    run: () => send("settings.proxy.set", { mode: "system" })
+  },
+  {
+    id: "proxy-none",
+    label: "Connect Without a Proxy",
+    run: () => send("settings.proxy.set", { mode: "none" })
+  },
+  { id: "settings", label: "Open Settings", run: () => send("nav.go", { url: "owl://settings" }) },
+  {
+    id: "force-dark",
+    label: "Toggle Dark Pages",
+    run: () => changeSettings({ force_dark: !state.settings?.force_dark })
+  },
+  {
+    id: "content-filter",
+    label: "Toggle Content Filtering",
+    run: () => changeSettings({ content_filter: !state.settings?.content_filter })
+  },
+  {
+    id: "timer-clamp",
+    label: "Cycle Background Timer Throttling",
+    run: () => cycleSetting("timer_clamp", TIMER_CLAMPS, "balanced")
+  },
+  {
+    id: "new-tab-page",
+    label: "Cycle New Tab Page (Home, Blank, Last Session)",
+    run: () => cycleSetting("new_tab_page", NEW_TAB_PAGES, "home")
+  },
   {
     id: "toggle-sidebar",
     label: "Toggle Sidebar",
@@ -475,13 +743,85 @@ const openTabMenu = (event, id) => {
   });
 };
 
+const closeHistoryMenu = () => {
+  if (!elements.historyMenu) return;
+  elements.historyMenu.classList.remove("is-open");
+  elements.historyMenu.setAttribute("aria-hidden", "true");
+  historyMenuState.open = false;
+};
+
+// Lists the back entries of the active tab under the Back button, newest first.
+const openHistoryMenu = async () => {
+  if (!elements.historyMenu || !elements.navBack) return;
+
+  let entries = [];
+  try {
+    ({ entries = [] } = await request("nav.history.list"));
+  } catch (error) {
+    console.warn("History unavailable", error);
+    return;
+  }
+
+  const back = entries.filter(({ offset }) => offset < 0).reverse();
+  if (!back.length) return;
+
+  const fragment = document.createDocumentFragment();
+  back.forEach(({ title, uri, offset }) => {
+    const item = document.createElement("button");
+    item.type = "button";
+    item.setAttribute("role", "menuitem");
+    item.dataset.offset = String(offset);
+    item.textContent = title || uri;
+    item.title = uri;
+    fragment.appendChild(item);
+  });
+  elements.historyMenu.replaceChildren(fragment);
+
+  closeTabMenu();
+  const rect = elements.navBack.getBoundingClientRect();
+  elements.historyMenu.style.left = `${Math.max(8, rect.left)}px`;
+  elements.historyMenu.style.top = `${rect.bottom + 4}px`;
+  elements.historyMenu.classList.add("is-open");
+  elements.historyMenu.setAttribute("aria-hidden", "false");
+  historyMenuState.open = true;
+  elements.historyMenu.querySelector("button")?.focus();
+};
+
+const cancelHistoryPress = () => {
+  clearTimeout(historyMenuState.pressTimer);
+  historyMenuState.pressTimer = null;
+};
+
+// Fills the address bar suggestions; replies for an outdated query are dropped.
+const applySuggestions = (payload) => {
+  if (!elements.addressSuggestions || !elements.address) return;
+  if (payload?.query !== elements.address.value.trim()) return;
+
+  const fragment = document.createDocumentFragment();
+  (payload.suggestions || []).forEach(({ url, display_url, title }) => {
+    const option = document.createElement("option");
+    option.value = url;
+    option.label = title ? `${title} — ${display_url}` : display_url;
+    fragment.appendChild(option);
+  });
+  elements.addressSuggestions.replaceChildren(fragment);
+};
+
 const setupEventHandlers = () => {
   elements.address?.addEventListener("keydown", ({ key }) => {
     if (key === "Enter") navigateFromAddress();
   });
 
+  elements.address?.addEventListener("input", () => {
+    send("nav.suggest", { query: elements.address.value.trim() });
+  });
+
   elements.go?.addEventListener("click", navigateFromAddress);
 
+  elements.permissionAllow?.addEventListener("click", () => respondToPermission(true));
+  elements.permissionDeny?.addEventListener("click", () => respondToPermission(false));
+  elements.permissionDismiss?.addEventListener("click", () => respondToPermission(false, true));
+
   if (elements.navBack) {
     elements.navBack.textContent = "Back";
     elements.navBack.setAttribute("aria-label", "Back");
@@ -498,7 +838,36 @@ const setupEventHandlers = () => {
 
   elements.newTab?.addEventListener("click", () => send("tab.create"));
   elements.home?.addEventListener("click", () => send("nav.home"));
-  elements.navBack?.addEventListener("click", () => send("nav.back"));
+  elements.navBack?.addEventListener("click", () => {
+    // The click ending a long press only opens the history menu.
+    if (historyMenuState.suppressClick) {
+      historyMenuState.suppressClick = false;
+      return;
+    }
+    send("nav.back");
+  });
+  elements.navBack?.addEventListener("pointerdown", () => {
+    cancelHistoryPress();
+    historyMenuState.suppressClick = false;
+    historyMenuState.pressTimer = setTimeout(() => {
+      historyMenuState.pressTimer = null;
+      historyMenuState.suppressClick = true;
+      openHistoryMenu();
+    }, HISTORY_PRESS_MS);
+  });
+  elements.navBack?.addEventListener("pointerup", cancelHistoryPress);
+  elements.navBack?.addEventListener("pointerleave", cancelHistoryPress);
+  elements.navBack?.addEventListener("contextmenu", (event) => {
+    event.preventDefault();
+    cancelHistoryPress();
+    openHistoryMenu();
+  });
+  elements.historyMenu?.addEventListener("click", (event) => {
+    const item = event.target.closest("button[data-offset]");
+    if (!item) return;
+    send("nav.history.go", { offset: Number(item.dataset.offset) });
+    closeHistoryMenu();
+  });
   elements.navForward?.addEventListener("click", () => send("nav.forward"));
 
   elements.navReload?.addEventListener("click", () => {
@@ -512,8 +881,7 @@ const setupEventHandlers = () => {
 
   elements.tabSearch?.addEventListener("input", (event) => {
     state.tabQuery = event.target.value.trim();
-    const filtered = filterNodes(state.lastTabs, state.tabQuery);
-    renderTabs(filtered);
+    refreshTabMatches();
   });
 
   if (state.peekEnabled) {
@@ -593,6 +961,22 @@ const setupEventHandlers = () => {
         event.preventDefault();
         openPalette();
       },
+      "=": () => {
+        event.preventDefault();
+        send("nav.zoom", { step: 1 });
+      },
+      "+": () => {
+        event.preventDefault();
+        send("nav.zoom", { step: 1 });
+      },
+      "-": () => {
+        event.preventDefault();
+        send("nav.zoom", { step: -1 });
+      },
+      0: () => {
+        event.preventDefault();
+        send("nav.zoom", { step: 0 });
+      },
     };
 
     if (modKey && shortcuts[key]) {
@@ -606,6 +990,9 @@ const setupEventHandlers = () => {
       if (menuState.open) {
         closeTabMenu();
       }
+      if (historyMenuState.open) {
+        closeHistoryMenu();
+      }
     }
   });
 
@@ -617,6 +1004,7 @@ const setupEventHandlers = () => {
     if (action === "pin") send("tab.pin", { id: menuState.tabId });
     if (action === "mute") send("tab.mute", { id: menuState.tabId });
     if (action === "unload") send("tab.unload", { id: menuState.tabId });
+    if (action === "duplicate") send("tab.duplicate", { id: menuState.tabId });
 
     closeTabMenu();
   });
@@ -647,6 +1035,13 @@ const setupEventHandlers = () => {
   });
 
   window.addEventListener("click", (event) => {
+    if (
+      historyMenuState.open &&
+      !elements.historyMenu?.contains(event.target) &&
+      !elements.navBack?.contains(event.target)
+    ) {
+      closeHistoryMenu();
+    }
     if (!menuState.open) return;
     if (!elements.tabMenu?.contains(event.target)) {
       closeTabMenu();
@@ -684,11 +1079,23 @@ window.__owl_receive = (message) => {
   if (!message?.type) return;
 
   const messageHandlers = {
-    "state.tabs": () => applyState(message.payload),
+    "state.tabs": () => applyFullState(message.payload),
+    "state.tabs.patch": () => applyTabsPatch(message.payload),
     "state.nav": () => applyNavState(message.payload),
     "state.assets": () => applyAssets(message.payload),
     "state.favicon": () => applyFaviconUpdate(message.payload),
     "state.sidebar": () => applySidebarState(Boolean(message.payload?.collapsed)),
+    "state.suggestions": () => applySuggestions(message.payload),
+    "state.crash": () => applyCrash(message.payload),
+    "state.memory": () => applyMemoryState(message.payload),
+    "state.permission": () => applyPermission(message.payload),
+    "state.bookmarks": () => {
+      state.bookmarks = message.payload?.bookmarks || [];
+    },
+    "state.settings": () => {
+      state.settings = message.payload || null;
+    },
+    response: () => resolveRequest(message.id, message.payload),
   };
 
   messageHandlers[message.type]?.();
@@ -1,18 +1,45 @@
-use std::cell::RefCell;
-use std::collections::HashMap;
+use scheduler::{ExecutionBudget, ExecutionBudgetHints};
+use tabs::{TabId, TabState};
+
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "webkit")]
+mod webkit;
 
-use gtk::prelude::*;
-use scheduler::{
-    EngineExecutionFeedback, EngineFeedbackProvider, EngineScheduler, ExecutionBudget,
-    ExecutionBudgetHints,
+#[cfg(feature = "webkit")]
+pub use webkit::{
+    FindCallback, FindMatches, FindOptions, NavEntry, PrintError, ProxyConfig, ScriptInjectionTime,
+    ScriptPause, ScriptWorld, SnapshotError, SnapshotRegion, UserScriptId, WebKitEngine,
 };
-use tabs::{TabId, TabState};
-use webkit6::prelude::*;
+
+/// Optional engine features, detected once at runtime.
+///
+/// Higher layers branch on these instead of relying on calls that silently
+/// do nothing when the engine build lacks a feature.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct EngineCapabilities {
+    /// JavaScript can be switched off per view, which suspension relies on.
+    pub javascript_toggle: bool,
+    /// Web process memory limits can be configured.
+    pub memory_limits: bool,
+    /// Content-blocker rule lists can be compiled and applied.
+    pub content_filters: bool,
+    /// Views can search their page text.
+    pub find: bool,
+    /// JavaScript can be paused and later resumed with timers and page state
+    /// intact. Without it, pausing disables JavaScript instead.
+    pub script_pause: bool,
+    /// Network sessions accept proxy settings; see `WebKitEngine::set_proxy`.
+    pub proxy_settings: bool,
+}
 
 /// Interface to the web engine implementation.
 pub trait EngineController {
     type View;
 
+    /// Returns the optional features this engine supports.
+    fn capabilities(&self) -> EngineCapabilities;
+
     /// Creates a new view instance for a tab.
     fn create_view(&self) -> Self::View;
 
@@ -34,107 +61,3 @@ pub trait EngineController {
     /// Applies advisory execution hints for the tab.
     fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints);
 }
-
-/// WebKitGTK-backed engine controller.
-#[derive(Debug, Default)]
-pub struct WebKitEngine {
-    views: RefCell<HashMap<TabId, webkit6::WebView>>,
-}
-
-impl WebKitEngine {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    fn settings() -> webkit6::Settings {
-        webkit6::Settings::builder()
-            .enable_javascript(true)
-            .build()
-    }
-
-    fn with_view<F: FnOnce(&webkit6::WebView)>(&self, tab: TabId, f: F) {
-        if let Some(view) = self.views.borrow().get(&tab) {
-            f(view);
-        }
-    }
-
-    fn set_javascript_enabled(view: &webkit6::WebView, enabled: bool) {
-        if let Some(settings) = webkit6::prelude::WebViewExt::settings(view) {
-            settings.set_enable_javascript(enabled);
-        }
-    }
-}
-
-impl EngineController for WebKitEngine {
-    type View = webkit6::WebView;
-
-    fn create_view(&self) -> Self::View {
-        let settings = Self::settings();
-        webkit6::WebView::builder().settings(&settings).build()
-    }
-
-    fn load_uri(&self, view: &Self::View, uri: &str) {
-        view.load_uri(uri);
-    }
-
-    fn register_view(&self, tab: TabId, view: &Self::View) {
-        self.views.borrow_mut().insert(tab, view.clone());
-    }
-
-    fn unregister_view(&self, tab: TabId) {
-        self.views.borrow_mut().remove(&tab);
-    }
-
-    fn apply_tab_state(&self, tab: TabId, state: TabState) {
-        self.with_view(tab, |view| match state {
-            TabState::Active => {
-                view.set_visible(true);
-                Self::set_javascript_enabled(view, true);
-            }
-            TabState::Background => {
-                // WebKitGTK does not expose explicit timer-clamp controls. We rely on
-                // widget visibility to trigger Page Visibility throttling in the engine.
-                view.set_visible(false);
-                Self::set_javascript_enabled(view, true);
-            }
-            TabState::Suspended => {
-                // WebKitGTK does not currently expose a true pause/resume API for JS.
-                // Disabling JavaScript is the closest safe approximation for suspension.
-                view.set_visible(false);
-                Self::set_javascript_enabled(view, false);
-            }
-        });
-    }
-
-    fn apply_execution_budget(&self, tab: TabId, _budget: ExecutionBudget) {
-        // TODO: Apply per-tab CPU and scheduling budgets when cgroup integration lands.
-        self.with_view(tab, |_| {});
-    }
-
-    fn apply_execution_hints(&self, tab: TabId, _hints: ExecutionBudgetHints) {
-        // TODO: Map advisory hints to WebKit settings once supported.
-        self.with_view(tab, |_| {});
-    }
-}
-
-impl EngineScheduler for WebKitEngine {
-    fn apply_tab_state(&self, tab: TabId, state: TabState) {
-        <Self as EngineController>::apply_tab_state(self, tab, state);
-    }
-
-    fn apply_execution_budget(&self, tab: TabId, budget: ExecutionBudget) {
-        <Self as EngineController>::apply_execution_budget(self, tab, budget);
-    }
-
-    fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints) {
-        <Self as EngineController>::apply_execution_hints(self, tab, hints);
-    }
-}
-
-impl EngineFeedbackProvider for WebKitEngine {
-    fn poll_execution_feedback(&self, _tab: TabId) -> EngineExecutionFeedback {
-        // WebKitGTK does not currently expose reliable per-tab signals for these fields.
-        // Return conservative defaults until the backend provides explicit counters.
-        EngineExecutionFeedback::default()
-    }
-}
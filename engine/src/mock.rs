@@ -0,0 +1,244 @@
+//! GTK-free engine backend for exercising engine consumers in headless tests.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use scheduler::{
+    EngineExecutionFeedback, EngineFeedbackProvider, EngineScheduler, ExecutionBudget,
+    ExecutionBudgetHints,
+};
+use tabs::{TabId, TabState};
+
+use crate::{EngineCapabilities, EngineController};
+
+/// Stand-in for a web view that records the URIs loaded into it.
+///
+/// Clones share their load history, like clones of a GTK widget.
+#[derive(Debug, Clone, Default)]
+pub struct MockView {
+    id: u64,
+    loads: Rc<RefCell<Vec<String>>>,
+}
+
+impl MockView {
+    /// Returns the id assigned when the engine created the view.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns every URI loaded into the view, oldest first.
+    pub fn loaded_uris(&self) -> Vec<String> {
+        self.loads.borrow().clone()
+    }
+
+    /// Returns the most recently loaded URI.
+    pub fn uri(&self) -> Option<String> {
+        self.loads.borrow().last().cloned()
+    }
+}
+
+/// A single call received by [`MockEngine`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MockCall {
+    CreateView(u64),
+    LoadUri(u64, String),
+    RegisterView(TabId, u64),
+    UnregisterView(TabId),
+    TabState(TabId, TabState),
+    Budget(TabId, ExecutionBudget),
+    Hints(TabId, ExecutionBudgetHints),
+}
+
+/// Engine backend that keeps all state in memory and logs every call.
+///
+/// The latest state, budget and hints applied to each tab are kept until
+/// the tab is unregistered. Feedback polls return the value set with
+/// [`MockEngine::set_feedback`] and are not logged.
+#[derive(Debug, Default)]
+pub struct MockEngine {
+    calls: RefCell<Vec<MockCall>>,
+    next_view: Cell<u64>,
+    views: RefCell<HashMap<TabId, MockView>>,
+    states: RefCell<HashMap<TabId, TabState>>,
+    budgets: RefCell<HashMap<TabId, ExecutionBudget>>,
+    hints: RefCell<HashMap<TabId, ExecutionBudgetHints>>,
+    feedback: RefCell<HashMap<TabId, EngineExecutionFeedback>>,
+    capabilities: Cell<EngineCapabilities>,
+}
+
+impl MockEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every call received so far, oldest first.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Forgets all logged calls, keeping per-tab state.
+    pub fn clear_calls(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    /// Returns the view registered for the tab.
+    pub fn view(&self, tab: TabId) -> Option<MockView> {
+        self.views.borrow().get(&tab).cloned()
+    }
+
+    /// Returns the state most recently applied to the tab.
+    pub fn applied_state(&self, tab: TabId) -> Option<TabState> {
+        self.states.borrow().get(&tab).copied()
+    }
+
+    /// Returns the budget most recently applied to the tab.
+    pub fn applied_budget(&self, tab: TabId) -> Option<ExecutionBudget> {
+        self.budgets.borrow().get(&tab).copied()
+    }
+
+    /// Returns the hints most recently applied to the tab.
+    pub fn applied_hints(&self, tab: TabId) -> Option<ExecutionBudgetHints> {
+        self.hints.borrow().get(&tab).copied()
+    }
+
+    /// Sets the capabilities the engine reports; none by default.
+    pub fn set_capabilities(&self, capabilities: EngineCapabilities) {
+        self.capabilities.set(capabilities);
+    }
+
+    /// Sets the feedback returned for the tab; other tabs report defaults.
+    pub fn set_feedback(&self, tab: TabId, feedback: EngineExecutionFeedback) {
+        self.feedback.borrow_mut().insert(tab, feedback);
+    }
+
+    fn record(&self, call: MockCall) {
+        self.calls.borrow_mut().push(call);
+    }
+}
+
+impl EngineController for MockEngine {
+    type View = MockView;
+
+    fn capabilities(&self) -> EngineCapabilities {
+        self.capabilities.get()
+    }
+
+    fn create_view(&self) -> MockView {
+        let id = self.next_view.get() + 1;
+        self.next_view.set(id);
+        self.record(MockCall::CreateView(id));
+        MockView {
+            id,
+            loads: Rc::default(),
+        }
+    }
+
+    fn load_uri(&self, view: &MockView, uri: &str) {
+        self.record(MockCall::LoadUri(view.id, uri.to_string()));
+        view.loads.borrow_mut().push(uri.to_string());
+    }
+
+    fn register_view(&self, tab: TabId, view: &MockView) {
+        self.record(MockCall::RegisterView(tab, view.id));
+        self.views.borrow_mut().insert(tab, view.clone());
+    }
+
+    fn unregister_view(&self, tab: TabId) {
+        self.record(MockCall::UnregisterView(tab));
+        self.views.borrow_mut().remove(&tab);
+        self.states.borrow_mut().remove(&tab);
+        self.budgets.borrow_mut().remove(&tab);
+        self.hints.borrow_mut().remove(&tab);
+        self.feedback.borrow_mut().remove(&tab);
+    }
+
+    fn apply_tab_state(&self, tab: TabId, state: TabState) {
+        self.record(MockCall::TabState(tab, state));
+        self.states.borrow_mut().insert(tab, state);
+    }
+
+    fn apply_execution_budget(&self, tab: TabId, budget: ExecutionBudget) {
+        self.record(MockCall::Budget(tab, budget));
+        self.budgets.borrow_mut().insert(tab, budget);
+    }
+
+    fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints) {
+        self.record(MockCall::Hints(tab, hints));
+        self.hints.borrow_mut().insert(tab, hints);
+    }
+}
+
+impl EngineScheduler for MockEngine {
+    fn apply_tab_state(&self, tab: TabId, state: TabState) {
+        <Self as EngineController>::apply_tab_state(self, tab, state);
+    }
+
+    fn apply_execution_budget(&self, tab: TabId, budget: ExecutionBudget) {
+        <Self as EngineController>::apply_execution_budget(self, tab, budget);
+    }
+
+    fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints) {
+        <Self as EngineController>::apply_execution_hints(self, tab, hints);
+    }
+
+    fn supports_script_pause(&self) -> bool {
+        self.capabilities().script_pause
+    }
+}
+
+impl EngineFeedbackProvider for MockEngine {
+    fn poll_execution_feedback(&self, tab: TabId) -> EngineExecutionFeedback {
+        self.feedback
+            .borrow()
+            .get(&tab)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use scheduler::{BudgetTier, ExecutionGovernor, JSExecutionGovernor};
+    use tabs::{TabId, TabState};
+
+    use super::{MockCall, MockEngine};
+    use crate::EngineController;
+
+    #[test]
+    fn governor_decisions_reach_the_mock() {
+        let engine = Rc::new(MockEngine::new());
+        let governor = ExecutionGovernor::new(Rc::clone(&engine));
+        let tab = TabId::new(1);
+        let view = engine.create_view();
+        engine.register_view(tab, &view);
+        engine.load_uri(&view, "https://example.org/");
+
+        governor.on_tab_state_changed(tab, TabState::Active);
+
+        assert_eq!(engine.applied_state(tab), Some(TabState::Active));
+        assert_eq!(
+            engine.applied_budget(tab).map(|budget| budget.tier),
+            Some(BudgetTier::Foreground)
+        );
+        assert!(engine.applied_hints(tab).is_some());
+        assert_eq!(
+            engine.view(tab).and_then(|view| view.uri()).as_deref(),
+            Some("https://example.org/")
+        );
+        assert_eq!(
+            engine.calls()[..3],
+            [
+                MockCall::CreateView(view.id()),
+                MockCall::RegisterView(tab, view.id()),
+                MockCall::LoadUri(view.id(), "https://example.org/".to_string()),
+            ]
+        );
+
+        engine.unregister_view(tab);
+        assert_eq!(engine.applied_state(tab), None);
+        assert!(engine.view(tab).is_none());
+    }
+}
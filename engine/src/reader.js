@@ -0,0 +1,31 @@
+// Reader mode: marks the element holding most of the page's paragraph text
+// as the content root, and its ancestors as the path to it. reader.css
+// hides everything else.
+(() => {
+  const root = document.documentElement;
+  if (!document.body || root.classList.contains("owl-reader")) return;
+
+  const textLength = (element) =>
+    Array.from(element.children)
+      .filter((child) => child.tagName === "P" || child.tagName === "PRE")
+      .reduce((length, child) => length + child.textContent.trim().length, 0);
+
+  let best = null;
+  let bestLength = 0;
+  document.body
+    .querySelectorAll("article, main, [role='main'], section, div")
+    .forEach((element) => {
+      const length = textLength(element);
+      if (length > bestLength) {
+        best = element;
+        bestLength = length;
+      }
+    });
+  if (!best || bestLength < 200) return;
+
+  best.setAttribute("data-owl-reader", "content");
+  for (let node = best.parentElement; node; node = node.parentElement) {
+    node.setAttribute("data-owl-reader", "path");
+  }
+  root.classList.add("owl-reader");
+})();
@@ -0,0 +1,1383 @@
+//! WebKitGTK implementation of the engine interface.
+
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use gtk::glib;
+use gtk::prelude::*;
+use scheduler::{
+    BudgetTier, EngineExecutionFeedback, EngineFeedbackProvider, EngineScheduler,
+    ExecutionBudget, ExecutionBudgetHints,
+};
+use tabs::{TabId, TabState};
+use webkit6::prelude::*;
+
+use crate::{EngineCapabilities, EngineController};
+
+/// Upper bound on matches counted by find-in-page.
+const MAX_FIND_MATCHES: u32 = 1000;
+
+/// Options for find-in-page searches. Searches always wrap around.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    pub at_word_starts: bool,
+}
+
+impl FindOptions {
+    fn to_webkit(self) -> u32 {
+        let mut flags = webkit6::FindOptions::WRAP_AROUND;
+        if !self.case_sensitive {
+            flags |= webkit6::FindOptions::CASE_INSENSITIVE;
+        }
+        if self.at_word_starts {
+            flags |= webkit6::FindOptions::AT_WORD_STARTS;
+        }
+        flags.bits()
+    }
+}
+
+/// How [`WebKitEngine::pause_scripts`] stopped a tab's JavaScript.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScriptPause {
+    /// Execution is halted and continues where it stopped, with timers and
+    /// page state intact. Suits `Frozen`.
+    Paused,
+    /// JavaScript is disabled. Running timers stop for good, so the page may
+    /// need a reload to work again. Only suits `Suspended`.
+    Disabled,
+}
+
+/// When a script added with [`WebKitEngine::add_user_script`] runs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScriptInjectionTime {
+    /// Before any of the page's own scripts, with an empty document.
+    DocumentStart,
+    /// Once the document has been parsed, before subresources finish loading.
+    DocumentEnd,
+}
+
+/// Where a script added with [`WebKitEngine::add_user_script`] runs.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ScriptWorld {
+    /// Alongside the page's scripts, sharing their globals.
+    Main,
+    /// In a separate world that shares the DOM but not JavaScript globals,
+    /// so the page can neither see nor tamper with the script.
+    Isolated,
+}
+
+/// Proxy used by the engine's network sessions; see [`WebKitEngine::set_proxy`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyConfig {
+    /// The desktop's proxy configuration.
+    System,
+    /// No proxy, even if the desktop has one.
+    Direct,
+    /// All requests go through the proxy at this uri, e.g.
+    /// `socks5://127.0.0.1:9050`.
+    Manual(String),
+}
+
+/// Identifies a script added with [`WebKitEngine::add_user_script`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct UserScriptId(u64);
+
+/// Find-in-page position. `current` is 1-based and zero when nothing matched.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct FindMatches {
+    pub current: u32,
+    pub total: u32,
+}
+
+/// Most back or forward entries returned by [`WebKitEngine::back_forward_list`]
+/// on each side of the current entry.
+const MAX_HISTORY_ENTRIES: i32 = 15;
+
+/// One entry of a tab's back/forward history.
+///
+/// `offset` is relative to the current entry: negative values go back,
+/// positive values go forward, and zero is the current page.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NavEntry {
+    pub title: String,
+    pub uri: String,
+    pub offset: i32,
+    pub is_current: bool,
+}
+
+/// Most hosts prefetched within one [`PREFETCH_WINDOW`].
+const MAX_PREFETCHES: usize = 4;
+
+/// How long a prefetched host counts against [`MAX_PREFETCHES`] and is not
+/// prefetched again.
+const PREFETCH_WINDOW: Duration = Duration::from_secs(10);
+
+/// Callback receiving find-in-page match updates.
+pub type FindCallback = Rc<dyn Fn(FindMatches)>;
+
+struct FindSession {
+    controller: webkit6::FindController,
+    matches: Rc<Cell<FindMatches>>,
+    callback: FindCallback,
+    handlers: Vec<glib::SignalHandlerId>,
+}
+
+impl fmt::Debug for FindSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FindSession")
+            .field("matches", &self.matches.get())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Part of the page captured by [`WebKitEngine::capture_snapshot`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SnapshotRegion {
+    /// Only the currently visible viewport.
+    Viewport,
+    /// The whole document, including content scrolled out of view.
+    FullDocument,
+}
+
+/// Reasons a snapshot could not be captured.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// No view is registered for the tab.
+    UnknownTab,
+    /// The view has not been realized yet, so there is nothing to capture.
+    NotRealized,
+    /// WebKit failed to render the snapshot, e.g. because the web process crashed.
+    Capture(glib::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::UnknownTab => f.write_str("no view registered for tab"),
+            SnapshotError::NotRealized => f.write_str("view is not realized"),
+            SnapshotError::Capture(error) => write!(f, "snapshot failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Reasons a page could not be printed to PDF.
+#[derive(Debug)]
+pub enum PrintError {
+    /// No view is registered for the tab.
+    UnknownTab,
+    /// The page is still loading; printing now would capture a partial page.
+    Loading,
+    /// The destination is not an absolute path or cannot be written.
+    Destination(std::io::Error),
+    /// WebKit failed to print the page.
+    Print(glib::Error),
+}
+
+impl fmt::Display for PrintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrintError::UnknownTab => f.write_str("no view registered for tab"),
+            PrintError::Loading => f.write_str("page is still loading"),
+            PrintError::Destination(error) => write!(f, "cannot write destination: {error}"),
+            PrintError::Print(error) => write!(f, "printing failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for PrintError {}
+
+const READER_STYLE: &str = include_str!("reader.css");
+const READER_SCRIPT: &str = include_str!("reader.js");
+const READER_TEARDOWN_SCRIPT: &str = "document.documentElement.classList.remove('owl-reader'); \
+     document.querySelectorAll('[data-owl-reader]').forEach((node) => \
+     node.removeAttribute('data-owl-reader'));";
+
+/// Inverts the page and re-inverts media so photos keep their colors.
+const FORCE_DARK_STYLE: &str = "html { filter: invert(1) hue-rotate(180deg) !important; \
+     background: #fff !important; } \
+     img, video, picture, canvas, iframe, embed, object, [style*='background-image'] \
+     { filter: invert(1) hue-rotate(180deg) !important; }";
+
+/// Replaces page colors with white text on black, yellow links and a
+/// visible focus ring.
+const HIGH_CONTRAST_STYLE: &str = "*, *::before, *::after { background-color: #000 !important; \
+     color: #fff !important; border-color: #fff !important; text-shadow: none !important; \
+     box-shadow: none !important; } \
+     a, a * { color: #ff0 !important; text-decoration: underline !important; } \
+     a:visited, a:visited * { color: #0ff !important; } \
+     :focus { outline: 2px solid #ff0 !important; outline-offset: 2px !important; }";
+
+/// Makes the page report itself hidden, for views WebKit still considers
+/// visible; see [`WebKitEngine::hide_view`].
+const VISIBILITY_HIDDEN_SCRIPT: &str = "Object.defineProperty(document, 'visibilityState', \
+     { configurable: true, get: () => 'hidden' }); \
+     Object.defineProperty(document, 'hidden', { configurable: true, get: () => true }); \
+     document.dispatchEvent(new Event('visibilitychange'));";
+/// Drops the overrides of [`VISIBILITY_HIDDEN_SCRIPT`], restoring WebKit's own
+/// values.
+const VISIBILITY_RESTORE_SCRIPT: &str = "delete document.visibilityState; \
+     delete document.hidden; \
+     document.dispatchEvent(new Event('visibilitychange'));";
+/// Fires the Page Lifecycle `freeze` event; see [`WebKitEngine::freeze_view`].
+const PAGE_FREEZE_SCRIPT: &str = "document.dispatchEvent(new Event('freeze'));";
+/// Fires the Page Lifecycle `resume` event that ends a `freeze`.
+const PAGE_RESUME_SCRIPT: &str = "document.dispatchEvent(new Event('resume'));";
+
+/// Script world used for [`ScriptWorld::Isolated`] user scripts.
+const ISOLATED_WORLD: &str = "owl-user-scripts";
+
+/// A script added with [`WebKitEngine::add_user_script`], for one tab or,
+/// with `tab` unset, for every view.
+#[derive(Debug)]
+struct InstalledScript {
+    tab: Option<TabId>,
+    script: webkit6::UserScript,
+}
+
+/// Stylesheet and extraction script injected for reader mode.
+#[derive(Debug)]
+struct ReaderContent {
+    style: webkit6::UserStyleSheet,
+    script: webkit6::UserScript,
+}
+
+impl ReaderContent {
+    fn new() -> Self {
+        Self {
+            style: webkit6::UserStyleSheet::new(
+                READER_STYLE,
+                webkit6::UserContentInjectedFrames::TopFrame,
+                webkit6::UserStyleLevel::User,
+                &[],
+                &[],
+            ),
+            script: webkit6::UserScript::new(
+                READER_SCRIPT,
+                webkit6::UserContentInjectedFrames::TopFrame,
+                webkit6::UserScriptInjectionTime::End,
+                &[],
+                &[],
+            ),
+        }
+    }
+}
+
+/// Web process memory limit, in MiB, for views created with each budget tier.
+fn tier_memory_limit_mib(tier: BudgetTier) -> u32 {
+    match tier {
+        BudgetTier::Foreground => 4096,
+        BudgetTier::VisibleBackground => 1536,
+        BudgetTier::IdleBackground => 512,
+    }
+}
+
+/// Identifier of the content filter in the filter store and on user content
+/// managers.
+const CONTENT_FILTER_ID: &str = "owl-content-filter";
+
+/// WebKitGTK-backed engine controller.
+#[derive(Debug, Default)]
+pub struct WebKitEngine {
+    views: RefCell<HashMap<TabId, webkit6::WebView>>,
+    finds: RefCell<HashMap<TabId, FindSession>>,
+    content_filter: RefCell<Option<webkit6::UserContentFilter>>,
+    content_filter_disabled: Cell<bool>,
+    reader: OnceCell<ReaderContent>,
+    reader_tabs: RefCell<HashSet<TabId>>,
+    force_dark: OnceCell<webkit6::UserStyleSheet>,
+    force_dark_tabs: RefCell<HashSet<TabId>>,
+    high_contrast: OnceCell<webkit6::UserStyleSheet>,
+    high_contrast_enabled: Cell<bool>,
+    /// Default and minimum font size in pixels, once set.
+    font_sizes: Cell<Option<(u32, u32)>>,
+    user_scripts: RefCell<HashMap<UserScriptId, InstalledScript>>,
+    next_user_script: Cell<u64>,
+    /// Tabs whose scripts are stopped by `pause_scripts`.
+    paused_scripts: RefCell<HashSet<TabId>>,
+    /// Tabs whose page was told it is hidden by script; see `hide_view`.
+    visibility_shim_tabs: RefCell<HashSet<TabId>>,
+    /// Tabs whose page was sent the `freeze` event; see `freeze_view`.
+    frozen_tabs: RefCell<HashSet<TabId>>,
+    /// Proxy applied to network sessions, once set.
+    proxy: RefCell<Option<ProxyConfig>>,
+    /// Sessions of private views, which need the proxy applied separately.
+    ephemeral_sessions: RefCell<Vec<glib::WeakRef<webkit6::NetworkSession>>>,
+    /// Hosts prefetched recently, oldest first.
+    prefetches: RefCell<VecDeque<(String, Instant)>>,
+    capabilities: OnceCell<EngineCapabilities>,
+    /// Web contexts with memory limits, created on first use per tier.
+    tier_contexts: RefCell<Vec<(BudgetTier, webkit6::WebContext)>>,
+}
+
+impl WebKitEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn settings() -> webkit6::Settings {
+        webkit6::Settings::builder()
+            .enable_javascript(true)
+            .build()
+    }
+
+    /// Creates a view whose web processes are held to the memory limit for
+    /// `budget`'s tier.
+    ///
+    /// Limits are set per `WebContext`, so each tier gets its own context
+    /// shared by all views created for it. Views in different contexts share
+    /// no web processes or memory caches, which costs memory of its own, and
+    /// a view keeps its limit when its budget changes later. Without the
+    /// `memory_limits` capability this is the same as `create_view`.
+    pub fn create_view_for_budget(&self, budget: ExecutionBudget) -> webkit6::WebView {
+        if !self.capabilities().memory_limits {
+            return self.create_view();
+        }
+        let context = self.tier_context(budget.tier);
+        webkit6::WebView::builder()
+            .settings(&Self::settings())
+            .web_context(&context)
+            .build()
+    }
+
+    fn tier_context(&self, tier: BudgetTier) -> webkit6::WebContext {
+        let mut contexts = self.tier_contexts.borrow_mut();
+        if let Some((_, context)) = contexts.iter().find(|(existing, _)| *existing == tier) {
+            return context.clone();
+        }
+        let mut settings = webkit6::MemoryPressureSettings::new();
+        settings.set_memory_limit(tier_memory_limit_mib(tier));
+        let context = webkit6::WebContext::builder()
+            .memory_pressure_settings(&settings)
+            .build();
+        contexts.push((tier, context.clone()));
+        context
+    }
+
+    /// Creates a view backed by its own ephemeral network session.
+    ///
+    /// Cookies, cache and site data live only in memory and are discarded
+    /// once the last reference to the view (and so its session) is dropped.
+    pub fn create_view_ephemeral(&self) -> webkit6::WebView {
+        let session = webkit6::NetworkSession::new_ephemeral();
+        if let Some(proxy) = &*self.proxy.borrow() {
+            if self.capabilities().proxy_settings {
+                apply_proxy(&session, proxy);
+            }
+        }
+        let mut sessions = self.ephemeral_sessions.borrow_mut();
+        sessions.retain(|session| session.upgrade().is_some());
+        sessions.push(session.downgrade());
+        drop(sessions);
+        webkit6::WebView::builder()
+            .settings(&Self::settings())
+            .network_session(&session)
+            .build()
+    }
+
+    /// Routes requests of the default network session and of every private
+    /// session, live or created later, through `proxy`. Returns `false`,
+    /// leaving sessions alone, without the `proxy_settings` capability.
+    ///
+    /// WebKitGTK has no DNS configuration of its own: name resolution follows
+    /// the system resolver, so DNS-over-HTTPS needs a resolver configured on
+    /// the system, or a SOCKS proxy that resolves names itself.
+    pub fn set_proxy(&self, proxy: ProxyConfig) -> bool {
+        if !self.capabilities().proxy_settings {
+            log::warn!("proxy settings are not supported by this WebKitGTK build");
+            return false;
+        }
+        if self.proxy.borrow().as_ref() == Some(&proxy) {
+            return true;
+        }
+        if let Some(session) = webkit6::NetworkSession::default() {
+            apply_proxy(&session, &proxy);
+        }
+        let mut sessions = self.ephemeral_sessions.borrow_mut();
+        sessions.retain(|session| match session.upgrade() {
+            Some(session) => {
+                apply_proxy(&session, &proxy);
+                true
+            }
+            None => false,
+        });
+        drop(sessions);
+        *self.proxy.borrow_mut() = Some(proxy);
+        true
+    }
+
+    /// Compiles the WebKit content-blocker rule list at `path` and applies it
+    /// to every registered view, now and as views are registered later.
+    ///
+    /// Compilation is asynchronous; a rule list that fails to compile is
+    /// logged and views stay unfiltered.
+    #[cfg(feature = "content-filter")]
+    pub fn load_content_filter(self: &Rc<Self>, path: &Path) {
+        let storage = glib::user_cache_dir()
+            .join("owl-browser")
+            .join("content-filters");
+        let store = webkit6::UserContentFilterStore::new(&storage.to_string_lossy());
+        let file = gtk::gio::File::for_path(path);
+        let engine = Rc::downgrade(self);
+        let path = path.to_path_buf();
+        store.save_from_file(
+            CONTENT_FILTER_ID,
+            &file,
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                let filter = match result {
+                    Ok(filter) => filter,
+                    Err(error) => {
+                        log::error!(
+                            "failed to compile content filter {}: {error}",
+                            path.display()
+                        );
+                        return;
+                    }
+                };
+                if let Some(engine) = engine.upgrade() {
+                    *engine.content_filter.borrow_mut() = Some(filter);
+                    engine.apply_content_filter_to_views();
+                }
+            },
+        );
+    }
+
+    /// Content filtering is compiled out; logs and leaves views unfiltered.
+    #[cfg(not(feature = "content-filter"))]
+    pub fn load_content_filter(self: &Rc<Self>, path: &Path) {
+        log::warn!(
+            "content filtering is not available in this build; ignoring {}",
+            path.display()
+        );
+    }
+
+    /// Turns the loaded content filter on or off for every view.
+    pub fn set_content_filter_enabled(&self, enabled: bool) {
+        if self.content_filter_disabled.replace(!enabled) != !enabled {
+            self.apply_content_filter_to_views();
+        }
+    }
+
+    /// Whether a content filter is loaded and enabled.
+    pub fn content_filter_active(&self) -> bool {
+        !self.content_filter_disabled.get() && self.content_filter.borrow().is_some()
+    }
+
+    fn apply_content_filter_to_views(&self) {
+        for view in self.views.borrow().values() {
+            self.apply_content_filter(view);
+        }
+    }
+
+    fn apply_content_filter(&self, view: &webkit6::WebView) {
+        let Some(manager) = view.user_content_manager() else {
+            return;
+        };
+        match &*self.content_filter.borrow() {
+            Some(filter) if !self.content_filter_disabled.get() => manager.add_filter(filter),
+            _ => manager.remove_filter_by_id(CONTENT_FILTER_ID),
+        }
+    }
+
+    fn with_view<F: FnOnce(&webkit6::WebView)>(&self, tab: TabId, f: F) {
+        if let Some(view) = self.views.borrow().get(&tab) {
+            f(view);
+        }
+    }
+
+    /// Hides the view so WebKit reports the page hidden and throttles its
+    /// timers.
+    ///
+    /// WebKitGTK derives Page Visibility from whether the view is mapped and
+    /// from the toplevel's suspended state, not from the `visible` property
+    /// itself. A hidden widget is never mapped in GTK 4, and a `GtkStack`
+    /// child that is not the visible one is not mapped either, because the
+    /// stack clears its child-visible flag. So hiding is enough whenever the
+    /// view sits in an ordinary GTK container. If the view is still mapped
+    /// afterwards, the page is at least told it is hidden by script, so pages
+    /// that honor `visibilitychange` back off; WebKit's own timer throttling
+    /// does not apply in that case.
+    fn hide_view(&self, tab: TabId, view: &webkit6::WebView) {
+        view.set_visible(false);
+        if !view.is_mapped() {
+            self.restore_visibility(tab, view);
+            return;
+        }
+        if self.visibility_shim_tabs.borrow_mut().insert(tab) {
+            log::warn!("view for tab {tab} stays mapped while hidden; faking page visibility");
+            Self::run_script(view, VISIBILITY_HIDDEN_SCRIPT);
+        }
+    }
+
+    fn show_view(&self, tab: TabId, view: &webkit6::WebView) {
+        view.set_visible(true);
+        self.restore_visibility(tab, view);
+    }
+
+    fn restore_visibility(&self, tab: TabId, view: &webkit6::WebView) {
+        if self.visibility_shim_tabs.borrow_mut().remove(&tab) {
+            Self::run_script(view, VISIBILITY_RESTORE_SCRIPT);
+        }
+    }
+
+    /// Tells the page it is frozen.
+    ///
+    /// Hiding the view is the strongest timer throttling WebKitGTK offers,
+    /// so without a resumable script pause a frozen tab would run just like
+    /// a background one. Pages following the Page Lifecycle API stop their
+    /// own timers and polling on `freeze`, which is the difference.
+    fn freeze_view(&self, tab: TabId, view: &webkit6::WebView) {
+        if self.frozen_tabs.borrow_mut().insert(tab) {
+            Self::run_script(view, PAGE_FREEZE_SCRIPT);
+        }
+    }
+
+    fn thaw_view(&self, tab: TabId, view: &webkit6::WebView) {
+        if self.frozen_tabs.borrow_mut().remove(&tab) {
+            Self::run_script(view, PAGE_RESUME_SCRIPT);
+        }
+    }
+
+    /// Stops the tab's JavaScript, returning how, or `None` for unknown tabs.
+    ///
+    /// A real pause would send the inspector protocol's `Debugger.pause`, but
+    /// WebKitGTK only speaks that protocol to a remote inspector enabled with
+    /// `WEBKIT_INSPECTOR_SERVER` before the web process starts, and
+    /// `WebKitWebInspector` can only show or close the inspector. So this
+    /// always disables JavaScript for now; `capabilities().script_pause` turns
+    /// true once a pause is available. The governor reads it through
+    /// `supports_script_pause`, and keeps suspending tabs under moderate
+    /// memory pressure until then.
+    pub fn pause_scripts(&self, tab: TabId) -> Option<ScriptPause> {
+        let view = self.views.borrow().get(&tab).cloned()?;
+        Some(self.pause_view_scripts(tab, &view))
+    }
+
+    /// Lets the tab's JavaScript run again. Returns whether it was stopped.
+    pub fn resume_scripts(&self, tab: TabId) -> bool {
+        let Some(view) = self.views.borrow().get(&tab).cloned() else {
+            return false;
+        };
+        self.resume_view_scripts(tab, &view)
+    }
+
+    fn pause_view_scripts(&self, tab: TabId, view: &webkit6::WebView) -> ScriptPause {
+        Self::set_javascript_enabled(view, false);
+        self.paused_scripts.borrow_mut().insert(tab);
+        ScriptPause::Disabled
+    }
+
+    fn resume_view_scripts(&self, tab: TabId, view: &webkit6::WebView) -> bool {
+        // Always re-enable: the view may have been handed over from a tab
+        // that was suspended.
+        Self::set_javascript_enabled(view, true);
+        self.paused_scripts.borrow_mut().remove(&tab)
+    }
+
+    fn set_javascript_enabled(view: &webkit6::WebView, enabled: bool) {
+        if let Some(settings) = webkit6::prelude::WebViewExt::settings(view) {
+            settings.set_enable_javascript(enabled);
+        }
+    }
+
+    /// Mutes or unmutes all audio played by the tab's view.
+    pub fn set_muted(&self, tab: TabId, muted: bool) {
+        self.with_view(tab, |view| view.set_is_muted(muted));
+    }
+
+    /// Overrides the User-Agent sent by the tab's view; `None` restores the
+    /// engine default.
+    pub fn set_user_agent(&self, tab: TabId, user_agent: Option<&str>) {
+        self.with_view(tab, |view| {
+            if let Some(settings) = webkit6::prelude::WebViewExt::settings(view) {
+                settings.set_user_agent(user_agent);
+            }
+        });
+    }
+
+    /// Turns reader mode on or off for the tab's view.
+    ///
+    /// While on, the reader stylesheet and script stay installed on the
+    /// view's user content manager so they re-apply after each navigation.
+    /// Turning it off removes both and restores the current page.
+    pub fn set_reader_mode(&self, tab: TabId, enabled: bool) {
+        if self.reader_tabs.borrow().contains(&tab) == enabled {
+            return;
+        }
+        self.with_view(tab, |view| {
+            let Some(manager) = view.user_content_manager() else {
+                return;
+            };
+            let reader = self.reader.get_or_init(ReaderContent::new);
+            if enabled {
+                manager.add_style_sheet(&reader.style);
+                manager.add_script(&reader.script);
+                Self::run_script(view, READER_SCRIPT);
+                self.reader_tabs.borrow_mut().insert(tab);
+            } else {
+                manager.remove_style_sheet(&reader.style);
+                manager.remove_script(&reader.script);
+                Self::run_script(view, READER_TEARDOWN_SCRIPT);
+                self.reader_tabs.borrow_mut().remove(&tab);
+            }
+        });
+    }
+
+    /// Forces a dark rendering onto the tab's view, or removes it again.
+    ///
+    /// The stylesheet stays installed while on, so it also applies to pages
+    /// the view navigates to.
+    pub fn set_force_dark(&self, tab: TabId, enabled: bool) {
+        if self.force_dark_tabs.borrow().contains(&tab) == enabled {
+            return;
+        }
+        self.with_view(tab, |view| {
+            let Some(manager) = view.user_content_manager() else {
+                return;
+            };
+            let style = self.force_dark.get_or_init(|| {
+                webkit6::UserStyleSheet::new(
+                    FORCE_DARK_STYLE,
+                    webkit6::UserContentInjectedFrames::TopFrame,
+                    webkit6::UserStyleLevel::User,
+                    &[],
+                    &[],
+                )
+            });
+            if enabled {
+                manager.add_style_sheet(style);
+                self.force_dark_tabs.borrow_mut().insert(tab);
+            } else {
+                manager.remove_style_sheet(style);
+                self.force_dark_tabs.borrow_mut().remove(&tab);
+            }
+        });
+    }
+
+    /// Injects `source` into the top frame of `tab`'s view, or of every view
+    /// when `tab` is `None`, and returns an id for
+    /// [`WebKitEngine::remove_user_script`].
+    ///
+    /// The script is installed on the view's user content manager, so it
+    /// runs again on every page the view loads, starting with the next load;
+    /// the current page is left alone. A tab's scripts follow it across
+    /// re-registration, so they stay until removed, including after the tab
+    /// closes.
+    pub fn add_user_script(
+        &self,
+        tab: Option<TabId>,
+        source: &str,
+        injection_time: ScriptInjectionTime,
+        world: ScriptWorld,
+    ) -> UserScriptId {
+        let injection_time = match injection_time {
+            ScriptInjectionTime::DocumentStart => webkit6::UserScriptInjectionTime::Start,
+            ScriptInjectionTime::DocumentEnd => webkit6::UserScriptInjectionTime::End,
+        };
+        let frames = webkit6::UserContentInjectedFrames::TopFrame;
+        let script = match world {
+            ScriptWorld::Main => webkit6::UserScript::new(source, frames, injection_time, &[], &[]),
+            ScriptWorld::Isolated => webkit6::UserScript::for_world(
+                source,
+                frames,
+                injection_time,
+                ISOLATED_WORLD,
+                &[],
+                &[],
+            ),
+        };
+
+        let id = UserScriptId(self.next_user_script.get());
+        self.next_user_script.set(id.0 + 1);
+        for view in self.distinct_views(tab) {
+            if let Some(manager) = view.user_content_manager() {
+                manager.add_script(&script);
+            }
+        }
+        self.user_scripts
+            .borrow_mut()
+            .insert(id, InstalledScript { tab, script });
+        id
+    }
+
+    /// Removes a script added with [`WebKitEngine::add_user_script`] from
+    /// every view it was injected into. Pages already loaded keep whatever
+    /// it did. Returns whether the id was known.
+    pub fn remove_user_script(&self, id: UserScriptId) -> bool {
+        let Some(installed) = self.user_scripts.borrow_mut().remove(&id) else {
+            return false;
+        };
+        for view in self.distinct_views(installed.tab) {
+            if let Some(manager) = view.user_content_manager() {
+                manager.remove_script(&installed.script);
+            }
+        }
+        true
+    }
+
+    /// Distinct registered views of `tab`, or of every tab for `None`.
+    fn distinct_views(&self, tab: Option<TabId>) -> Vec<webkit6::WebView> {
+        let views = self.views.borrow();
+        let mut distinct: Vec<webkit6::WebView> = Vec::new();
+        for (id, view) in views.iter() {
+            if tab.is_none_or(|tab| tab == *id) && !distinct.contains(view) {
+                distinct.push(view.clone());
+            }
+        }
+        distinct
+    }
+
+    /// Installs or removes the user scripts of `tab` on its view. Scripts
+    /// for every view are only touched when no other tab shares the view,
+    /// so they are installed once per view.
+    fn sync_user_scripts(&self, tab: TabId, view: &webkit6::WebView, install: bool) {
+        let Some(manager) = view.user_content_manager() else {
+            return;
+        };
+        let shared = self.shares_view(tab, view);
+        let scripts = self.user_scripts.borrow();
+        let mut ids: Vec<&UserScriptId> = scripts.keys().collect();
+        // Scripts of the same injection time run in the order they were added.
+        ids.sort_unstable();
+        for id in ids {
+            let installed = &scripts[id];
+            let applies = match installed.tab {
+                Some(owner) => owner == tab,
+                None => !shared,
+            };
+            if !applies {
+                continue;
+            }
+            if install {
+                manager.add_script(&installed.script);
+            } else {
+                manager.remove_script(&installed.script);
+            }
+        }
+    }
+
+    /// Sets the tab's zoom, as a factor of the page's normal size.
+    pub fn set_zoom_level(&self, tab: TabId, level: f64) {
+        self.with_view(tab, |view| {
+            if view.zoom_level() != level {
+                view.set_zoom_level(level);
+            }
+        });
+    }
+
+    /// Makes zooming scale only the tab's text, or the whole page again.
+    ///
+    /// The current zoom level carries over: WebKit moves it from the page
+    /// factor to the text factor or back when the setting changes, and it is
+    /// set again here in case the view reports a different level afterwards.
+    pub fn set_zoom_text_only(&self, tab: TabId, text_only: bool) {
+        self.with_view(tab, |view| {
+            let Some(settings) = webkit6::prelude::WebViewExt::settings(view) else {
+                return;
+            };
+            if settings.is_zoom_text_only() == text_only {
+                return;
+            }
+            let level = view.zoom_level();
+            settings.set_zoom_text_only(text_only);
+            if view.zoom_level() != level {
+                view.set_zoom_level(level);
+            }
+        });
+    }
+
+    /// Whether a tab other than `tab` is registered with `view`.
+    fn shares_view(&self, tab: TabId, view: &webkit6::WebView) -> bool {
+        self.views
+            .borrow()
+            .iter()
+            .any(|(id, other)| *id != tab && other == view)
+    }
+
+    /// Sets the font size, in pixels, for text without an explicit size,
+    /// and the smallest size pages may use (0 for none), on every view now
+    /// and as views are registered later.
+    pub fn set_font_sizes(&self, default: u32, minimum: u32) {
+        self.font_sizes.set(Some((default, minimum)));
+        for view in self.distinct_views(None) {
+            self.apply_font_sizes(&view);
+        }
+    }
+
+    fn apply_font_sizes(&self, view: &webkit6::WebView) {
+        let Some((default, minimum)) = self.font_sizes.get() else {
+            return;
+        };
+        if let Some(settings) = webkit6::prelude::WebViewExt::settings(view) {
+            settings.set_default_font_size(default);
+            settings.set_minimum_font_size(minimum);
+        }
+    }
+
+    /// Turns the high-contrast stylesheet on or off for every view, now and
+    /// as views are registered later. Like force-dark, the stylesheet stays
+    /// installed across navigations.
+    pub fn set_high_contrast(&self, enabled: bool) {
+        if self.high_contrast_enabled.replace(enabled) == enabled {
+            return;
+        }
+        for view in self.distinct_views(None) {
+            self.install_high_contrast(&view, enabled);
+        }
+    }
+
+    fn install_high_contrast(&self, view: &webkit6::WebView, install: bool) {
+        let Some(manager) = view.user_content_manager() else {
+            return;
+        };
+        let style = self.high_contrast.get_or_init(|| {
+            webkit6::UserStyleSheet::new(
+                HIGH_CONTRAST_STYLE,
+                webkit6::UserContentInjectedFrames::AllFrames,
+                webkit6::UserStyleLevel::User,
+                &[],
+                &[],
+            )
+        });
+        if install {
+            manager.add_style_sheet(style);
+        } else {
+            manager.remove_style_sheet(style);
+        }
+    }
+
+    /// Returns whether reader mode is on for the tab.
+    pub fn is_reader_mode(&self, tab: TabId) -> bool {
+        self.reader_tabs.borrow().contains(&tab)
+    }
+
+    fn run_script(view: &webkit6::WebView, script: &str) {
+        view.evaluate_javascript(
+            script,
+            None,
+            None,
+            None::<&gtk::gio::Cancellable>,
+            |result| {
+                if let Err(error) = result {
+                    log::warn!("failed to run injected script: {error}");
+                }
+            },
+        );
+    }
+
+    /// Returns the tab's back/forward history, oldest first.
+    ///
+    /// At most [`MAX_HISTORY_ENTRIES`] entries are returned on each side of
+    /// the current one. Unknown tabs and views that have not loaded anything
+    /// yet return an empty list.
+    pub fn back_forward_list(&self, tab: TabId) -> Vec<NavEntry> {
+        let Some(list) = self
+            .views
+            .borrow()
+            .get(&tab)
+            .and_then(|view| view.back_forward_list())
+        else {
+            return Vec::new();
+        };
+        (-MAX_HISTORY_ENTRIES..=MAX_HISTORY_ENTRIES)
+            .filter_map(|offset| {
+                let item = list.nth_item(offset)?;
+                let uri = item.uri().map(|uri| uri.to_string()).unwrap_or_default();
+                let title = item
+                    .title()
+                    .map(|title| title.to_string())
+                    .filter(|title| !title.is_empty())
+                    .unwrap_or_else(|| uri.clone());
+                Some(NavEntry {
+                    title,
+                    uri,
+                    offset,
+                    is_current: offset == 0,
+                })
+            })
+            .collect()
+    }
+
+    /// Navigates the tab to the history entry `offset` steps from the
+    /// current one. Returns false when there is no such entry.
+    pub fn go_to_offset(&self, tab: TabId, offset: i32) -> bool {
+        // Navigating emits view signals, so do not hold the borrow.
+        let Some(view) = self.views.borrow().get(&tab).cloned() else {
+            return false;
+        };
+        let Some(item) = view
+            .back_forward_list()
+            .and_then(|list| list.nth_item(offset))
+        else {
+            return false;
+        };
+        if offset != 0 {
+            view.go_to_back_forward_list_item(&item);
+        }
+        true
+    }
+
+    /// Resolves the host of `url` ahead of a likely navigation from `tab`.
+    ///
+    /// Only http(s) URLs on a different origin than the tab's current page
+    /// are prefetched, each host at most once per [`PREFETCH_WINDOW`] and no
+    /// more than [`MAX_PREFETCHES`] hosts per window. Views without a network
+    /// session are left alone. Returns whether a prefetch was issued.
+    pub fn prefetch(&self, tab: TabId, url: &str) -> bool {
+        let Some(target) = http_origin(url) else {
+            return false;
+        };
+        let Some(view) = self.views.borrow().get(&tab).cloned() else {
+            return false;
+        };
+        let current = view.uri().and_then(|uri| http_origin(&uri));
+        if current.as_ref() == Some(&target) {
+            return false;
+        }
+        let Some(session) = view.network_session() else {
+            return false;
+        };
+
+        let (_, host, _) = target;
+        let now = Instant::now();
+        let mut prefetches = self.prefetches.borrow_mut();
+        while prefetches
+            .front()
+            .is_some_and(|(_, at)| now.duration_since(*at) >= PREFETCH_WINDOW)
+        {
+            prefetches.pop_front();
+        }
+        if prefetches.len() >= MAX_PREFETCHES
+            || prefetches.iter().any(|(prefetched, _)| *prefetched == host)
+        {
+            return false;
+        }
+        session.prefetch_dns(&host);
+        prefetches.push_back((host, now));
+        true
+    }
+
+    /// Returns whether the tab's view is muted, or `None` for unknown tabs.
+    pub fn is_muted(&self, tab: TabId) -> Option<bool> {
+        self.views.borrow().get(&tab).map(|view| view.is_muted())
+    }
+
+    /// Starts a find-in-page search, replacing any search already running.
+    ///
+    /// `callback` receives match updates until [`WebKitEngine::find_finish`].
+    /// An empty query clears highlights and reports zero matches.
+    pub fn find_in_page(
+        &self,
+        tab: TabId,
+        query: &str,
+        options: FindOptions,
+        callback: FindCallback,
+    ) {
+        self.find_finish(tab);
+        if query.is_empty() {
+            callback(FindMatches::default());
+            return;
+        }
+
+        let controller = self
+            .views
+            .borrow()
+            .get(&tab)
+            .and_then(|view| view.find_controller());
+        let Some(controller) = controller else {
+            callback(FindMatches::default());
+            return;
+        };
+
+        let matches = Rc::new(Cell::new(FindMatches::default()));
+        let mut handlers = Vec::with_capacity(2);
+
+        let matches_for_count = Rc::clone(&matches);
+        let callback_for_count = Rc::clone(&callback);
+        handlers.push(controller.connect_counted_matches(move |_, total| {
+            let current = if total == 0 {
+                0
+            } else {
+                matches_for_count.get().current.clamp(1, total)
+            };
+            let update = FindMatches { current, total };
+            matches_for_count.set(update);
+            callback_for_count(update);
+        }));
+
+        let matches_for_failure = Rc::clone(&matches);
+        let callback_for_failure = Rc::clone(&callback);
+        handlers.push(controller.connect_failed_to_find_text(move |_| {
+            matches_for_failure.set(FindMatches::default());
+            callback_for_failure(FindMatches::default());
+        }));
+
+        let flags = options.to_webkit();
+        controller.count_matches(query, flags, MAX_FIND_MATCHES);
+        controller.search(query, flags, MAX_FIND_MATCHES);
+
+        self.finds.borrow_mut().insert(
+            tab,
+            FindSession {
+                controller,
+                matches,
+                callback,
+                handlers,
+            },
+        );
+    }
+
+    /// Moves to the next match of the running search.
+    pub fn find_next(&self, tab: TabId) {
+        self.step_find(
+            tab,
+            |matches| matches.current % matches.total + 1,
+            |controller| controller.search_next(),
+        );
+    }
+
+    /// Moves to the previous match of the running search.
+    pub fn find_previous(&self, tab: TabId) {
+        self.step_find(
+            tab,
+            |matches| {
+                if matches.current <= 1 {
+                    matches.total
+                } else {
+                    matches.current - 1
+                }
+            },
+            |controller| controller.search_previous(),
+        );
+    }
+
+    /// Ends the running search for the tab and clears highlights.
+    pub fn find_finish(&self, tab: TabId) {
+        let Some(session) = self.finds.borrow_mut().remove(&tab) else {
+            return;
+        };
+        for handler in session.handlers {
+            session.controller.disconnect(handler);
+        }
+        session.controller.search_finish();
+    }
+
+    /// Captures the tab's page as PNG bytes.
+    ///
+    /// The callback always runs, either with the encoded image or with the
+    /// reason capture was not possible.
+    pub fn capture_snapshot<F>(&self, tab: TabId, region: SnapshotRegion, callback: F)
+    where
+        F: FnOnce(Result<Vec<u8>, SnapshotError>) + 'static,
+    {
+        let view = self.views.borrow().get(&tab).cloned();
+        let Some(view) = view else {
+            callback(Err(SnapshotError::UnknownTab));
+            return;
+        };
+        if !view.is_realized() {
+            callback(Err(SnapshotError::NotRealized));
+            return;
+        }
+
+        let region = match region {
+            SnapshotRegion::Viewport => webkit6::SnapshotRegion::Visible,
+            SnapshotRegion::FullDocument => webkit6::SnapshotRegion::FullDocument,
+        };
+        webkit6::prelude::WebViewExt::snapshot(
+            &view,
+            region,
+            webkit6::SnapshotOptions::NONE,
+            None::<&gtk::gio::Cancellable>,
+            move |result| {
+                let png = result
+                    .map(|texture| texture.save_to_png_bytes().to_vec())
+                    .map_err(SnapshotError::Capture);
+                callback(png);
+            },
+        );
+    }
+
+    /// Prints the tab's page to a PDF file at `path`, reporting the outcome
+    /// to `callback` once WebKit has finished.
+    ///
+    /// Pages that are still loading are rejected rather than printed half
+    /// rendered. The destination is created up front so an unwritable
+    /// directory fails immediately; it is removed again if printing fails.
+    pub fn print_to_pdf<F>(&self, tab: TabId, path: &Path, callback: F)
+    where
+        F: FnOnce(Result<(), PrintError>) + 'static,
+    {
+        let view = self.views.borrow().get(&tab).cloned();
+        let Some(view) = view else {
+            callback(Err(PrintError::UnknownTab));
+            return;
+        };
+        if view.is_loading() {
+            callback(Err(PrintError::Loading));
+            return;
+        }
+        if !path.is_absolute() {
+            callback(Err(PrintError::Destination(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "path must be absolute",
+            ))));
+            return;
+        }
+        if let Err(error) = std::fs::File::create(path) {
+            callback(Err(PrintError::Destination(error)));
+            return;
+        }
+        let uri = match glib::filename_to_uri(path, None) {
+            Ok(uri) => uri,
+            Err(error) => {
+                let _ = std::fs::remove_file(path);
+                callback(Err(PrintError::Print(error)));
+                return;
+            }
+        };
+
+        let settings = gtk::PrintSettings::new();
+        settings.set_printer("Print to File");
+        settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+        settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(&uri));
+        let operation = webkit6::PrintOperation::builder()
+            .web_view(&view)
+            .print_settings(&settings)
+            .build();
+
+        // `finished` also follows `failed`, so remember the error and report
+        // once from `finished`.
+        let failure: Rc<RefCell<Option<glib::Error>>> = Rc::new(RefCell::new(None));
+        let failure_for_failed = Rc::clone(&failure);
+        operation.connect_failed(move |_, error| {
+            *failure_for_failed.borrow_mut() = Some(error.clone());
+        });
+
+        let path = path.to_path_buf();
+        let callback = RefCell::new(Some(callback));
+        // The operation must outlive the print job; the handler holds the
+        // last reference until it runs.
+        let keep_alive = RefCell::new(Some(operation.clone()));
+        operation.connect_finished(move |_| {
+            keep_alive.borrow_mut().take();
+            let Some(callback) = callback.borrow_mut().take() else {
+                return;
+            };
+            match failure.borrow_mut().take() {
+                Some(error) => {
+                    let _ = std::fs::remove_file(&path);
+                    callback(Err(PrintError::Print(error)));
+                }
+                None => callback(Ok(())),
+            }
+        });
+        operation.print();
+    }
+
+    fn step_find(
+        &self,
+        tab: TabId,
+        advance: impl Fn(FindMatches) -> u32,
+        search: impl Fn(&webkit6::FindController),
+    ) {
+        let finds = self.finds.borrow();
+        let Some(session) = finds.get(&tab) else {
+            return;
+        };
+
+        let mut matches = session.matches.get();
+        if matches.total > 0 {
+            matches.current = advance(matches);
+            session.matches.set(matches);
+            search(&session.controller);
+        }
+        (session.callback)(matches);
+    }
+}
+
+fn apply_proxy(session: &webkit6::NetworkSession, proxy: &ProxyConfig) {
+    match proxy {
+        ProxyConfig::System => {
+            session.set_proxy_settings(webkit6::NetworkProxyMode::Default, None);
+        }
+        ProxyConfig::Direct => {
+            session.set_proxy_settings(webkit6::NetworkProxyMode::NoProxy, None);
+        }
+        ProxyConfig::Manual(uri) => {
+            let mut settings = webkit6::NetworkProxySettings::new(Some(uri), &[]);
+            session.set_proxy_settings(webkit6::NetworkProxyMode::Custom, Some(&mut settings));
+        }
+    }
+}
+
+/// Splits an http(s) URL into its lowercased scheme, host and port; other
+/// URLs yield `None`.
+fn http_origin(url: &str) -> Option<(String, String, i32)> {
+    let uri = glib::Uri::parse(url, glib::UriFlags::NONE).ok()?;
+    let scheme = uri.scheme().to_ascii_lowercase();
+    if scheme != "http" && scheme != "https" {
+        return None;
+    }
+    let host = uri.host()?.to_ascii_lowercase();
+    if host.is_empty() {
+        return None;
+    }
+    Some((scheme, host, uri.port()))
+}
+
+impl EngineController for WebKitEngine {
+    type View = webkit6::WebView;
+
+    fn capabilities(&self) -> EngineCapabilities {
+        *self.capabilities.get_or_init(|| {
+            let capabilities = EngineCapabilities {
+                javascript_toggle: has_property::<webkit6::Settings>("enable-javascript"),
+                memory_limits: has_property::<webkit6::WebContext>("memory-pressure-settings"),
+                content_filters: cfg!(feature = "content-filter"),
+                find: has_property::<webkit6::FindController>("web-view"),
+                // See `pause_scripts`.
+                script_pause: false,
+                // Per-session proxies arrived with `WebKitNetworkSession` in
+                // WebKitGTK 2.40; older releases only had a proxy on the
+                // whole `WebKitWebContext`.
+                proxy_settings: (webkit6::major_version(), webkit6::minor_version()) >= (2, 40),
+            };
+            log::info!("engine capabilities: {capabilities:?}");
+            capabilities
+        })
+    }
+
+    fn create_view(&self) -> Self::View {
+        let settings = Self::settings();
+        webkit6::WebView::builder().settings(&settings).build()
+    }
+
+    fn load_uri(&self, view: &Self::View, uri: &str) {
+        view.load_uri(uri);
+    }
+
+    fn register_view(&self, tab: TabId, view: &Self::View) {
+        self.apply_content_filter(view);
+        let previous = self.views.borrow_mut().insert(tab, view.clone());
+        if let Some(previous) = previous {
+            self.sync_user_scripts(tab, &previous, false);
+            if self.high_contrast_enabled.get() && !self.shares_view(tab, &previous) {
+                self.install_high_contrast(&previous, false);
+            }
+        }
+        self.sync_user_scripts(tab, view, true);
+        self.apply_font_sizes(view);
+        if self.high_contrast_enabled.get() && !self.shares_view(tab, view) {
+            self.install_high_contrast(view, true);
+        }
+    }
+
+    fn unregister_view(&self, tab: TabId) {
+        self.find_finish(tab);
+        // Views can be shared between tabs, so leave no reader mode behind.
+        self.set_reader_mode(tab, false);
+        self.set_force_dark(tab, false);
+        self.with_view(tab, |view| {
+            self.thaw_view(tab, view);
+            self.restore_visibility(tab, view);
+            self.paused_scripts.borrow_mut().remove(&tab);
+        });
+        let view = self.views.borrow_mut().remove(&tab);
+        if let Some(view) = view {
+            self.sync_user_scripts(tab, &view, false);
+            if self.high_contrast_enabled.get() && !self.shares_view(tab, &view) {
+                self.install_high_contrast(&view, false);
+            }
+        }
+    }
+
+    fn apply_tab_state(&self, tab: TabId, state: TabState) {
+        self.with_view(tab, |view| match state {
+            TabState::Active => {
+                // Scripts must run again before the page hears it is resumed
+                // and the visibility shim is undone.
+                self.resume_view_scripts(tab, view);
+                self.thaw_view(tab, view);
+                self.show_view(tab, view);
+            }
+            TabState::Background => {
+                // WebKitGTK does not expose explicit timer-clamp controls. We rely on
+                // widget visibility to trigger Page Visibility throttling in the engine.
+                self.resume_view_scripts(tab, view);
+                self.thaw_view(tab, view);
+                self.hide_view(tab, view);
+            }
+            TabState::Frozen => {
+                // Freezing must never tear down timers or page state, so scripts
+                // are only paused when that pause is resumable. Otherwise they
+                // keep running under hidden-page throttling, and the page is
+                // asked to stop its own work.
+                if self.capabilities().script_pause {
+                    self.hide_view(tab, view);
+                    self.freeze_view(tab, view);
+                    self.pause_view_scripts(tab, view);
+                } else {
+                    self.resume_view_scripts(tab, view);
+                    self.hide_view(tab, view);
+                    self.freeze_view(tab, view);
+                }
+            }
+            TabState::Suspended => {
+                self.hide_view(tab, view);
+                self.pause_view_scripts(tab, view);
+            }
+        });
+    }
+
+    fn apply_execution_budget(&self, tab: TabId, _budget: ExecutionBudget) {
+        // TODO: Apply per-tab CPU and scheduling budgets when cgroup integration lands.
+        // Memory limits are fixed when the view is created; see
+        // `create_view_for_budget`.
+        self.with_view(tab, |_| {});
+    }
+
+    fn apply_execution_hints(&self, tab: TabId, _hints: ExecutionBudgetHints) {
+        // TODO: Map advisory hints to WebKit settings once supported.
+        self.with_view(tab, |_| {});
+    }
+}
+
+/// Returns whether the installed WebKitGTK build gives `T` the property `name`.
+fn has_property<T: StaticType>(name: &str) -> bool {
+    glib::object::ObjectClass::from_type(T::static_type())
+        .is_some_and(|class| class.has_property(name))
+}
+
+impl EngineScheduler for WebKitEngine {
+    fn apply_tab_state(&self, tab: TabId, state: TabState) {
+        <Self as EngineController>::apply_tab_state(self, tab, state);
+    }
+
+    fn apply_execution_budget(&self, tab: TabId, budget: ExecutionBudget) {
+        <Self as EngineController>::apply_execution_budget(self, tab, budget);
+    }
+
+    fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints) {
+        <Self as EngineController>::apply_execution_hints(self, tab, hints);
+    }
+
+    fn supports_script_pause(&self) -> bool {
+        self.capabilities().script_pause
+    }
+}
+
+impl EngineFeedbackProvider for WebKitEngine {
+    fn poll_execution_feedback(&self, _tab: TabId) -> EngineExecutionFeedback {
+        // WebKitGTK does not currently expose reliable per-tab signals for these fields.
+        // Return conservative defaults until the backend provides explicit counters.
+        EngineExecutionFeedback::default()
+    }
+}
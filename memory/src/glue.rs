@@ -0,0 +1,29 @@
+//! GLib main-loop glue between the pressure monitor and the governor.
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use scheduler::ExecutionGovernor;
+
+use crate::pressure::MemoryPressureReceiver;
+
+/// How often [`apply_to_governor`] drains the monitor by default.
+pub const DEFAULT_APPLY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Drains `receiver` every `interval` on the thread-default main context and
+/// applies the latest level to `governor`.
+///
+/// The source owns the receiver: removing it drops the receiver, which stops
+/// the monitor thread.
+pub fn apply_to_governor(
+    governor: Rc<ExecutionGovernor>,
+    receiver: MemoryPressureReceiver,
+    interval: Duration,
+) -> glib::SourceId {
+    glib::timeout_add_local(interval, move || {
+        if let Some(pressure) = receiver.drain_latest() {
+            governor.set_memory_pressure(pressure);
+        }
+        glib::ControlFlow::Continue
+    })
+}
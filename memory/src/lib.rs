@@ -1,5 +1,7 @@
 use tabs::TabId;
 
+#[cfg(feature = "glib")]
+pub mod glue;
 pub mod pressure;
 
 /// Snapshot of memory usage for a tab process.
@@ -1,9 +1,10 @@
 use scheduler::MemoryPressure;
+use std::cell::RefCell;
 use std::fs::File;
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{self, Receiver};
-use std::thread;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
 
 /// Source abstraction for memory pressure.
@@ -70,9 +71,17 @@ pub enum MemoryPressureSourceKind {
 /// Background monitor that emits memory pressure updates via a channel.
 ///
 /// The worker performs blocking I/O off the UI thread. The receiver can be
-/// polled non-blockingly from the main loop.
+/// polled non-blockingly from the main loop. Dropping the receiver stops the
+/// worker, as does [`MemoryPressureReceiver::stop`].
 pub struct MemoryPressureReceiver {
     receiver: Receiver<MemoryPressure>,
+    worker: RefCell<Option<MonitorWorker>>,
+}
+
+struct MonitorWorker {
+    /// Wakes the worker out of its sleep; closing it also stops the worker.
+    stop: Sender<()>,
+    handle: JoinHandle<()>,
 }
 
 impl MemoryPressureReceiver {
@@ -82,7 +91,8 @@ impl MemoryPressureReceiver {
         config: MemoryPressureMonitorConfig,
     ) -> Self {
         let (sender, receiver) = mpsc::channel();
-        thread::spawn(move || {
+        let (stop, stop_receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
             let mut smoother = PressureSmoother::new(config.monotonic_window);
             loop {
                 if let Some(reading) = source.sample(&thresholds) {
@@ -92,11 +102,32 @@ impl MemoryPressureReceiver {
                         break;
                     }
                 }
-                thread::sleep(config.sample_interval);
+                // Sleep until the next sample unless asked to stop first.
+                match stop_receiver.recv_timeout(config.sample_interval) {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                }
             }
         });
 
-        Self { receiver }
+        Self {
+            receiver,
+            worker: RefCell::new(Some(MonitorWorker { stop, handle })),
+        }
+    }
+
+    /// Stops the worker thread and waits for it to exit.
+    ///
+    /// A pending sleep is interrupted, so this returns as soon as any sample
+    /// in progress completes. Calling it again does nothing.
+    pub fn stop(&self) {
+        let Some(worker) = self.worker.borrow_mut().take() else {
+            return;
+        };
+        let _ = worker.stop.send(());
+        if worker.handle.join().is_err() {
+            log::warn!("memory pressure monitor panicked");
+        }
     }
 
     /// Returns the latest pressure update if one is available.
@@ -109,6 +140,12 @@ impl MemoryPressureReceiver {
     }
 }
 
+impl Drop for MemoryPressureReceiver {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
 /// Composite source that prefers cgroup v2, then system meminfo, then RSS.
 #[derive(Debug)]
 pub struct DefaultMemoryPressureSource {
@@ -431,4 +468,37 @@ mod tests {
         assert_eq!(map_headroom_per_mille(100, &thresholds), MemoryPressure::Severe);
         assert_eq!(map_headroom_per_mille(5, &thresholds), MemoryPressure::Severe);
     }
+
+    struct FixedSource;
+
+    impl MemoryPressureSource for FixedSource {
+        fn sample(&mut self, _thresholds: &MemoryPressureThresholds) -> Option<MemoryPressureReading> {
+            Some(MemoryPressureReading {
+                pressure: MemoryPressure::Moderate,
+                headroom_per_mille: 150,
+                source: MemoryPressureSourceKind::SystemMemInfo,
+            })
+        }
+    }
+
+    #[test]
+    fn stop_interrupts_the_sample_sleep() {
+        let receiver = MemoryPressureReceiver::start(
+            FixedSource,
+            MemoryPressureThresholds::default(),
+            MemoryPressureMonitorConfig {
+                sample_interval: Duration::from_secs(3600),
+                ..MemoryPressureMonitorConfig::default()
+            },
+        );
+
+        let started = Instant::now();
+        receiver.stop();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        receiver.stop();
+
+        // The first sample is taken before the worker sleeps.
+        assert_eq!(receiver.drain_latest(), Some(MemoryPressure::Moderate));
+        assert_eq!(receiver.drain_latest(), None);
+    }
 }
@@ -11,16 +11,23 @@ This is a structural contract test; do not remove.
 )]
 
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 #[cfg(feature = "diagnostics")]
 use std::fmt;
 use std::rc::Rc;
 use std::time::{Duration, Instant};
 
 use tabs::{TabId, TabState};
+use util::metrics::{self, Counter};
+
+#[cfg(feature = "prometheus")]
+mod prometheus;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 
 /// Simple execution budget tiers used as policy signals.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BudgetTier {
     /// Active tab with immediate user intent.
     Foreground,
@@ -38,6 +45,7 @@ impl Default for BudgetTier {
 
 /// Coarse memory pressure signal.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MemoryPressure {
     Low,
     Moderate,
@@ -55,6 +63,7 @@ impl Default for MemoryPressure {
 /// Budgets are policy signals used by the scheduler to gate effective tab
 /// states. They do not measure real JS CPU time yet.
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecutionBudget {
     /// Current budget tier for policy-driven scheduling.
     pub tier: BudgetTier,
@@ -65,6 +74,7 @@ pub struct ExecutionBudget {
 /// These signals are advisory only and do not alter execution by themselves.
 /// The scheduler may use them as hints, but they are not authoritative.
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EngineExecutionFeedback {
     /// Indicates recent long-running JavaScript tasks.
     ///
@@ -97,11 +107,30 @@ pub trait EngineFeedbackProvider {
     fn poll_execution_feedback(&self, tab: TabId) -> EngineExecutionFeedback;
 }
 
+/// Source of the current time for the governor.
+///
+/// Tests substitute `testing::MockClock` to step past idle and burst
+/// windows without sleeping.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// [`Clock`] backed by [`Instant::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
 /// Advisory signals derived from budgets and memory pressure.
 ///
 /// These hints do not enforce behavior and must not change JS semantics on their own.
 /// The engine layer may choose to apply them or ignore them based on capability.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExecutionBudgetHints {
     /// Optional maximum timer frequency; `None` means no clamp requested.
     pub max_timer_frequency: Option<Duration>,
@@ -133,84 +162,112 @@ impl ExecutionBudgetHints {
     }
 }
 
+/// Lookup table mapping `(BudgetTier, MemoryPressure)` to advisory hints.
+///
+/// The default table is monotonic: Severe ⊆ Moderate ⊆ Low. Custom tables
+/// are used as-is; integrators are responsible for keeping them sensible.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct HintPolicy {
+    table: [[ExecutionBudgetHints; 3]; 3],
+}
+
+impl HintPolicy {
+    const DEFAULT: Self = {
+        const TIMER_20HZ: Duration = Duration::from_millis(50);
+        const TIMER_10HZ: Duration = Duration::from_millis(100);
+        const TIMER_4HZ: Duration = Duration::from_millis(250);
+        const TIMER_2HZ: Duration = Duration::from_millis(500);
+        const TIMER_1HZ: Duration = Duration::from_millis(1000);
+        const TIMER_0_5HZ: Duration = Duration::from_millis(2000);
+
+        Self {
+            table: [
+                // BudgetTier::Foreground
+                [
+                    ExecutionBudgetHints::new(None, true, true, true, false),
+                    ExecutionBudgetHints::new(None, true, true, true, false),
+                    ExecutionBudgetHints::new(Some(TIMER_20HZ), true, false, false, false),
+                ],
+                // BudgetTier::VisibleBackground
+                [
+                    ExecutionBudgetHints::new(Some(TIMER_10HZ), true, true, true, false),
+                    ExecutionBudgetHints::new(Some(TIMER_4HZ), true, false, false, false),
+                    ExecutionBudgetHints::new(Some(TIMER_2HZ), false, false, false, true),
+                ],
+                // BudgetTier::IdleBackground
+                [
+                    ExecutionBudgetHints::new(Some(TIMER_2HZ), false, false, false, true),
+                    ExecutionBudgetHints::new(Some(TIMER_1HZ), false, false, false, true),
+                    ExecutionBudgetHints::new(Some(TIMER_0_5HZ), false, false, false, true),
+                ],
+            ],
+        }
+    };
+
+    /// Returns the hints for a tier and pressure level.
+    pub fn get(&self, tier: BudgetTier, pressure: MemoryPressure) -> ExecutionBudgetHints {
+        self.table[tier_index(tier)][pressure_index(pressure)]
+    }
+
+    /// Overrides the hints for a tier and pressure level.
+    pub fn set(&mut self, tier: BudgetTier, pressure: MemoryPressure, hints: ExecutionBudgetHints) {
+        self.table[tier_index(tier)][pressure_index(pressure)] = hints;
+    }
+
+    /// Returns a copy with every timer clamp interval multiplied by
+    /// `factor`, so values above 1 throttle harder. A factor of 0 drops all
+    /// clamps; entries without a clamp stay unclamped.
+    pub fn scale_timers(mut self, factor: f64) -> Self {
+        for hints in self.table.iter_mut().flatten() {
+            hints.max_timer_frequency = if factor > 0.0 {
+                hints.max_timer_frequency.map(|interval| interval.mul_f64(factor))
+            } else {
+                None
+            };
+        }
+        self
+    }
+}
+
+impl Default for HintPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn tier_index(tier: BudgetTier) -> usize {
+    match tier {
+        BudgetTier::Foreground => 0,
+        BudgetTier::VisibleBackground => 1,
+        BudgetTier::IdleBackground => 2,
+    }
+}
+
+fn pressure_index(pressure: MemoryPressure) -> usize {
+    match pressure {
+        MemoryPressure::Low => 0,
+        MemoryPressure::Moderate => 1,
+        MemoryPressure::Severe => 2,
+    }
+}
+
+/// Maps a budget + pressure signal into advisory hints using `policy`.
+pub fn map_execution_hints_with(
+    policy: &HintPolicy,
+    budget: ExecutionBudget,
+    pressure: MemoryPressure,
+) -> ExecutionBudgetHints {
+    policy.get(budget.tier, pressure)
+}
+
 /// Maps a budget + pressure signal into advisory hints.
 ///
-/// This mapping is monotonic: Severe ⊆ Moderate ⊆ Low.
+/// Uses the default [`HintPolicy`], which is monotonic: Severe ⊆ Moderate ⊆ Low.
 pub fn map_execution_hints(
     budget: ExecutionBudget,
     pressure: MemoryPressure,
 ) -> ExecutionBudgetHints {
-    const TIMER_20HZ: Duration = Duration::from_millis(50);
-    const TIMER_10HZ: Duration = Duration::from_millis(100);
-    const TIMER_4HZ: Duration = Duration::from_millis(250);
-    const TIMER_2HZ: Duration = Duration::from_millis(500);
-    const TIMER_1HZ: Duration = Duration::from_millis(1000);
-    const TIMER_0_5HZ: Duration = Duration::from_millis(2000);
-
-    match budget.tier {
-        BudgetTier::Foreground => match pressure {
-            MemoryPressure::Low | MemoryPressure::Moderate => ExecutionBudgetHints::new(
-                None,
-                true,
-                true,
-                true,
-                false,
-            ),
-            MemoryPressure::Severe => ExecutionBudgetHints::new(
-                Some(TIMER_20HZ),
-                true,
-                false,
-                false,
-                false,
-            ),
-        },
-        BudgetTier::VisibleBackground => match pressure {
-            MemoryPressure::Low => ExecutionBudgetHints::new(
-                Some(TIMER_10HZ),
-                true,
-                true,
-                true,
-                false,
-            ),
-            MemoryPressure::Moderate => ExecutionBudgetHints::new(
-                Some(TIMER_4HZ),
-                true,
-                false,
-                false,
-                false,
-            ),
-            MemoryPressure::Severe => ExecutionBudgetHints::new(
-                Some(TIMER_2HZ),
-                false,
-                false,
-                false,
-                true,
-            ),
-        },
-        BudgetTier::IdleBackground => match pressure {
-            MemoryPressure::Low => ExecutionBudgetHints::new(
-                Some(TIMER_2HZ),
-                false,
-                false,
-                false,
-                true,
-            ),
-            MemoryPressure::Moderate => ExecutionBudgetHints::new(
-                Some(TIMER_1HZ),
-                false,
-                false,
-                false,
-                true,
-            ),
-            MemoryPressure::Severe => ExecutionBudgetHints::new(
-                Some(TIMER_0_5HZ),
-                false,
-                false,
-                false,
-                true,
-            ),
-        },
-    }
+    map_execution_hints_with(&HintPolicy::DEFAULT, budget, pressure)
 }
 
 /// Engine-facing hooks used by the scheduler without exposing engine types.
@@ -225,6 +282,15 @@ pub trait EngineScheduler: EngineFeedbackProvider {
     ///
     /// These are intent signals only; the engine may ignore them to preserve compatibility.
     fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints);
+
+    /// Whether the engine can pause a `Frozen` tab's scripts and later resume
+    /// them with timers and page state intact.
+    ///
+    /// When it can, moderate memory pressure freezes background tabs instead
+    /// of suspending them, since freezing then stops their scripts too.
+    fn supports_script_pause(&self) -> bool {
+        false
+    }
 }
 
 /// Internal storage for engine execution feedback.
@@ -232,42 +298,109 @@ pub trait EngineScheduler: EngineFeedbackProvider {
 /// Feedback may be stale or incomplete; absence of a signal does not imply
 /// absence of activity. This is observational only, and the scheduler does
 /// not derive policy or change execution based on this state yet.
-#[cfg(feature = "diagnostics")]
+///
+/// Only the latest value is kept in the default build; sampling metadata
+/// used for aging and staleness reports requires `diagnostics`.
 struct ExecutionFeedbackState {
     per_tab: HashMap<TabId, FeedbackRecord>,
 }
 
-#[cfg(feature = "diagnostics")]
+/// Blocking samples needed before a tab can be reported unresponsive, so a
+/// single transient sample never is.
+const MIN_UNRESPONSIVE_SAMPLES: u32 = 2;
+
 #[derive(Debug, Clone, Copy)]
 struct FeedbackRecord {
     feedback: EngineExecutionFeedback,
-    updated_in_last_sample: bool,
     last_sampled_at: Instant,
+    /// Consecutive samples that returned unchanged feedback.
+    stable_samples: u32,
+    /// When the current run of `js_blocking_render` samples began.
+    blocking_since: Option<Instant>,
+    /// Consecutive samples reporting `js_blocking_render`.
+    blocking_samples: u32,
+    /// Whether the current blocking run has already been reported.
+    unresponsive_reported: bool,
+    #[cfg(feature = "diagnostics")]
+    updated_in_last_sample: bool,
+    #[cfg(feature = "diagnostics")]
     sample_count: u32,
 }
 
-#[cfg(feature = "diagnostics")]
 impl FeedbackRecord {
     fn new(feedback: EngineExecutionFeedback, sampled_at: Instant) -> Self {
-        Self {
+        let mut record = Self {
             feedback,
-            updated_in_last_sample: true,
             last_sampled_at: sampled_at,
+            stable_samples: 0,
+            blocking_since: None,
+            blocking_samples: 0,
+            unresponsive_reported: false,
+            #[cfg(feature = "diagnostics")]
+            updated_in_last_sample: true,
+            #[cfg(feature = "diagnostics")]
             sample_count: 1,
-        }
+        };
+        record.track_blocking(feedback.js_blocking_render, sampled_at);
+        record
     }
 
     fn update(&mut self, feedback: EngineExecutionFeedback, sampled_at: Instant) -> bool {
         let changed = self.feedback != feedback;
         if changed {
             self.feedback = feedback;
+            self.stable_samples = 0;
+        } else {
+            self.stable_samples = self.stable_samples.saturating_add(1);
         }
-        self.updated_in_last_sample = changed;
         self.last_sampled_at = sampled_at;
-        self.sample_count = self.sample_count.saturating_add(1);
+        self.track_blocking(feedback.js_blocking_render, sampled_at);
+        #[cfg(feature = "diagnostics")]
+        {
+            self.updated_in_last_sample = changed;
+            self.sample_count = self.sample_count.saturating_add(1);
+        }
         changed
     }
 
+    fn track_blocking(&mut self, blocking: bool, sampled_at: Instant) {
+        if blocking {
+            self.blocking_since.get_or_insert(sampled_at);
+            self.blocking_samples = self.blocking_samples.saturating_add(1);
+        } else {
+            self.blocking_since = None;
+            self.blocking_samples = 0;
+            self.unresponsive_reported = false;
+        }
+    }
+
+    /// Returns how long the tab has reported `js_blocking_render`, measured
+    /// between samples; a single blocking sample yields `None`.
+    fn blocking_for(&self) -> Option<Duration> {
+        let since = self.blocking_since?;
+        (self.blocking_samples >= MIN_UNRESPONSIVE_SAMPLES)
+            .then(|| self.last_sampled_at.saturating_duration_since(since))
+    }
+
+    /// Returns how long to wait after the last sample before sampling again.
+    ///
+    /// Once feedback has been stable for `feedback_backoff_after` samples,
+    /// the interval doubles with every further stable sample, up to
+    /// `feedback_max_interval`.
+    fn sample_interval(&self, config: &SchedulerConfig) -> Duration {
+        let min = config.feedback_min_interval;
+        let max = config.feedback_max_interval.max(min);
+        let Some(extra) = self
+            .stable_samples
+            .checked_sub(config.feedback_backoff_after)
+        else {
+            return min;
+        };
+        min.checked_mul(2u32.saturating_pow(extra.saturating_add(1)))
+            .map_or(max, |interval| interval.min(max))
+    }
+
+    #[cfg(feature = "diagnostics")]
     fn staleness_tag(&self) -> FeedbackStalenessTag {
         if self.updated_in_last_sample {
             FeedbackStalenessTag::Fresh
@@ -277,7 +410,6 @@ impl FeedbackRecord {
     }
 }
 
-#[cfg(feature = "diagnostics")]
 impl ExecutionFeedbackState {
     fn new() -> Self {
         Self {
@@ -301,6 +433,14 @@ impl ExecutionFeedbackState {
         self.per_tab.insert(tab, FeedbackRecord::new(feedback, sampled_at));
         true
     }
+
+    /// Returns true when the tab has never been sampled or its sampling
+    /// interval has elapsed.
+    fn is_due(&self, tab: TabId, now: Instant, config: &SchedulerConfig) -> bool {
+        self.per_tab.get(&tab).is_none_or(|record| {
+            now.saturating_duration_since(record.last_sampled_at) >= record.sample_interval(config)
+        })
+    }
 }
 
 /// Read-only snapshot view of stored execution feedback.
@@ -372,6 +512,8 @@ impl FeedbackAgingWindows {
 #[cfg(feature = "diagnostics")]
 pub struct ExecutionFeedbackAggregate<'a> {
     state: std::cell::Ref<'a, ExecutionFeedbackState>,
+    /// Time the snapshot was taken; feedback ages are measured from here.
+    now: Instant,
 }
 
 /// Aggregate counts of feedback ages.
@@ -437,7 +579,7 @@ pub struct ExecutionFeedbackAggregateReport<'a> {
 #[cfg(feature = "diagnostics")]
 impl<'a> fmt::Display for ExecutionFeedbackAggregateReport<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let now = Instant::now();
+        let now = self.aggregate.now;
         let mut dist = FeedbackAgeDistribution::default();
         let mut long_tasks = 0usize;
         let mut wasm_active = 0usize;
@@ -582,7 +724,7 @@ impl<'a> ExecutionFeedbackAggregate<'a> {
     ///
     /// Aggregates are diagnostic only and may be stale.
     pub fn count_by_age_class(&self, windows: FeedbackAgingWindows, class: FeedbackAgeClass) -> usize {
-        let now = Instant::now();
+        let now = self.now;
         self.state
             .per_tab
             .values()
@@ -594,7 +736,7 @@ impl<'a> ExecutionFeedbackAggregate<'a> {
     ///
     /// Aggregates are diagnostic only and may be stale.
     pub fn age_distribution(&self, windows: FeedbackAgingWindows) -> FeedbackAgeDistribution {
-        let now = Instant::now();
+        let now = self.now;
         let mut dist = FeedbackAgeDistribution::default();
         for record in self.state.per_tab.values() {
             match windows.classify(now.duration_since(record.last_sampled_at)) {
@@ -610,7 +752,7 @@ impl<'a> ExecutionFeedbackAggregate<'a> {
     ///
     /// Returns `None` when no feedback has been sampled.
     pub fn max_feedback_age(&self) -> Option<Duration> {
-        let now = Instant::now();
+        let now = self.now;
         self.state
             .per_tab
             .values()
@@ -622,7 +764,7 @@ impl<'a> ExecutionFeedbackAggregate<'a> {
     ///
     /// Returns `None` when no feedback has been sampled.
     pub fn average_feedback_age(&self) -> Option<Duration> {
-        let now = Instant::now();
+        let now = self.now;
         let mut total: u128 = 0;
         let mut count: u128 = 0;
         for record in self.state.per_tab.values() {
@@ -705,7 +847,6 @@ impl<'a> ExecutionFeedbackSnapshot<'a> {
     }
 }
 
-#[cfg(feature = "diagnostics")]
 #[derive(Debug, Clone, Copy)]
 enum FeedbackSamplingEvent {
     TabStateChange,
@@ -715,15 +856,13 @@ enum FeedbackSamplingEvent {
 /// Determines when to opportunistically sample engine feedback.
 ///
 /// Sampling frequency is intentionally low and tied to existing scheduler events.
-/// This remains extensible for future rate limiting or adaptive sampling.
-#[cfg(feature = "diagnostics")]
+/// Per-tab rate limiting and backoff are configured on [`SchedulerConfig`].
 #[derive(Debug, Clone, Copy)]
 struct FeedbackSamplingTrigger {
     sample_on_state_change: bool,
     sample_on_budget_change: bool,
 }
 
-#[cfg(feature = "diagnostics")]
 impl FeedbackSamplingTrigger {
     fn should_sample(&self, event: FeedbackSamplingEvent) -> bool {
         match event {
@@ -733,7 +872,6 @@ impl FeedbackSamplingTrigger {
     }
 }
 
-#[cfg(feature = "diagnostics")]
 impl Default for FeedbackSamplingTrigger {
     fn default() -> Self {
         Self {
@@ -743,6 +881,137 @@ impl Default for FeedbackSamplingTrigger {
     }
 }
 
+/// Timing windows that drive intent detection and idle bursts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct SchedulerConfig {
+    /// Input within this window marks the user as actively interacting.
+    pub active_input_window: Duration,
+    /// Time without input after which the user is considered idle.
+    pub idle_threshold: Duration,
+    /// Interval between background bursts while the user is idle.
+    pub idle_burst_interval: Duration,
+    /// Length of each idle burst.
+    pub idle_burst_duration: Duration,
+    /// Most background tabs woken by a single idle burst.
+    ///
+    /// Successive bursts rotate through the background tabs, so with many
+    /// tabs each one runs every few bursts instead of all waking at once.
+    pub idle_burst_max_tabs: usize,
+    /// Grace period during which a recently interacted tab keeps running.
+    pub tab_input_grace: Duration,
+    /// Minimum time between event-driven feedback samples of the same tab.
+    pub feedback_min_interval: Duration,
+    /// Upper bound for the sampling interval of tabs with stable feedback.
+    ///
+    /// Set it to `feedback_min_interval` to disable adaptive backoff.
+    pub feedback_max_interval: Duration,
+    /// Number of unchanged samples before a tab's sampling starts backing off.
+    pub feedback_backoff_after: u32,
+    /// How long a tab must keep reporting `js_blocking_render` across
+    /// samples before it is reported unresponsive.
+    pub unresponsive_after: Duration,
+    /// Poll delay while the user is active or an idle burst is open.
+    pub poll_interval: Duration,
+    /// Longest poll delay once the user has gone idle.
+    pub idle_poll_interval: Duration,
+    /// How long a tab may stay in the background before it is suspended,
+    /// whether or not the user is idle. Pinned and audible tabs are exempt.
+    ///
+    /// The default, `Duration::MAX`, never suspends.
+    pub background_discard_after: Duration,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            active_input_window: Duration::from_millis(1200),
+            idle_threshold: Duration::from_secs(4),
+            idle_burst_interval: Duration::from_secs(5),
+            idle_burst_duration: Duration::from_millis(500),
+            idle_burst_max_tabs: 8,
+            tab_input_grace: Duration::from_millis(800),
+            feedback_min_interval: Duration::from_millis(200),
+            feedback_max_interval: Duration::from_secs(5),
+            feedback_backoff_after: 4,
+            unresponsive_after: Duration::from_secs(5),
+            poll_interval: Duration::from_millis(250),
+            idle_poll_interval: Duration::from_secs(2),
+            background_discard_after: Duration::MAX,
+        }
+    }
+}
+
+/// User interaction level derived from the time since the last input.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum UserActivity {
+    /// Input arrived within the active input window.
+    Active,
+    /// Between the active window and the idle threshold.
+    Transitioning,
+    /// No input for at least the idle threshold; background tabs only run in idle bursts.
+    Idle,
+}
+
+impl UserActivity {
+    fn classify(since_input: Duration, config: &SchedulerConfig) -> Self {
+        if since_input <= config.active_input_window {
+            UserActivity::Active
+        } else if since_input >= config.idle_threshold {
+            UserActivity::Idle
+        } else {
+            UserActivity::Transitioning
+        }
+    }
+}
+
+/// Changes made by a single reconcile pass, ordered by tab id.
+///
+/// State changes include tabs seen for the first time; budget changes only
+/// list tabs whose tier differs from what the engine was last given.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ReconcileReport {
+    /// Tabs whose effective state changed, with the new state.
+    pub state_changes: Vec<(TabId, TabState)>,
+    /// Tabs whose budget tier changed, with the new tier.
+    pub budget_changes: Vec<(TabId, BudgetTier)>,
+}
+
+impl ReconcileReport {
+    /// Returns true when the pass changed nothing.
+    pub fn is_empty(&self) -> bool {
+        self.state_changes.is_empty() && self.budget_changes.is_empty()
+    }
+}
+
+/// Number of tracked tabs in each effective state.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct StateCounts {
+    pub active: usize,
+    pub background: usize,
+    pub frozen: usize,
+    pub suspended: usize,
+}
+
+impl StateCounts {
+    /// Returns the number of tabs counted.
+    pub fn total(&self) -> usize {
+        self.active + self.background + self.frozen + self.suspended
+    }
+}
+
+/// Per-tab scheduler snapshot for external telemetry.
+///
+/// Fields are `None` until the governor has applied a budget or hints, or
+/// sampled feedback, for the tab.
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, serde::Serialize)]
+pub struct TabTelemetry {
+    pub tab: u64,
+    pub budget: Option<ExecutionBudget>,
+    pub hints: Option<ExecutionBudgetHints>,
+    pub feedback: Option<EngineExecutionFeedback>,
+}
+
 /// Interface for governing JavaScript execution without rewriting scripts.
 pub trait JSExecutionGovernor {
     /// Applies a new budget to the tab.
@@ -752,230 +1021,1133 @@ pub trait JSExecutionGovernor {
     fn on_tab_state_changed(&self, tab: TabId, state: TabState);
 }
 
+/// Explicit per-tab override set through the governor's suspend/resume API.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExplicitRequest {
+    /// Set by [`ExecutionGovernor::request_suspend`].
+    Suspend,
+    /// Set by [`ExecutionGovernor::request_resume`].
+    Resume,
+}
+
+/// The deciding reason behind a tab's effective state, for explaining it to
+/// the user.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum StateReason {
+    /// The tab is shown in a focused or visible window.
+    Foreground,
+    /// The tab is shown, but its window is hidden.
+    WindowHidden,
+    /// The tab manager put the tab in a frozen or suspended state itself.
+    Assigned,
+    /// Background work is deferred while the user is active.
+    UserActive,
+    /// The user is idle and no idle burst currently includes the tab.
+    IdleNoBurst,
+    /// An idle burst lets the tab run for a while.
+    IdleBurst,
+    /// The tab had input moments ago, so it keeps running for a grace period.
+    RecentInput,
+    /// Pinned tabs keep running in the background.
+    PinnedProtected,
+    /// The tab is playing audio, so it keeps running.
+    AudiblePlaying,
+    /// A background tab the policy lets run.
+    BackgroundAllowed,
+    /// Moderate memory pressure forced the tab to suspend.
+    MemoryPressureModerate,
+    /// Severe memory pressure forced the tab to suspend.
+    MemoryPressureSevere,
+    /// Suspended by [`ExecutionGovernor::request_suspend`].
+    ExplicitSuspend,
+    /// Kept running by [`ExecutionGovernor::request_resume`].
+    ExplicitResume,
+    /// The tab has been in the background for longer than
+    /// [`SchedulerConfig::background_discard_after`].
+    BackgroundExpired,
+    /// The tab is presenting; see [`ExecutionGovernor::set_presentation_tab`].
+    Presenting,
+}
+
+/// Inputs behind a tab's scheduling decision.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PlanInputs {
+    /// State assigned by the tab manager.
+    pub base_state: TabState,
+    /// How recently the user interacted with any tab.
+    pub activity: UserActivity,
+    /// Whether an idle burst that includes this tab is currently open.
+    pub idle_burst: bool,
+    /// Whether the tab itself had input within the grace period.
+    pub tab_recent: bool,
+    /// Whether the browser window has focus; see [`ExecutionGovernor::on_tab_blurred`].
+    pub focused: bool,
+    /// Whether the browser window is shown; see [`ExecutionGovernor::set_window_visible`].
+    pub window_visible: bool,
+    /// Memory pressure applied to the tab.
+    pub pressure: MemoryPressure,
+    pub pinned: bool,
+    pub audible: bool,
+    pub presenting: bool,
+    /// Whether the tab has been in the background for longer than
+    /// [`SchedulerConfig::background_discard_after`].
+    pub background_expired: bool,
+    /// See [`EngineScheduler::supports_script_pause`].
+    pub script_pause: bool,
+    /// Explicit suspend or resume, if any.
+    pub request: Option<ExplicitRequest>,
+}
+
+impl PlanInputs {
+    /// Computes the effective state, budget, and hints for these inputs.
+    ///
+    /// This is the whole per-tab policy; it has no side effects.
+    fn decide(self, policy: &HintPolicy) -> TabPlan {
+        let user_active = self.activity == UserActivity::Active;
+        let user_idle = self.activity == UserActivity::Idle;
+        let Self {
+            base_state,
+            idle_burst,
+            tab_recent,
+            focused,
+            window_visible,
+            pressure,
+            pinned,
+            audible,
+            background_expired,
+            ..
+        } = self;
+
+        let mut reason = StateReason::Assigned;
+        let (mut effective, mut budget) = match base_state {
+            // Nobody can see the active tab of a hidden window, so it is
+            // budgeted like a background tab, but is never frozen.
+            TabState::Active if !window_visible => {
+                reason = StateReason::WindowHidden;
+                (
+                    TabState::Background,
+                    ExecutionBudget {
+                        tier: BudgetTier::IdleBackground,
+                    },
+                )
+            }
+            // The active tab of a blurred window stays visible but gives up
+            // its foreground priority until the window is focused again.
+            TabState::Active => {
+                reason = StateReason::Foreground;
+                (
+                    TabState::Active,
+                    ExecutionBudget {
+                        tier: if focused {
+                            BudgetTier::Foreground
+                        } else {
+                            BudgetTier::VisibleBackground
+                        },
+                    },
+                )
+            }
+            TabState::Frozen => (
+                TabState::Frozen,
+                ExecutionBudget {
+                    tier: BudgetTier::IdleBackground,
+                },
+            ),
+            TabState::Suspended => (
+                TabState::Suspended,
+                ExecutionBudget {
+                    tier: BudgetTier::IdleBackground,
+                },
+            ),
+            TabState::Background => {
+                // Intent influences the budget tier, which in turn gates execution.
+                // Pinned tabs are never demoted to idle bursts.
+                let tier = if user_active || pinned {
+                    BudgetTier::VisibleBackground
+                } else if user_idle {
+                    BudgetTier::IdleBackground
+                } else {
+                    BudgetTier::VisibleBackground
+                };
+
+                // Audible tabs keep running so playback does not cut out.
+                let (allow, why) = if audible {
+                    (true, StateReason::AudiblePlaying)
+                } else if user_active {
+                    // Defer non-critical background JS while the user is active.
+                    (false, StateReason::UserActive)
+                } else if user_idle && !pinned {
+                    if idle_burst {
+                        (true, StateReason::IdleBurst)
+                    } else {
+                        (false, StateReason::IdleNoBurst)
+                    }
+                } else if pinned {
+                    (true, StateReason::PinnedProtected)
+                } else {
+                    (true, StateReason::BackgroundAllowed)
+                };
+
+                // Intent-driven deferral is recoverable, so the tab is frozen
+                // rather than suspended.
+                let state = if allow || tab_recent {
+                    TabState::Background
+                } else {
+                    TabState::Frozen
+                };
+                reason = if !allow && tab_recent {
+                    StateReason::RecentInput
+                } else {
+                    why
+                };
+
+                (state, ExecutionBudget { tier })
+            }
+        };
+
+        // Memory pressure only demotes budgets; it never promotes.
+        // Foreground tabs stay protected unless pressure is severe.
+        // Pinned background tabs only give way to severe pressure.
+        let intent_tier = budget.tier;
+        budget.tier = match (pressure, budget.tier) {
+            (MemoryPressure::Low, tier) => tier,
+            (MemoryPressure::Moderate, tier) if pinned && base_state == TabState::Background => {
+                tier
+            }
+            (MemoryPressure::Moderate, BudgetTier::Foreground) => BudgetTier::Foreground,
+            (MemoryPressure::Moderate, BudgetTier::VisibleBackground) => BudgetTier::IdleBackground,
+            (MemoryPressure::Moderate, BudgetTier::IdleBackground) => BudgetTier::IdleBackground,
+            (MemoryPressure::Severe, BudgetTier::Foreground) => BudgetTier::VisibleBackground,
+            (MemoryPressure::Severe, BudgetTier::VisibleBackground) => BudgetTier::IdleBackground,
+            (MemoryPressure::Severe, BudgetTier::IdleBackground) => BudgetTier::IdleBackground,
+        };
+
+        // Budget tiers further gate effective state for background tabs.
+        // Suspension is reserved for pressure-forced demotion; idle gating
+        // only freezes the tab, and leaves audible tabs running.
+        if base_state == TabState::Background
+            && budget.tier == BudgetTier::IdleBackground
+            && !(user_idle && idle_burst)
+            && !tab_recent
+        {
+            let forced_by_pressure =
+                budget.tier != intent_tier || pressure == MemoryPressure::Severe;
+            if forced_by_pressure {
+                // A resumable pause relieves moderate pressure without
+                // discarding the page.
+                effective = if pressure == MemoryPressure::Moderate && self.script_pause {
+                    TabState::Frozen
+                } else {
+                    TabState::Suspended
+                };
+                reason = if pressure == MemoryPressure::Severe {
+                    StateReason::MemoryPressureSevere
+                } else {
+                    StateReason::MemoryPressureModerate
+                };
+            } else if !audible {
+                effective = TabState::Frozen;
+                reason = StateReason::IdleNoBurst;
+            }
+        }
+
+        // Long-unused background tabs are suspended whatever the user is
+        // doing, under the same exemptions as idle gating.
+        if base_state == TabState::Background && background_expired && !pinned && !audible {
+            effective = TabState::Suspended;
+            budget.tier = BudgetTier::IdleBackground;
+            reason = StateReason::BackgroundExpired;
+        }
+
+        // Explicit requests override intent, idle, pressure, and age gating.
+        // A resumed tab runs, but keeps its pressure-demoted budget.
+        match self.request {
+            Some(ExplicitRequest::Suspend) => {
+                effective = TabState::Suspended;
+                budget.tier = BudgetTier::IdleBackground;
+                reason = StateReason::ExplicitSuspend;
+            }
+            Some(ExplicitRequest::Resume) if effective != TabState::Active => {
+                effective = TabState::Background;
+                reason = StateReason::ExplicitResume;
+            }
+            _ => {}
+        }
+
+        // Presentation tabs bypass intent, idle, and pressure gating.
+        let hint_pressure = if self.presenting {
+            effective = TabState::Active;
+            budget.tier = BudgetTier::Foreground;
+            reason = StateReason::Presenting;
+            MemoryPressure::Low
+        } else {
+            pressure
+        };
+
+        TabPlan {
+            state: effective,
+            budget,
+            hints: map_execution_hints_with(policy, budget, hint_pressure),
+            reason,
+            inputs: self,
+        }
+    }
+}
+
+/// A scheduling decision for one tab, with the inputs that drove it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct TabPlan {
+    /// Effective state the tab runs in.
+    pub state: TabState,
+    pub budget: ExecutionBudget,
+    pub hints: ExecutionBudgetHints,
+    /// Why the tab ends up in `state`.
+    pub reason: StateReason,
+    pub inputs: PlanInputs,
+}
+
+/// Callback invoked with `(tab, old_effective, new_effective)` after the
+/// governor changes a tab's effective state.
+pub type TransitionObserver = Box<dyn Fn(TabId, TabState, TabState)>;
+
+/// Callback invoked with `(tab, blocked_for)` once a tab has reported
+/// `js_blocking_render` for at least `unresponsive_after`.
+pub type UnresponsiveObserver = Box<dyn Fn(TabId, Duration)>;
+
 /// Level-1 governor that delegates state changes to the engine.
 ///
 /// This tracks tab states and applies engine-level throttling hooks but does
 /// not implement advanced scheduling logic yet.
 pub struct ExecutionGovernor {
     engine: Rc<dyn EngineScheduler>,
+    clock: Rc<dyn Clock>,
+    config: SchedulerConfig,
+    hint_policy: Cell<HintPolicy>,
     states: RefCell<HashMap<TabId, TabState>>,
     budgets: RefCell<HashMap<TabId, ExecutionBudget>>,
     hints: RefCell<HashMap<TabId, ExecutionBudgetHints>>,
-    #[cfg(feature = "diagnostics")]
     feedback: RefCell<ExecutionFeedbackState>,
-    #[cfg(feature = "diagnostics")]
     feedback_trigger: FeedbackSamplingTrigger,
     effective_states: RefCell<HashMap<TabId, TabState>>,
     last_global_input: Cell<Instant>,
     last_idle_burst: Cell<Instant>,
+    /// Background tabs woken by the current idle burst.
+    burst_tabs: RefCell<HashSet<TabId>>,
+    /// Last tab woken by the previous burst; the next burst continues after it.
+    burst_cursor: Cell<Option<TabId>>,
     last_tab_input: RefCell<HashMap<TabId, Instant>>,
+    /// When each tab last left the active state.
+    background_since: RefCell<HashMap<TabId, Instant>>,
     memory_pressure: Cell<MemoryPressure>,
+    window_focused: Cell<bool>,
+    window_visible: Cell<bool>,
+    tab_memory_pressure: RefCell<HashMap<TabId, MemoryPressure>>,
+    pinned: RefCell<HashSet<TabId>>,
+    requests: RefCell<HashMap<TabId, ExplicitRequest>>,
+    audible: RefCell<HashSet<TabId>>,
+    presentation_tab: Cell<Option<TabId>>,
+    transition_observer: RefCell<Option<TransitionObserver>>,
+    observer_generation: Cell<u64>,
+    unresponsive_observer: RefCell<Option<UnresponsiveObserver>>,
+    unresponsive_generation: Cell<u64>,
+    notifying: Cell<bool>,
+    last_report: RefCell<ReconcileReport>,
 }
 
 impl ExecutionGovernor {
     pub fn new<E: EngineScheduler + 'static>(engine: Rc<E>) -> Self {
-        let now = Instant::now();
-        let engine: Rc<dyn EngineScheduler> = engine;
+        Self::with_config(engine, SchedulerConfig::default())
+    }
+
+    /// Creates a governor with custom timing windows.
+    pub fn with_config<E: EngineScheduler + 'static>(engine: Rc<E>, config: SchedulerConfig) -> Self {
+        Self::from_dyn_with_config(engine, config)
+    }
+
+    /// Creates a governor from an engine that is already a trait object.
+    pub fn from_dyn(engine: Rc<dyn EngineScheduler>) -> Self {
+        Self::from_dyn_with_config(engine, SchedulerConfig::default())
+    }
+
+    /// Like [`ExecutionGovernor::from_dyn`], with custom timing windows.
+    pub fn from_dyn_with_config(engine: Rc<dyn EngineScheduler>, config: SchedulerConfig) -> Self {
+        Self::from_dyn_with_clock(engine, config, Rc::new(SystemClock))
+    }
+
+    /// Creates a governor that reads the time from `clock` instead of the
+    /// system clock.
+    pub fn with_clock<E: EngineScheduler + 'static, C: Clock + 'static>(
+        engine: Rc<E>,
+        config: SchedulerConfig,
+        clock: Rc<C>,
+    ) -> Self {
+        Self::from_dyn_with_clock(engine, config, clock)
+    }
+
+    /// Like [`ExecutionGovernor::with_clock`], for an engine that is already
+    /// a trait object.
+    pub fn from_dyn_with_clock(
+        engine: Rc<dyn EngineScheduler>,
+        config: SchedulerConfig,
+        clock: Rc<dyn Clock>,
+    ) -> Self {
+        let now = clock.now();
         Self {
             engine,
+            clock,
+            config,
+            hint_policy: Cell::new(HintPolicy::default()),
             states: RefCell::new(HashMap::new()),
             budgets: RefCell::new(HashMap::new()),
             hints: RefCell::new(HashMap::new()),
-            #[cfg(feature = "diagnostics")]
             feedback: RefCell::new(ExecutionFeedbackState::new()),
-            #[cfg(feature = "diagnostics")]
             feedback_trigger: FeedbackSamplingTrigger::default(),
             effective_states: RefCell::new(HashMap::new()),
             last_global_input: Cell::new(now),
             last_idle_burst: Cell::new(now),
+            burst_tabs: RefCell::new(HashSet::new()),
+            burst_cursor: Cell::new(None),
             last_tab_input: RefCell::new(HashMap::new()),
+            background_since: RefCell::new(HashMap::new()),
             memory_pressure: Cell::new(MemoryPressure::Low),
+            window_focused: Cell::new(true),
+            window_visible: Cell::new(true),
+            tab_memory_pressure: RefCell::new(HashMap::new()),
+            pinned: RefCell::new(HashSet::new()),
+            requests: RefCell::new(HashMap::new()),
+            audible: RefCell::new(HashSet::new()),
+            presentation_tab: Cell::new(None),
+            transition_observer: RefCell::new(None),
+            observer_generation: Cell::new(0),
+            unresponsive_observer: RefCell::new(None),
+            unresponsive_generation: Cell::new(0),
+            notifying: Cell::new(false),
+            last_report: RefCell::new(ReconcileReport::default()),
         }
     }
 
-    /// Returns the last known state for a tab, if tracked.
-    pub fn state(&self, tab: TabId) -> Option<TabState> {
-        self.states.borrow().get(&tab).copied()
+    /// Installs an observer notified whenever a tab's effective state changes.
+    ///
+    /// The observer runs after the engine has applied the new state. Tabs seen
+    /// for the first time do not produce a notification. Calls made from inside
+    /// the observer are recorded but do not reconcile until the next poll.
+    pub fn set_transition_observer(&self, observer: TransitionObserver) {
+        *self.transition_observer.borrow_mut() = Some(observer);
+        self.observer_generation.set(self.observer_generation.get() + 1);
     }
 
-    /// Records a user interaction for the given tab.
-    pub fn record_user_input(&self, tab: TabId) {
-        let now = Instant::now();
-        self.mark_recent_input(tab, now);
-        self.reconcile(now);
+    /// Removes the transition observer, if any.
+    pub fn clear_transition_observer(&self) {
+        self.transition_observer.borrow_mut().take();
+        self.observer_generation.set(self.observer_generation.get() + 1);
     }
 
-    /// Updates memory pressure. This only ever demotes budget tiers.
-    pub fn set_memory_pressure(&self, pressure: MemoryPressure) {
-        self.memory_pressure.set(pressure);
-        self.reconcile(Instant::now());
+    /// Installs an observer notified when a tab becomes unresponsive.
+    ///
+    /// A tab is unresponsive once consecutive feedback samples have reported
+    /// `js_blocking_render` for at least `unresponsive_after`. Each blocking
+    /// run is reported once; a non-blocking sample ends the run.
+    pub fn set_unresponsive_observer(&self, observer: UnresponsiveObserver) {
+        *self.unresponsive_observer.borrow_mut() = Some(observer);
+        self.unresponsive_generation
+            .set(self.unresponsive_generation.get() + 1);
     }
 
-    /// Polls engine feedback for a tab and stores it if it changed.
-    ///
-    /// Sampling is opportunistic and may be stale; this is observational only
-    /// and does not affect scheduling decisions.
-    #[cfg(feature = "diagnostics")]
-    pub fn poll_execution_feedback(&self, tab: TabId) {
-        self.feedback
-            .borrow_mut()
-            .update_for_tab(tab, self.engine.as_ref(), Instant::now());
+    /// Removes the unresponsive observer, if any.
+    pub fn clear_unresponsive_observer(&self) {
+        self.unresponsive_observer.borrow_mut().take();
+        self.unresponsive_generation
+            .set(self.unresponsive_generation.get() + 1);
     }
 
-    /// Returns a read-only snapshot of stored execution feedback for a tab.
-    ///
-    /// The snapshot may be stale or incomplete and must not drive policy.
-    /// `None` indicates feedback has not been sampled for the tab yet.
-    #[cfg(feature = "diagnostics")]
-    pub fn get_execution_feedback(
-        &self,
-        tab: TabId,
-    ) -> Option<ExecutionFeedbackSnapshot<'_>> {
-        let state = self.feedback.borrow();
-        if !state.per_tab.contains_key(&tab) {
-            return None;
-        }
-        Some(ExecutionFeedbackSnapshot { state, tab })
+    /// Replaces the hint table used to derive advisory hints.
+    pub fn with_hint_policy(self, policy: HintPolicy) -> Self {
+        self.hint_policy.set(policy);
+        self
     }
 
-    /// Returns an aggregated, read-only snapshot of all stored feedback.
-    ///
-    /// This data is observational only and must not influence scheduling policy.
-    #[cfg(feature = "diagnostics")]
-    pub fn execution_feedback_snapshot(&self) -> ExecutionFeedbackAggregate<'_> {
-        ExecutionFeedbackAggregate {
-            state: self.feedback.borrow(),
+    /// Swaps the hint table at runtime and re-applies hints to every tab.
+    pub fn set_hint_policy(&self, policy: HintPolicy) {
+        if self.hint_policy.replace(policy) != policy {
+            self.reconcile(self.clock.now());
         }
     }
 
-    /// Polls the governor to refresh idle/burst state.
-    pub fn poll(&self) {
-        self.reconcile(Instant::now());
+    /// Returns the timing configuration in use.
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.config
     }
 
-    fn mark_recent_input(&self, tab: TabId, now: Instant) {
-        self.last_global_input.set(now);
-        self.last_idle_burst.set(now);
-        self.last_tab_input.borrow_mut().insert(tab, now);
+    /// Returns the last known state for a tab, if tracked.
+    pub fn state(&self, tab: TabId) -> Option<TabState> {
+        self.states.borrow().get(&tab).copied()
     }
 
-    fn reconcile(&self, now: Instant) {
-        const ACTIVE_INPUT_WINDOW: Duration = Duration::from_millis(1200);
-        const IDLE_THRESHOLD: Duration = Duration::from_secs(4);
-        const IDLE_BURST_INTERVAL: Duration = Duration::from_secs(5);
-        const IDLE_BURST_DURATION: Duration = Duration::from_millis(500);
-        const TAB_INPUT_GRACE: Duration = Duration::from_millis(800);
+    /// Returns true when the scheduler runs the tab in a different state than
+    /// the tab manager assigned, e.g. a background tab suspended under pressure.
+    pub fn is_overridden(&self, tab: TabId) -> bool {
+        let base = self.states.borrow().get(&tab).copied();
+        let effective = self.effective_states.borrow().get(&tab).copied();
+        match (base, effective) {
+            (Some(base), Some(effective)) => base != effective,
+            _ => false,
+        }
+    }
 
-        // Intent is separate from tab lifecycle: tab state is owned by the tab manager,
-        // while intent reflects recent user interaction and can further gate background JS.
-        let since_input = now.duration_since(self.last_global_input.get());
-        let user_active = since_input <= ACTIVE_INPUT_WINDOW;
-        let user_idle = since_input >= IDLE_THRESHOLD;
+    /// Returns how recently the user interacted, as used for background gating.
+    pub fn user_activity(&self) -> UserActivity {
+        self.user_activity_at(self.clock.now())
+    }
 
-        // When idle, allow short background bursts at a fixed interval.
-        let allow_idle_burst = if user_idle {
-            let since_burst = now.duration_since(self.last_idle_burst.get());
-            if since_burst >= IDLE_BURST_INTERVAL {
-                self.last_idle_burst.set(now);
-                true
-            } else {
-                since_burst <= IDLE_BURST_DURATION
+    /// Applies budgets for several tabs in one pass.
+    ///
+    /// When a tab appears more than once the last budget wins, so the engine
+    /// sees at most one budget and one hint update per tab.
+    pub fn set_budgets(&self, budgets: &[(TabId, ExecutionBudget)]) {
+        let mut batch: Vec<(TabId, ExecutionBudget)> = Vec::with_capacity(budgets.len());
+        for &(tab, budget) in budgets {
+            match batch.iter_mut().find(|(id, _)| *id == tab) {
+                Some(entry) => entry.1 = budget,
+                None => batch.push((tab, budget)),
             }
-        } else {
-            false
-        };
-
-        let states_snapshot: Vec<(TabId, TabState)> =
-            self.states.borrow().iter().map(|(id, state)| (*id, *state)).collect();
-        let last_tab_input = self.last_tab_input.borrow();
-        let mut effective_states = self.effective_states.borrow_mut();
+        }
 
-        let pressure = self.memory_pressure.get();
+        for (tab, budget) in batch {
+            let budget_changed = self.apply_budget(tab, budget);
+            let pressure = self.memory_pressure_for(tab);
+            let hints = map_execution_hints_with(&self.hint_policy.get(), budget, pressure);
+            self.apply_hints(tab, hints);
+            self.maybe_poll_feedback(tab, false, budget_changed, self.clock.now());
+        }
+    }
 
-        for (tab, base_state) in states_snapshot {
-            // Short grace window for tabs that were just interacted with.
-            let tab_recent = last_tab_input
-                .get(&tab)
-                .map(|ts| now.duration_since(*ts) <= TAB_INPUT_GRACE)
-                .unwrap_or(false);
-
-            let (mut effective, mut budget) = match base_state {
-                TabState::Active => (
-                    TabState::Active,
-                    ExecutionBudget {
-                        tier: BudgetTier::Foreground,
-                    },
-                ),
-                TabState::Suspended => (
-                    TabState::Suspended,
-                    ExecutionBudget {
-                        tier: BudgetTier::IdleBackground,
-                    },
-                ),
-                TabState::Background => {
-                    // Intent influences the budget tier, which in turn gates execution.
-                    let tier = if user_active {
-                        BudgetTier::VisibleBackground
-                    } else if user_idle {
-                        BudgetTier::IdleBackground
-                    } else {
-                        BudgetTier::VisibleBackground
-                    };
-
-                    let allow = if user_active {
-                        // Defer non-critical background JS while the user is active.
-                        false
-                    } else if user_idle {
-                        allow_idle_burst
-                    } else {
-                        true
-                    };
+    /// Drops all tracked state for a tab that has been closed.
+    pub fn forget_tab(&self, tab: TabId) {
+        self.states.borrow_mut().remove(&tab);
+        self.budgets.borrow_mut().remove(&tab);
+        self.hints.borrow_mut().remove(&tab);
+        self.effective_states.borrow_mut().remove(&tab);
+        self.last_tab_input.borrow_mut().remove(&tab);
+        self.background_since.borrow_mut().remove(&tab);
+        self.tab_memory_pressure.borrow_mut().remove(&tab);
+        self.pinned.borrow_mut().remove(&tab);
+        self.requests.borrow_mut().remove(&tab);
+        self.audible.borrow_mut().remove(&tab);
+        self.burst_tabs.borrow_mut().remove(&tab);
+        if self.presentation_tab.get() == Some(tab) {
+            self.presentation_tab.set(None);
+        }
+        self.feedback.borrow_mut().per_tab.remove(&tab);
+    }
 
-                    let state = if allow || tab_recent {
-                        TabState::Background
-                    } else {
-                        TabState::Suspended
-                    };
+    /// Records a user interaction for the given tab.
+    ///
+    /// Input reaching a blurred or hidden window, such as the pointer
+    /// passing over it, is ignored so the user can still go idle.
+    pub fn record_user_input(&self, tab: TabId) {
+        if !self.window_focused.get() || !self.window_visible.get() {
+            return;
+        }
+        let now = self.clock.now();
+        self.mark_recent_input(tab, now);
+        self.reconcile(now);
+    }
 
-                    (state, ExecutionBudget { tier })
-                }
-            };
+    /// Records that the window showing `tab` gained focus.
+    ///
+    /// Focus is a stronger intent signal than in-page input: the user counts
+    /// as active and the tab's foreground budget is restored right away.
+    pub fn on_tab_focused(&self, tab: TabId) {
+        self.on_tab_focused_at(tab, self.clock.now());
+    }
 
-            // Memory pressure only demotes budgets; it never promotes.
-            // Foreground tabs stay protected unless pressure is severe.
-            budget.tier = match (pressure, budget.tier) {
-                (MemoryPressure::Low, tier) => tier,
-                (MemoryPressure::Moderate, BudgetTier::Foreground) => BudgetTier::Foreground,
-                (MemoryPressure::Moderate, BudgetTier::VisibleBackground) => BudgetTier::IdleBackground,
-                (MemoryPressure::Moderate, BudgetTier::IdleBackground) => BudgetTier::IdleBackground,
-                (MemoryPressure::Severe, BudgetTier::Foreground) => BudgetTier::VisibleBackground,
-                (MemoryPressure::Severe, BudgetTier::VisibleBackground) => BudgetTier::IdleBackground,
-                (MemoryPressure::Severe, BudgetTier::IdleBackground) => BudgetTier::IdleBackground,
-            };
+    /// Records that the window showing `tab` lost focus.
+    ///
+    /// Until [`ExecutionGovernor::on_tab_focused`], the active tab runs with
+    /// a background budget and in-page input no longer keeps the user active.
+    pub fn on_tab_blurred(&self, tab: TabId) {
+        self.on_tab_blurred_at(tab, self.clock.now());
+    }
 
-            // Budget tiers further gate effective state for background tabs.
-            if base_state == TabState::Background {
-                if budget.tier == BudgetTier::IdleBackground && !(user_idle && allow_idle_burst) && !tab_recent {
-                    effective = TabState::Suspended;
-                }
+    fn on_tab_focused_at(&self, tab: TabId, now: Instant) {
+        self.window_focused.set(true);
+        self.mark_recent_input(tab, now);
+        self.reconcile(now);
+    }
+
+    fn on_tab_blurred_at(&self, tab: TabId, now: Instant) {
+        self.window_focused.set(false);
+        // The tab lost the user's attention, so its input grace ends too.
+        self.last_tab_input.borrow_mut().remove(&tab);
+        self.reconcile(now);
+    }
+
+    /// Records whether the browser window is shown, e.g. after it is
+    /// minimized or restored.
+    ///
+    /// While hidden, the active tab runs as a background tab with an idle
+    /// budget; it is not frozen or suspended. Showing the window restores
+    /// its budget right away.
+    pub fn set_window_visible(&self, visible: bool) {
+        self.set_window_visible_at(visible, self.clock.now());
+    }
+
+    fn set_window_visible_at(&self, visible: bool, now: Instant) {
+        self.window_visible.set(visible);
+        self.reconcile(now);
+    }
+
+    /// Updates memory pressure. This only ever demotes budget tiers.
+    pub fn set_memory_pressure(&self, pressure: MemoryPressure) {
+        let previous = self.memory_pressure.replace(pressure);
+        if previous != pressure {
+            metrics::increment(Counter::PressureTransitions);
+            log::info!("memory pressure from={previous:?} to={pressure:?}");
+        }
+        self.reconcile(self.clock.now());
+    }
+
+    /// Returns the global memory pressure level.
+    pub fn memory_pressure(&self) -> MemoryPressure {
+        self.memory_pressure.get()
+    }
+
+    /// Overrides memory pressure for one tab, e.g. a tab with outsized RSS.
+    ///
+    /// The tab uses this level instead of the global one from
+    /// [`ExecutionGovernor::set_memory_pressure`]. Like the global level, it
+    /// only ever demotes the tab's budget tier.
+    pub fn set_tab_memory_pressure(&self, tab: TabId, pressure: MemoryPressure) {
+        let previous = self.tab_memory_pressure.borrow_mut().insert(tab, pressure);
+        if previous != Some(pressure) {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Returns a tab to the global memory pressure level.
+    pub fn clear_tab_memory_pressure(&self, tab: TabId) {
+        if self.tab_memory_pressure.borrow_mut().remove(&tab).is_some() {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Returns the memory pressure applied to a tab: its own level if set,
+    /// otherwise the global one.
+    pub fn memory_pressure_for(&self, tab: TabId) -> MemoryPressure {
+        self.tab_memory_pressure
+            .borrow()
+            .get(&tab)
+            .copied()
+            .unwrap_or_else(|| self.memory_pressure.get())
+    }
+
+    /// Marks a tab as pinned, protecting it from idle gating.
+    ///
+    /// Pinned background tabs keep the `VisibleBackground` tier and keep
+    /// running while the user is idle. Only severe memory pressure demotes
+    /// and suspends them.
+    pub fn set_tab_pinned(&self, tab: TabId, pinned: bool) {
+        let changed = if pinned {
+            self.pinned.borrow_mut().insert(tab)
+        } else {
+            self.pinned.borrow_mut().remove(&tab)
+        };
+        if changed {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Returns true when the tab is pinned.
+    pub fn is_pinned(&self, tab: TabId) -> bool {
+        self.pinned.borrow().contains(&tab)
+    }
+
+    /// Marks a tab as playing audio. Audible background tabs are not frozen
+    /// by intent or idle gating, but memory pressure can still suspend them.
+    pub fn set_tab_audible(&self, tab: TabId, audible: bool) {
+        let changed = if audible {
+            self.audible.borrow_mut().insert(tab)
+        } else {
+            self.audible.borrow_mut().remove(&tab)
+        };
+        if changed {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Computes what the governor would do with a tab right now, without
+    /// applying anything to the engine.
+    ///
+    /// Returns `None` for tabs the governor does not track. The result can
+    /// differ from the applied state until the next reconcile.
+    pub fn plan(&self, tab: TabId) -> Option<TabPlan> {
+        self.plan_at(tab, self.clock.now())
+    }
+
+    /// Computes what the governor would do with `tab` if it were in `state`
+    /// right now, whether or not the tab has been reported yet.
+    ///
+    /// This lets a new tab be sized, e.g. given a view with its tier's
+    /// memory limit, before it is reported through
+    /// [`JSExecutionGovernor::on_tab_state_changed`].
+    pub fn plan_for(&self, tab: TabId, state: TabState) -> TabPlan {
+        self.plan_in(tab, state, self.clock.now())
+    }
+
+    /// Explains why a tab is in its effective state, e.g. for a "this tab is
+    /// sleeping because" tooltip. Returns `None` for tabs the governor does
+    /// not track.
+    ///
+    /// Like [`ExecutionGovernor::plan`], this reflects the inputs right now,
+    /// which the applied state catches up with on the next reconcile.
+    pub fn explain(&self, tab: TabId) -> Option<StateReason> {
+        self.plan(tab).map(|plan| plan.reason)
+    }
+
+    /// Suspends a tab until [`ExecutionGovernor::clear_request`] or
+    /// [`ExecutionGovernor::request_resume`], regardless of focus, idleness,
+    /// or memory pressure.
+    pub fn request_suspend(&self, tab: TabId) {
+        self.set_request(tab, ExplicitRequest::Suspend);
+    }
+
+    /// Keeps a tab running until [`ExecutionGovernor::clear_request`] or
+    /// [`ExecutionGovernor::request_suspend`].
+    ///
+    /// An explicit resume overrides idle gating and memory pressure,
+    /// including severe pressure: the tab runs, but its budget tier and
+    /// hints stay demoted by the pressure. Only a presentation tab takes
+    /// precedence over explicit requests.
+    pub fn request_resume(&self, tab: TabId) {
+        self.set_request(tab, ExplicitRequest::Resume);
+    }
+
+    /// Drops an explicit suspend or resume, returning the tab to the
+    /// scheduler's heuristics.
+    pub fn clear_request(&self, tab: TabId) {
+        if self.requests.borrow_mut().remove(&tab).is_some() {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Explicitly suspends every tracked tab except the active, pinned,
+    /// audible and presentation tabs, reconciling once. Returns the tabs that
+    /// were not already under an explicit suspend, in id order.
+    pub fn suspend_all_background(&self) -> Vec<TabId> {
+        let presenting = self.presentation_tab.get();
+        let mut affected: Vec<TabId> = {
+            let audible = self.audible.borrow();
+            self.states
+                .borrow()
+                .iter()
+                .filter(|(tab, state)| {
+                    **state != TabState::Active
+                        && Some(**tab) != presenting
+                        && !self.is_pinned(**tab)
+                        && !audible.contains(*tab)
+                })
+                .map(|(tab, _)| *tab)
+                .collect()
+        };
+        affected.sort_unstable();
+        {
+            let mut requests = self.requests.borrow_mut();
+            affected.retain(|tab| {
+                requests.insert(*tab, ExplicitRequest::Suspend) != Some(ExplicitRequest::Suspend)
+            });
+        }
+        if !affected.is_empty() {
+            self.reconcile(self.clock.now());
+        }
+        affected
+    }
+
+    /// Drops every explicit suspend, returning those tabs to the scheduler's
+    /// heuristics with one reconcile. Returns them in id order.
+    pub fn wake_all(&self) -> Vec<TabId> {
+        let mut affected: Vec<TabId> = {
+            let mut requests = self.requests.borrow_mut();
+            let suspended = requests
+                .iter()
+                .filter(|(_, request)| **request == ExplicitRequest::Suspend)
+                .map(|(tab, _)| *tab)
+                .collect();
+            requests.retain(|_, request| *request != ExplicitRequest::Suspend);
+            suspended
+        };
+        affected.sort_unstable();
+        if !affected.is_empty() {
+            self.reconcile(self.clock.now());
+        }
+        affected
+    }
+
+    fn set_request(&self, tab: TabId, request: ExplicitRequest) {
+        if self.requests.borrow_mut().insert(tab, request) != Some(request) {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Marks a tab as presenting (e.g. fullscreen video or slides).
+    ///
+    /// The presentation tab always runs as Active with a Foreground budget,
+    /// regardless of focus, idleness, or memory pressure. Only one tab can
+    /// present at a time; `None` clears it.
+    pub fn set_presentation_tab(&self, tab: Option<TabId>) {
+        if self.presentation_tab.replace(tab) != tab {
+            self.reconcile(self.clock.now());
+        }
+    }
+
+    /// Returns the current presentation tab, if any.
+    pub fn presentation_tab(&self) -> Option<TabId> {
+        self.presentation_tab.get()
+    }
+
+    /// Polls engine feedback for a tab and stores it if it changed.
+    ///
+    /// Sampling is opportunistic and may be stale; this is observational only
+    /// and does not affect scheduling decisions. Explicit polls are not rate
+    /// limited, unlike the sampling done on state and budget changes.
+    pub fn poll_execution_feedback(&self, tab: TabId) {
+        self.sample_feedback(tab, self.clock.now());
+    }
+
+    /// Returns whether the tab's feedback has reported `js_blocking_render`
+    /// for at least `unresponsive_after` across consecutive samples.
+    pub fn is_unresponsive(&self, tab: TabId) -> bool {
+        self.feedback
+            .borrow()
+            .per_tab
+            .get(&tab)
+            .and_then(FeedbackRecord::blocking_for)
+            .is_some_and(|blocked_for| blocked_for >= self.config.unresponsive_after)
+    }
+
+    /// Returns the most recently sampled feedback for a tab.
+    ///
+    /// Feedback is sampled when the tab's state or budget changes, or by
+    /// [`ExecutionGovernor::poll_execution_feedback`]; `None` means it has
+    /// not been sampled yet. Like the rest of feedback, this may be stale.
+    pub fn latest_feedback(&self, tab: TabId) -> Option<EngineExecutionFeedback> {
+        self.feedback
+            .borrow()
+            .per_tab
+            .get(&tab)
+            .map(|record| record.feedback)
+    }
+
+    /// Returns the current budget, hints, and latest feedback for a tab.
+    #[cfg(feature = "serde")]
+    pub fn tab_telemetry(&self, tab: TabId) -> TabTelemetry {
+        TabTelemetry {
+            tab: tab.get(),
+            budget: self.budgets.borrow().get(&tab).copied(),
+            hints: self.hints.borrow().get(&tab).copied(),
+            feedback: self.latest_feedback(tab),
+        }
+    }
+
+    /// Returns [`ExecutionGovernor::tab_telemetry`] as a single JSON line,
+    /// without a trailing newline.
+    #[cfg(feature = "serde")]
+    pub fn telemetry_json_line(&self, tab: TabId) -> String {
+        serde_json::to_string(&self.tab_telemetry(tab))
+            .expect("telemetry snapshots always serialize")
+    }
+
+    /// Returns a read-only snapshot of stored execution feedback for a tab.
+    ///
+    /// The snapshot may be stale or incomplete and must not drive policy.
+    /// `None` indicates feedback has not been sampled for the tab yet.
+    #[cfg(feature = "diagnostics")]
+    pub fn get_execution_feedback(
+        &self,
+        tab: TabId,
+    ) -> Option<ExecutionFeedbackSnapshot<'_>> {
+        let state = self.feedback.borrow();
+        if !state.per_tab.contains_key(&tab) {
+            return None;
+        }
+        Some(ExecutionFeedbackSnapshot { state, tab })
+    }
+
+    /// Returns an aggregated, read-only snapshot of all stored feedback.
+    ///
+    /// This data is observational only and must not influence scheduling policy.
+    #[cfg(feature = "diagnostics")]
+    pub fn execution_feedback_snapshot(&self) -> ExecutionFeedbackAggregate<'_> {
+        ExecutionFeedbackAggregate {
+            state: self.feedback.borrow(),
+            now: self.clock.now(),
+        }
+    }
+
+    /// Polls the governor to refresh idle/burst state.
+    ///
+    /// Returns what the pass changed; see also [`ExecutionGovernor::last_report`].
+    pub fn poll(&self) -> ReconcileReport {
+        self.reconcile(self.clock.now())
+    }
+
+    /// Returns how long the caller can wait before the next [`ExecutionGovernor::poll`].
+    ///
+    /// This is `poll_interval` while the user is active or an idle burst is
+    /// open. Otherwise it is the time until the user goes idle or the next
+    /// burst is due, bounded by `poll_interval` and `idle_poll_interval`.
+    pub fn next_poll_delay(&self) -> Duration {
+        self.next_poll_delay_at(self.clock.now())
+    }
+
+    fn next_poll_delay_at(&self, now: Instant) -> Duration {
+        let activity = self.user_activity_at(now);
+        let (burst_open, _) = self.idle_burst_at(now, activity);
+        if activity == UserActivity::Active || burst_open {
+            return self.config.poll_interval;
+        }
+        let next_change = if activity == UserActivity::Idle {
+            self.last_idle_burst.get() + self.config.idle_burst_interval
+        } else {
+            self.last_global_input.get() + self.config.idle_threshold
+        };
+        next_change
+            .saturating_duration_since(now)
+            .min(self.config.idle_poll_interval)
+            .max(self.config.poll_interval)
+    }
+
+    /// Returns the report from the most recent reconcile pass.
+    ///
+    /// Trait methods such as [`JSExecutionGovernor::on_tab_state_changed`]
+    /// reconcile without returning anything; this surfaces their effect.
+    pub fn last_report(&self) -> ReconcileReport {
+        self.last_report.borrow().clone()
+    }
+
+    /// Counts tabs by the state last applied to the engine.
+    ///
+    /// Cheap and always available, unlike the `diagnostics` aggregates; tabs
+    /// not yet reconciled are not counted.
+    pub fn state_counts(&self) -> StateCounts {
+        let mut counts = StateCounts::default();
+        for state in self.effective_states.borrow().values() {
+            match state {
+                TabState::Active => counts.active += 1,
+                TabState::Background => counts.background += 1,
+                TabState::Frozen => counts.frozen += 1,
+                TabState::Suspended => counts.suspended += 1,
             }
+        }
+        counts
+    }
 
-            let budget_changed = self.apply_budget(tab, budget);
-            let hints = map_execution_hints(budget, pressure);
-            self.apply_hints(tab, hints);
+    /// Opens an idle burst immediately instead of waiting for the interval.
+    ///
+    /// Background tabs get a run opportunity right away. This is a no-op
+    /// unless the user is idle.
+    pub fn trigger_idle_burst(&self) {
+        self.trigger_idle_burst_at(self.clock.now());
+    }
 
-            let state_changed = if let Some(previous) = effective_states.get(&tab) {
-                *previous != effective
-            } else {
-                true
-            };
+    fn trigger_idle_burst_at(&self, now: Instant) {
+        if self.user_activity_at(now) != UserActivity::Idle {
+            return;
+        }
+        self.start_idle_burst(now);
+        self.reconcile(now);
+    }
+
+    /// Restarts the burst clock and picks the background tabs that run in
+    /// this burst, continuing round-robin after the previous burst's tabs.
+    fn start_idle_burst(&self, now: Instant) {
+        self.last_idle_burst.set(now);
+
+        // Pinned and audible tabs run while idle anyway, so they take no slot.
+        let mut candidates: Vec<TabId> = self
+            .states
+            .borrow()
+            .iter()
+            .filter(|(tab, state)| {
+                **state == TabState::Background
+                    && !self.is_pinned(**tab)
+                    && !self.audible.borrow().contains(*tab)
+            })
+            .map(|(tab, _)| *tab)
+            .collect();
+        candidates.sort();
+
+        let start = self
+            .burst_cursor
+            .get()
+            .map_or(0, |last| candidates.partition_point(|tab| *tab <= last));
+        let chosen: Vec<TabId> = candidates
+            .iter()
+            .cycle()
+            .skip(start)
+            .take(self.config.idle_burst_max_tabs.min(candidates.len()))
+            .copied()
+            .collect();
+        if let Some(last) = chosen.last() {
+            self.burst_cursor.set(Some(*last));
+        }
+        *self.burst_tabs.borrow_mut() = chosen.into_iter().collect();
+    }
+
+    fn mark_recent_input(&self, tab: TabId, now: Instant) {
+        self.last_global_input.set(now);
+        self.last_idle_burst.set(now);
+        self.last_tab_input.borrow_mut().insert(tab, now);
+    }
+
+    fn user_activity_at(&self, now: Instant) -> UserActivity {
+        let since_input = now.saturating_duration_since(self.last_global_input.get());
+        UserActivity::classify(since_input, &self.config)
+    }
+
+    /// Returns whether an idle burst is open at `now`, and whether it is a
+    /// new burst that should restart the burst clock.
+    fn idle_burst_at(&self, now: Instant, activity: UserActivity) -> (bool, bool) {
+        if activity != UserActivity::Idle {
+            return (false, false);
+        }
+        // When idle, allow short background bursts at a fixed interval.
+        let since_burst = now.duration_since(self.last_idle_burst.get());
+        if since_burst >= self.config.idle_burst_interval {
+            (true, true)
+        } else {
+            (since_burst <= self.config.idle_burst_duration, false)
+        }
+    }
+
+    fn plan_at(&self, tab: TabId, now: Instant) -> Option<TabPlan> {
+        let base_state = self.state(tab)?;
+        Some(self.plan_in(tab, base_state, now))
+    }
+
+    fn plan_in(&self, tab: TabId, base_state: TabState, now: Instant) -> TabPlan {
+        let activity = self.user_activity_at(now);
+        let (burst_open, _) = self.idle_burst_at(now, activity);
+        let inputs = self.plan_inputs(tab, base_state, now, activity, burst_open);
+        inputs.decide(&self.hint_policy.get())
+    }
+
+    fn plan_inputs(
+        &self,
+        tab: TabId,
+        base_state: TabState,
+        now: Instant,
+        activity: UserActivity,
+        burst_open: bool,
+    ) -> PlanInputs {
+        // Short grace window for tabs that were just interacted with.
+        let tab_recent = self
+            .last_tab_input
+            .borrow()
+            .get(&tab)
+            .is_some_and(|ts| now.duration_since(*ts) <= self.config.tab_input_grace);
+        let background_expired = self.background_since.borrow().get(&tab).is_some_and(|since| {
+            now.saturating_duration_since(*since) >= self.config.background_discard_after
+        });
+
+        PlanInputs {
+            base_state,
+            activity,
+            idle_burst: burst_open && self.burst_tabs.borrow().contains(&tab),
+            tab_recent,
+            focused: self.window_focused.get(),
+            window_visible: self.window_visible.get(),
+            pressure: self.memory_pressure_for(tab),
+            pinned: self.is_pinned(tab),
+            audible: self.audible.borrow().contains(&tab),
+            presenting: self.presentation_tab.get() == Some(tab),
+            background_expired,
+            script_pause: self.engine.supports_script_pause(),
+            request: self.requests.borrow().get(&tab).copied(),
+        }
+    }
+
+    fn reconcile(&self, now: Instant) -> ReconcileReport {
+        if self.notifying.get() {
+            return ReconcileReport::default();
+        }
+        metrics::increment(Counter::Reconciles);
+
+        // Intent is separate from tab lifecycle: tab state is owned by the tab manager,
+        // while intent reflects recent user interaction and can further gate background JS.
+        let activity = self.user_activity_at(now);
+        let (burst_open, burst_started) = self.idle_burst_at(now, activity);
+        if burst_started {
+            self.start_idle_burst(now);
+        }
+
+        let states_snapshot: Vec<(TabId, TabState)> =
+            self.states.borrow().iter().map(|(id, state)| (*id, *state)).collect();
+        {
+            // Only live tabs keep an input timestamp.
+            let states = self.states.borrow();
+            self.last_tab_input
+                .borrow_mut()
+                .retain(|tab, _| states.contains_key(tab));
+        }
+        let mut effective_states = self.effective_states.borrow_mut();
+        let mut transitions = Vec::new();
+        let mut report = ReconcileReport::default();
+
+        for (tab, base_state) in states_snapshot {
+            let plan = self
+                .plan_inputs(tab, base_state, now, activity, burst_open)
+                .decide(&self.hint_policy.get());
+            let effective = plan.state;
+
+            let budget_changed = self.apply_budget(tab, plan.budget);
+            if budget_changed {
+                report.budget_changes.push((tab, plan.budget.tier));
+            }
+            self.apply_hints(tab, plan.hints);
+
+            let previous = effective_states.get(&tab).copied();
+            let state_changed = previous != Some(effective);
 
             if state_changed {
                 self.engine.apply_tab_state(tab, effective);
                 effective_states.insert(tab, effective);
+                report.state_changes.push((tab, effective));
+                if let Some(previous) = previous {
+                    log::debug!("tab={tab} from={previous:?} to={effective:?}");
+                    transitions.push((tab, previous, effective));
+                }
             }
 
-            self.maybe_poll_feedback(tab, state_changed, budget_changed);
+            self.maybe_poll_feedback(tab, state_changed, budget_changed, now);
+        }
+
+        drop(effective_states);
+        self.notify_transitions(&transitions);
+
+        report.state_changes.sort_by_key(|(tab, _)| *tab);
+        report.budget_changes.sort_by_key(|(tab, _)| *tab);
+        *self.last_report.borrow_mut() = report.clone();
+        report
+    }
+
+    fn notify_transitions(&self, transitions: &[(TabId, TabState, TabState)]) {
+        if transitions.is_empty() {
+            return;
+        }
+
+        // Take the observer out so it may safely replace or clear itself while running.
+        let Some(observer) = self.transition_observer.borrow_mut().take() else {
+            return;
+        };
+        let generation = self.observer_generation.get();
+
+        self.notifying.set(true);
+        for (tab, old, new) in transitions {
+            observer(*tab, *old, *new);
+        }
+        self.notifying.set(false);
+
+        if self.observer_generation.get() == generation {
+            *self.transition_observer.borrow_mut() = Some(observer);
         }
     }
 
@@ -1002,12 +2174,14 @@ impl ExecutionGovernor {
         self.engine.apply_execution_hints(tab, hints);
     }
 
-    #[cfg(feature = "diagnostics")]
+    /// Samples feedback after a state or budget change, unless the tab was
+    /// sampled too recently. See [`SchedulerConfig::feedback_min_interval`].
     fn maybe_poll_feedback(
         &self,
         tab: TabId,
         state_changed: bool,
         budget_changed: bool,
+        now: Instant,
     ) {
         let should_sample = (state_changed
             && self
@@ -1018,81 +2192,99 @@ impl ExecutionGovernor {
                     .feedback_trigger
                     .should_sample(FeedbackSamplingEvent::BudgetTierChange));
 
-        if should_sample {
-            self.poll_execution_feedback(tab);
+        if !should_sample {
+            return;
+        }
+
+        let due = self.feedback.borrow().is_due(tab, now, &self.config);
+        if due {
+            self.sample_feedback(tab, now);
         }
     }
 
-    #[cfg(not(feature = "diagnostics"))]
-    fn maybe_poll_feedback(
-        &self,
-        _tab: TabId,
-        _state_changed: bool,
-        _budget_changed: bool,
-    ) {
+    fn sample_feedback(&self, tab: TabId, now: Instant) {
+        self.feedback
+            .borrow_mut()
+            .update_for_tab(tab, self.engine.as_ref(), now);
+        self.check_unresponsive(tab);
+    }
+
+    fn check_unresponsive(&self, tab: TabId) {
+        let blocked_for = {
+            let mut feedback = self.feedback.borrow_mut();
+            let Some(record) = feedback.per_tab.get_mut(&tab) else {
+                return;
+            };
+            match record.blocking_for() {
+                Some(blocked_for)
+                    if blocked_for >= self.config.unresponsive_after
+                        && !record.unresponsive_reported =>
+                {
+                    record.unresponsive_reported = true;
+                    blocked_for
+                }
+                _ => return,
+            }
+        };
+
+        // Take the observer out so it may safely replace or clear itself while running.
+        let Some(observer) = self.unresponsive_observer.borrow_mut().take() else {
+            return;
+        };
+        let generation = self.unresponsive_generation.get();
+        observer(tab, blocked_for);
+        if self.unresponsive_generation.get() == generation {
+            *self.unresponsive_observer.borrow_mut() = Some(observer);
+        }
     }
 }
 
 impl JSExecutionGovernor for ExecutionGovernor {
     fn set_budget(&self, tab: TabId, budget: ExecutionBudget) {
         let budget_changed = self.apply_budget(tab, budget);
-        let hints = map_execution_hints(budget, self.memory_pressure.get());
+        let pressure = self.memory_pressure_for(tab);
+        let hints = map_execution_hints_with(&self.hint_policy.get(), budget, pressure);
         self.apply_hints(tab, hints);
-        self.maybe_poll_feedback(tab, false, budget_changed);
+        self.maybe_poll_feedback(tab, false, budget_changed, self.clock.now());
     }
 
     fn on_tab_state_changed(&self, tab: TabId, state: TabState) {
+        let now = self.clock.now();
         self.states.borrow_mut().insert(tab, state);
         if state == TabState::Active {
             // Treat focus changes as intent signals.
-            self.mark_recent_input(tab, Instant::now());
+            self.mark_recent_input(tab, now);
+            self.background_since.borrow_mut().remove(&tab);
+        } else {
+            // Moving between background states keeps the original time.
+            self.background_since.borrow_mut().entry(tab).or_insert(now);
         }
-        self.reconcile(Instant::now());
+        self.reconcile(now);
     }
 }
 
 #[cfg(all(test, feature = "diagnostics"))]
 mod diagnostics_contract_tests {
     use super::*;
+    use crate::testing::RecordingEngine;
 
     /// Diagnostics are observational only and must not be referenced by
     /// scheduling or budget decision code. These tests validate that
     /// diagnostics helpers are read-only and deterministic.
-    #[derive(Debug)]
-    struct DummyEngine {
-        feedback: EngineExecutionFeedback,
-    }
-
-    impl DummyEngine {
-        fn new() -> Self {
-            Self {
-                feedback: EngineExecutionFeedback {
-                    has_long_tasks: true,
-                    worker_count: 2,
-                    wasm_active: true,
-                    js_blocking_render: false,
-                },
-            }
-        }
-    }
-
-    impl EngineFeedbackProvider for DummyEngine {
-        fn poll_execution_feedback(&self, _tab: TabId) -> EngineExecutionFeedback {
-            self.feedback
-        }
-    }
-
-    impl EngineScheduler for DummyEngine {
-        fn apply_tab_state(&self, _tab: TabId, _state: TabState) {}
-
-        fn apply_execution_budget(&self, _tab: TabId, _budget: ExecutionBudget) {}
-
-        fn apply_execution_hints(&self, _tab: TabId, _hints: ExecutionBudgetHints) {}
+    fn engine() -> Rc<RecordingEngine> {
+        let engine = Rc::new(RecordingEngine::new());
+        engine.set_feedback(EngineExecutionFeedback {
+            has_long_tasks: true,
+            worker_count: 2,
+            wasm_active: true,
+            js_blocking_render: false,
+        });
+        engine
     }
 
     #[test]
     fn debug_line_is_read_only_and_stable() {
-        let engine = Rc::new(DummyEngine::new());
+        let engine = engine();
         let governor = ExecutionGovernor::new(engine);
         let tab = TabId::new(1);
 
@@ -1112,7 +2304,7 @@ mod diagnostics_contract_tests {
 
     #[test]
     fn aggregate_report_is_read_only() {
-        let engine = Rc::new(DummyEngine::new());
+        let engine = engine();
         let governor = ExecutionGovernor::new(engine);
         let tab1 = TabId::new(1);
         let tab2 = TabId::new(2);
@@ -1139,3 +2331,998 @@ mod diagnostics_contract_tests {
         assert_eq!(snapshot.sample_count(), 1);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{EngineCall, MockClock, RecordingEngine};
+
+    /// Governor over a recording engine and a clock that only the test moves.
+    struct Harness {
+        engine: Rc<RecordingEngine>,
+        clock: Rc<MockClock>,
+        governor: ExecutionGovernor,
+    }
+
+    impl Harness {
+        fn new() -> Self {
+            Self::with_config(SchedulerConfig::default())
+        }
+
+        fn with_config(config: SchedulerConfig) -> Self {
+            let engine = Rc::new(RecordingEngine::new());
+            let clock = Rc::new(MockClock::new());
+            let governor =
+                ExecutionGovernor::with_clock(Rc::clone(&engine), config, Rc::clone(&clock));
+            Self {
+                engine,
+                clock,
+                governor,
+            }
+        }
+
+        /// Moves time forward and polls, like the app's poll timer.
+        fn advance(&self, by: Duration) -> ReconcileReport {
+            self.clock.advance(by);
+            self.governor.poll()
+        }
+
+        /// Returns the state the governor last applied to `tab`.
+        fn applied(&self, tab: TabId) -> Option<TabState> {
+            self.engine
+                .tab_states()
+                .into_iter()
+                .rev()
+                .find(|(id, _)| *id == tab)
+                .map(|(_, state)| state)
+        }
+
+        /// Returns the budget the governor last applied to `tab`.
+        fn budget(&self, tab: TabId) -> Option<ExecutionBudget> {
+            self.engine
+                .budgets()
+                .into_iter()
+                .rev()
+                .find(|(id, _)| *id == tab)
+                .map(|(_, budget)| budget)
+        }
+
+        /// Returns the hints the governor last applied to `tab`.
+        fn hints(&self, tab: TabId) -> Option<ExecutionBudgetHints> {
+            self.engine
+                .hints()
+                .into_iter()
+                .rev()
+                .find(|(id, _)| *id == tab)
+                .map(|(_, hints)| hints)
+        }
+    }
+
+    #[test]
+    fn user_activity_crosses_thresholds() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+
+        assert_eq!(governor.user_activity(), UserActivity::Active);
+        t.clock.advance(config.active_input_window);
+        assert_eq!(governor.user_activity(), UserActivity::Active);
+        t.clock.advance(Duration::from_millis(1));
+        assert_eq!(governor.user_activity(), UserActivity::Transitioning);
+        t.clock
+            .advance(config.idle_threshold - config.active_input_window - Duration::from_millis(2));
+        assert_eq!(governor.user_activity(), UserActivity::Transitioning);
+        t.clock.advance(Duration::from_millis(1));
+        assert_eq!(governor.user_activity(), UserActivity::Idle);
+    }
+
+    #[test]
+    fn user_input_resets_activity() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        t.clock.advance(config.idle_threshold);
+        assert_eq!(governor.user_activity(), UserActivity::Idle);
+
+        governor.record_user_input(TabId::new(1));
+        assert_eq!(governor.user_activity(), UserActivity::Active);
+        t.clock.advance(config.idle_threshold);
+        assert_eq!(governor.user_activity(), UserActivity::Idle);
+    }
+
+    #[test]
+    fn trigger_idle_burst_lets_background_tab_run() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        governor.set_memory_pressure(MemoryPressure::Severe);
+
+        t.advance(config.idle_threshold);
+        assert_eq!(governor.user_activity(), UserActivity::Idle);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+
+        governor.trigger_idle_burst();
+        assert_eq!(t.applied(tab), Some(TabState::Background));
+
+        t.advance(config.idle_burst_duration + Duration::from_millis(1));
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+    }
+
+    #[test]
+    fn poll_delay_slows_down_between_idle_bursts() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        governor.on_tab_state_changed(TabId::new(1), TabState::Background);
+        assert_eq!(governor.next_poll_delay(), config.poll_interval);
+
+        // Between active and idle, wake when the user would go idle.
+        t.clock.advance(config.idle_threshold - Duration::from_millis(500));
+        assert_eq!(governor.next_poll_delay(), Duration::from_millis(500));
+
+        // Idle with the next burst far off: the slow interval caps the delay.
+        t.advance(Duration::from_millis(500));
+        assert_eq!(governor.user_activity(), UserActivity::Idle);
+        let until_burst = config.idle_burst_interval - config.idle_threshold;
+        assert_eq!(
+            governor.next_poll_delay(),
+            until_burst.clamp(config.poll_interval, config.idle_poll_interval)
+        );
+
+        t.advance(until_burst);
+        assert_eq!(governor.next_poll_delay(), config.poll_interval);
+
+        t.clock.advance(config.idle_burst_duration + Duration::from_millis(1));
+        assert_eq!(governor.next_poll_delay(), config.idle_poll_interval);
+    }
+
+    #[test]
+    fn blur_drops_foreground_budget_until_focus_returns() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Active);
+        let tier = || t.budget(tab).map(|budget| budget.tier);
+
+        t.clock.advance(Duration::from_millis(10));
+        governor.on_tab_blurred(tab);
+        assert_eq!(tier(), Some(BudgetTier::VisibleBackground));
+        assert_eq!(t.applied(tab), Some(TabState::Active));
+
+        // Pointer motion over the blurred window is not intent.
+        t.clock.advance(config.idle_threshold);
+        governor.record_user_input(tab);
+        assert_eq!(governor.user_activity(), UserActivity::Idle);
+
+        governor.on_tab_focused(tab);
+        assert_eq!(tier(), Some(BudgetTier::Foreground));
+        assert_eq!(governor.user_activity(), UserActivity::Active);
+    }
+
+    #[test]
+    fn plan_for_sizes_unreported_tabs_by_window_state() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        let tier = |state| governor.plan_for(tab, state).budget.tier;
+
+        assert_eq!(tier(TabState::Active), BudgetTier::Foreground);
+        assert_eq!(tier(TabState::Frozen), BudgetTier::IdleBackground);
+
+        t.clock.advance(Duration::from_millis(10));
+        governor.on_tab_blurred(tab);
+        assert_eq!(tier(TabState::Active), BudgetTier::VisibleBackground);
+
+        governor.set_window_visible(false);
+        assert_eq!(tier(TabState::Active), BudgetTier::IdleBackground);
+
+        // Planning does not start tracking the tab.
+        assert_eq!(governor.state(tab), None);
+        assert!(t.engine.calls().is_empty());
+    }
+
+    #[test]
+    fn hidden_window_stays_demoted_despite_input() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Active);
+        let tier = || t.budget(tab).map(|budget| budget.tier);
+
+        t.clock.advance(Duration::from_millis(10));
+        governor.set_window_visible(false);
+        assert_eq!(tier(), Some(BudgetTier::IdleBackground));
+        assert_eq!(t.applied(tab), Some(TabState::Background));
+
+        // Input cannot promote a window nobody can see.
+        governor.record_user_input(tab);
+        assert_eq!(tier(), Some(BudgetTier::IdleBackground));
+        assert_eq!(t.applied(tab), Some(TabState::Background));
+
+        // Hidden for longer than the idle threshold still never freezes it.
+        t.advance(governor.config().idle_threshold);
+        assert_eq!(t.applied(tab), Some(TabState::Background));
+
+        governor.set_window_visible(true);
+        assert_eq!(tier(), Some(BudgetTier::Foreground));
+        assert_eq!(t.applied(tab), Some(TabState::Active));
+    }
+
+    #[test]
+    fn trigger_idle_burst_is_noop_while_active() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        t.engine.clear();
+
+        governor.trigger_idle_burst();
+
+        assert!(t.engine.calls().is_empty());
+        assert_eq!(governor.plan(tab).map(|plan| plan.state), Some(TabState::Frozen));
+    }
+
+    #[test]
+    fn removed_tabs_leave_no_input_entries() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let first = TabId::new(1);
+        let second = TabId::new(2);
+        governor.on_tab_state_changed(first, TabState::Active);
+        governor.on_tab_state_changed(second, TabState::Background);
+        governor.record_user_input(first);
+        governor.record_user_input(second);
+
+        governor.forget_tab(first);
+        governor.record_user_input(TabId::new(3));
+
+        let recent = |tab| governor.plan_for(tab, TabState::Background).inputs.tab_recent;
+        assert!(!recent(first));
+        assert!(recent(second));
+        assert!(!recent(TabId::new(3)));
+    }
+
+    #[test]
+    fn explain_names_the_deciding_branch() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let active = TabId::new(1);
+        let background = TabId::new(2);
+        let audible = TabId::new(3);
+        let requested = TabId::new(4);
+        governor.on_tab_state_changed(active, TabState::Active);
+        governor.on_tab_state_changed(background, TabState::Background);
+        governor.on_tab_state_changed(audible, TabState::Background);
+        governor.on_tab_state_changed(requested, TabState::Background);
+        governor.set_tab_audible(audible, true);
+        governor.request_suspend(requested);
+
+        assert_eq!(governor.explain(active), Some(StateReason::Foreground));
+        assert_eq!(governor.explain(background), Some(StateReason::UserActive));
+        assert_eq!(governor.explain(audible), Some(StateReason::AudiblePlaying));
+        assert_eq!(governor.explain(requested), Some(StateReason::ExplicitSuspend));
+        assert_eq!(governor.explain(TabId::new(9)), None);
+
+        governor.set_memory_pressure(MemoryPressure::Severe);
+        assert_eq!(
+            governor.explain(background),
+            Some(StateReason::MemoryPressureSevere)
+        );
+        governor.set_presentation_tab(Some(background));
+        assert_eq!(governor.explain(background), Some(StateReason::Presenting));
+    }
+
+    #[test]
+    fn suspend_all_spares_active_pinned_and_audible_tabs() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let active = TabId::new(1);
+        let pinned = TabId::new(2);
+        let audible = TabId::new(3);
+        let plain = TabId::new(4);
+        let other = TabId::new(5);
+        governor.on_tab_state_changed(active, TabState::Active);
+        for tab in [pinned, audible, plain, other] {
+            governor.on_tab_state_changed(tab, TabState::Background);
+        }
+        governor.set_tab_pinned(pinned, true);
+        governor.set_tab_audible(audible, true);
+        governor.request_suspend(other);
+
+        assert_eq!(governor.suspend_all_background(), vec![plain]);
+        assert_eq!(t.applied(plain), Some(TabState::Suspended));
+        assert_eq!(t.applied(active), Some(TabState::Active));
+        assert_eq!(t.applied(audible), Some(TabState::Background));
+        assert!(governor.suspend_all_background().is_empty());
+
+        assert_eq!(governor.wake_all(), vec![plain, other]);
+        assert_eq!(governor.explain(plain), Some(StateReason::UserActive));
+        assert!(governor.wake_all().is_empty());
+    }
+
+    #[test]
+    fn long_background_tabs_suspend_even_while_the_user_is_active() {
+        let t = Harness::with_config(SchedulerConfig {
+            background_discard_after: Duration::from_secs(30 * 60),
+            ..SchedulerConfig::default()
+        });
+        let governor = &t.governor;
+        let config = *governor.config();
+        let active = TabId::new(1);
+        let plain = TabId::new(2);
+        let pinned = TabId::new(3);
+        let audible = TabId::new(4);
+        governor.on_tab_state_changed(active, TabState::Active);
+        for tab in [plain, pinned, audible] {
+            governor.on_tab_state_changed(tab, TabState::Background);
+        }
+        governor.set_tab_pinned(pinned, true);
+        governor.set_tab_audible(audible, true);
+
+        t.clock.advance(config.background_discard_after - Duration::from_secs(1));
+        governor.record_user_input(active);
+        assert_eq!(t.applied(plain), Some(TabState::Frozen));
+
+        t.clock.advance(Duration::from_secs(1));
+        governor.record_user_input(active);
+        assert_eq!(governor.user_activity(), UserActivity::Active);
+        assert_eq!(t.applied(plain), Some(TabState::Suspended));
+        assert_eq!(governor.explain(plain), Some(StateReason::BackgroundExpired));
+        // Pinned tabs still defer to the active user, but are not suspended.
+        assert_eq!(t.applied(pinned), Some(TabState::Frozen));
+        assert_eq!(t.applied(audible), Some(TabState::Background));
+
+        // Visiting the tab restarts its background time.
+        governor.on_tab_state_changed(plain, TabState::Active);
+        governor.on_tab_state_changed(plain, TabState::Background);
+        t.advance(config.tab_input_grace + Duration::from_millis(1));
+        assert_eq!(governor.explain(plain), Some(StateReason::UserActive));
+    }
+
+    #[test]
+    fn is_overridden_reports_pressure_suspension() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let active = TabId::new(1);
+        let background = TabId::new(2);
+        governor.on_tab_state_changed(active, TabState::Active);
+        governor.on_tab_state_changed(background, TabState::Background);
+        governor.set_memory_pressure(MemoryPressure::Severe);
+
+        t.advance(governor.config().idle_threshold);
+
+        assert_eq!(t.applied(background), Some(TabState::Suspended));
+        assert!(governor.is_overridden(background));
+        assert!(!governor.is_overridden(active));
+        assert!(!governor.is_overridden(TabId::new(3)));
+    }
+
+    #[test]
+    fn moderate_pressure_freezes_when_scripts_can_pause() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        governor.set_memory_pressure(MemoryPressure::Moderate);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+
+        t.engine.set_script_pause(true);
+        governor.poll();
+        assert_eq!(t.applied(tab), Some(TabState::Frozen));
+        assert_eq!(
+            governor.explain(tab),
+            Some(StateReason::MemoryPressureModerate)
+        );
+
+        // Severe pressure still discards the page.
+        governor.set_memory_pressure(MemoryPressure::Severe);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+    }
+
+    #[test]
+    fn state_counts_follow_transitions() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let first = TabId::new(1);
+        let second = TabId::new(2);
+        let third = TabId::new(3);
+        assert_eq!(governor.state_counts(), StateCounts::default());
+
+        governor.on_tab_state_changed(first, TabState::Active);
+        governor.on_tab_state_changed(second, TabState::Background);
+        governor.on_tab_state_changed(third, TabState::Background);
+        // Background tabs that never saw input are frozen straight away.
+        assert_eq!(
+            governor.state_counts(),
+            StateCounts {
+                active: 1,
+                frozen: 2,
+                ..StateCounts::default()
+            }
+        );
+
+        governor.request_suspend(third);
+        governor.forget_tab(second);
+        let counts = governor.state_counts();
+        assert_eq!(
+            counts,
+            StateCounts {
+                active: 1,
+                suspended: 1,
+                ..StateCounts::default()
+            }
+        );
+        assert_eq!(counts.total(), 2);
+    }
+
+    #[test]
+    fn set_budgets_dedupes_engine_calls() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let first = TabId::new(1);
+        let second = TabId::new(2);
+        let visible = ExecutionBudget {
+            tier: BudgetTier::VisibleBackground,
+        };
+        let idle = ExecutionBudget {
+            tier: BudgetTier::IdleBackground,
+        };
+
+        governor.set_budgets(&[(first, idle), (second, visible), (first, visible)]);
+
+        assert_eq!(t.engine.budgets(), vec![(first, visible), (second, visible)]);
+        let hinted: Vec<TabId> = t.engine.hints().iter().map(|(tab, _)| *tab).collect();
+        assert_eq!(hinted, vec![first, second]);
+
+        t.engine.clear();
+        governor.set_budgets(&[(first, visible), (second, visible)]);
+        assert!(t.engine.calls().is_empty());
+    }
+
+    #[test]
+    fn backgrounding_a_tab_produces_expected_engine_calls() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Active);
+        t.engine.clear();
+
+        governor.on_tab_state_changed(tab, TabState::Background);
+
+        let budget = ExecutionBudget {
+            tier: BudgetTier::VisibleBackground,
+        };
+        assert_eq!(
+            t.engine.calls(),
+            vec![
+                EngineCall::Budget(tab, budget),
+                EngineCall::Hints(tab, map_execution_hints(budget, MemoryPressure::Low)),
+                // The tab was just focused, so its input grace keeps it running.
+                EngineCall::TabState(tab, TabState::Background),
+            ]
+        );
+    }
+
+    #[test]
+    fn swapping_the_hint_policy_reapplies_hints() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        let budget = ExecutionBudget {
+            tier: BudgetTier::VisibleBackground,
+        };
+        t.engine.clear();
+
+        let relaxed = HintPolicy::default().scale_timers(0.0);
+        governor.set_hint_policy(relaxed);
+        assert_eq!(t.engine.hints(), vec![(tab, relaxed.get(budget.tier, MemoryPressure::Low))]);
+        assert_eq!(t.engine.hints()[0].1.max_timer_frequency, None);
+
+        // Setting the same policy again is a no-op.
+        t.engine.clear();
+        governor.set_hint_policy(relaxed);
+        assert!(t.engine.calls().is_empty());
+
+        let aggressive = HintPolicy::default().scale_timers(2.0);
+        governor.set_hint_policy(aggressive);
+        let default = map_execution_hints(budget, MemoryPressure::Low);
+        assert_eq!(
+            t.engine.hints()[0].1.max_timer_frequency,
+            default.max_timer_frequency.map(|interval| interval * 2)
+        );
+    }
+
+    #[test]
+    fn idle_bursts_wake_a_bounded_rotating_set_of_tabs() {
+        let t = Harness::with_config(SchedulerConfig {
+            idle_burst_max_tabs: 3,
+            ..SchedulerConfig::default()
+        });
+        let governor = &t.governor;
+        let config = *governor.config();
+        let tabs: Vec<TabId> = (1..=7).map(TabId::new).collect();
+        for tab in &tabs {
+            governor.on_tab_state_changed(*tab, TabState::Background);
+        }
+        let woken = |report: ReconcileReport| -> Vec<TabId> {
+            report
+                .state_changes
+                .into_iter()
+                .filter(|(_, state)| *state == TabState::Background)
+                .map(|(tab, _)| tab)
+                .collect()
+        };
+
+        t.advance(config.idle_threshold);
+        assert!(tabs
+            .iter()
+            .all(|tab| t.applied(*tab) == Some(TabState::Frozen)));
+
+        let mut bursts = Vec::new();
+        for _ in 0..3 {
+            let report = t.advance(config.idle_burst_interval);
+            assert!(governor.state_counts().background <= config.idle_burst_max_tabs);
+            bursts.push(woken(report));
+        }
+
+        let ids = |ids: &[u64]| ids.iter().copied().map(TabId::new).collect::<Vec<_>>();
+        assert_eq!(bursts, vec![ids(&[1, 2, 3]), ids(&[4, 5, 6]), ids(&[1, 2, 7])]);
+    }
+
+    #[test]
+    fn reconcile_reports_changes_once() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        let active = TabId::new(1);
+        let background = TabId::new(2);
+        governor.on_tab_state_changed(active, TabState::Active);
+        governor.on_tab_state_changed(background, TabState::Background);
+
+        let report = t.advance(config.idle_threshold);
+        assert_eq!(
+            report,
+            ReconcileReport {
+                state_changes: Vec::new(),
+                budget_changes: vec![(background, BudgetTier::IdleBackground)],
+            }
+        );
+        assert_eq!(governor.last_report(), report);
+        assert_eq!(
+            governor.state_counts(),
+            StateCounts {
+                active: 1,
+                frozen: 1,
+                ..StateCounts::default()
+            }
+        );
+        assert!(governor.poll().is_empty());
+        assert!(governor.last_report().is_empty());
+
+        let report = t.advance(config.idle_burst_interval);
+        assert_eq!(
+            report.state_changes,
+            vec![(background, TabState::Background)]
+        );
+        assert!(report.budget_changes.is_empty());
+        assert_eq!(
+            governor.state_counts(),
+            StateCounts {
+                active: 1,
+                background: 1,
+                ..StateCounts::default()
+            }
+        );
+    }
+
+    #[test]
+    fn trait_calls_surface_their_report() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let first = TabId::new(1);
+        let second = TabId::new(2);
+        governor.on_tab_state_changed(second, TabState::Background);
+        governor.on_tab_state_changed(first, TabState::Active);
+
+        let report = governor.last_report();
+        assert_eq!(report.state_changes, vec![(first, TabState::Active)]);
+        assert_eq!(report.budget_changes, vec![(first, BudgetTier::Foreground)]);
+        assert_eq!(
+            governor.state_counts(),
+            StateCounts {
+                active: 1,
+                frozen: 1,
+                ..StateCounts::default()
+            }
+        );
+    }
+
+    #[test]
+    fn latest_feedback_is_sampled_on_state_change() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        assert_eq!(governor.latest_feedback(tab), None);
+
+        let feedback = EngineExecutionFeedback {
+            has_long_tasks: true,
+            worker_count: 3,
+            ..EngineExecutionFeedback::default()
+        };
+        t.engine.set_feedback(feedback);
+        governor.on_tab_state_changed(tab, TabState::Active);
+        assert_eq!(governor.latest_feedback(tab), Some(feedback));
+
+        governor.forget_tab(tab);
+        assert_eq!(governor.latest_feedback(tab), None);
+    }
+
+    #[test]
+    fn feedback_sampling_is_rate_limited_per_tab() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Active);
+
+        let busy = EngineExecutionFeedback {
+            has_long_tasks: true,
+            ..EngineExecutionFeedback::default()
+        };
+        t.engine.set_feedback(busy);
+        t.clock
+            .advance(config.feedback_min_interval - Duration::from_millis(1));
+        governor.on_tab_state_changed(tab, TabState::Background);
+        assert_eq!(
+            governor.latest_feedback(tab),
+            Some(EngineExecutionFeedback::default())
+        );
+
+        t.clock.advance(Duration::from_millis(1));
+        governor.on_tab_state_changed(tab, TabState::Active);
+        assert_eq!(governor.latest_feedback(tab), Some(busy));
+    }
+
+    #[test]
+    fn stable_feedback_backs_off_to_the_max_interval() {
+        let config = SchedulerConfig::default();
+        let mut record = FeedbackRecord::new(EngineExecutionFeedback::default(), Instant::now());
+        let interval_after = |record: &mut FeedbackRecord, stable: u32| {
+            record.stable_samples = stable;
+            record.sample_interval(&config)
+        };
+
+        let min = config.feedback_min_interval;
+        assert_eq!(interval_after(&mut record, 0), min);
+        let backoff_after = config.feedback_backoff_after;
+        assert_eq!(interval_after(&mut record, backoff_after - 1), min);
+        assert_eq!(interval_after(&mut record, backoff_after), min * 2);
+        assert_eq!(interval_after(&mut record, backoff_after + 1), min * 4);
+        assert_eq!(
+            interval_after(&mut record, u32::MAX),
+            config.feedback_max_interval
+        );
+
+        record.update(
+            EngineExecutionFeedback {
+                wasm_active: true,
+                ..EngineExecutionFeedback::default()
+            },
+            Instant::now(),
+        );
+        assert_eq!(record.sample_interval(&config), min);
+    }
+
+    #[test]
+    fn sustained_blocking_reports_unresponsive_once() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        let tab = TabId::new(1);
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&reported);
+        governor.set_unresponsive_observer(Box::new(move |tab, blocked_for| {
+            sink.borrow_mut().push((tab, blocked_for));
+        }));
+        let sample_after = |by: Duration, feedback: EngineExecutionFeedback| {
+            t.clock.advance(by);
+            t.engine.set_feedback(feedback);
+            governor.poll_execution_feedback(tab);
+        };
+
+        let blocking = EngineExecutionFeedback {
+            js_blocking_render: true,
+            ..EngineExecutionFeedback::default()
+        };
+        sample_after(Duration::ZERO, blocking);
+        // A single blocking sample is never enough, however long ago it was.
+        assert!(!governor.is_unresponsive(tab));
+
+        sample_after(config.unresponsive_after, EngineExecutionFeedback::default());
+        sample_after(config.unresponsive_after, blocking);
+        assert!(!governor.is_unresponsive(tab));
+        assert!(reported.borrow().is_empty());
+
+        sample_after(config.unresponsive_after, blocking);
+        assert!(governor.is_unresponsive(tab));
+        sample_after(config.feedback_min_interval, blocking);
+        assert_eq!(*reported.borrow(), vec![(tab, config.unresponsive_after)]);
+
+        sample_after(
+            config.unresponsive_after - config.feedback_min_interval,
+            EngineExecutionFeedback::default(),
+        );
+        assert!(!governor.is_unresponsive(tab));
+    }
+
+    #[test]
+    fn tab_memory_pressure_overrides_the_global_level() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let heavy = TabId::new(1);
+        let light = TabId::new(2);
+        governor.on_tab_state_changed(heavy, TabState::Background);
+        governor.on_tab_state_changed(light, TabState::Background);
+        governor.set_memory_pressure(MemoryPressure::Moderate);
+        governor.set_tab_memory_pressure(heavy, MemoryPressure::Severe);
+        governor.set_tab_memory_pressure(light, MemoryPressure::Low);
+
+        t.advance(governor.config().idle_threshold);
+        assert_eq!(t.applied(heavy), Some(TabState::Suspended));
+        assert_eq!(t.applied(light), Some(TabState::Frozen));
+
+        governor.clear_tab_memory_pressure(light);
+        assert_eq!(
+            governor.memory_pressure_for(light),
+            MemoryPressure::Moderate
+        );
+        governor.forget_tab(heavy);
+        assert_eq!(
+            governor.memory_pressure_for(heavy),
+            MemoryPressure::Moderate
+        );
+    }
+
+    #[test]
+    fn tab_memory_pressure_only_demotes() {
+        let rank = |tier: BudgetTier| match tier {
+            BudgetTier::Foreground => 0,
+            BudgetTier::VisibleBackground => 1,
+            BudgetTier::IdleBackground => 2,
+        };
+        let pressures = [
+            MemoryPressure::Low,
+            MemoryPressure::Moderate,
+            MemoryPressure::Severe,
+        ];
+
+        for state in [TabState::Active, TabState::Background] {
+            let mut previous = None;
+            for pressure in pressures {
+                let t = Harness::new();
+                let tab = TabId::new(1);
+                t.governor.on_tab_state_changed(tab, state);
+                t.governor.set_tab_memory_pressure(tab, pressure);
+
+                let tier = t.budget(tab).expect("budget applied").tier;
+                if let Some(previous) = previous {
+                    assert!(
+                        rank(tier) >= rank(previous),
+                        "{state:?} promoted at {pressure:?}"
+                    );
+                }
+                previous = Some(tier);
+            }
+        }
+    }
+
+    #[test]
+    fn pinned_background_tab_survives_idle() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let config = *governor.config();
+        let pinned = TabId::new(1);
+        let unpinned = TabId::new(2);
+        governor.on_tab_state_changed(pinned, TabState::Background);
+        governor.on_tab_state_changed(unpinned, TabState::Background);
+        governor.set_tab_pinned(pinned, true);
+        governor.set_memory_pressure(MemoryPressure::Moderate);
+
+        // Moderate pressure demotes the unpinned tab as soon as input stops.
+        let input_stopped = config.active_input_window + Duration::from_millis(1);
+        t.advance(input_stopped);
+        assert_eq!(t.applied(pinned), Some(TabState::Background));
+        assert_eq!(t.applied(unpinned), Some(TabState::Suspended));
+
+        t.advance(config.idle_threshold - input_stopped);
+        assert_eq!(t.applied(pinned), Some(TabState::Background));
+        assert_eq!(
+            t.budget(pinned).map(|budget| budget.tier),
+            Some(BudgetTier::VisibleBackground)
+        );
+        assert_ne!(t.applied(unpinned), Some(TabState::Background));
+    }
+
+    #[test]
+    fn severe_pressure_suspends_pinned_tabs() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        governor.set_tab_pinned(tab, true);
+        governor.set_memory_pressure(MemoryPressure::Severe);
+
+        t.advance(governor.config().idle_threshold);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+
+        governor.set_tab_pinned(tab, false);
+        governor.forget_tab(tab);
+        assert!(!governor.is_pinned(tab));
+    }
+
+    #[test]
+    fn explicit_suspend_overrides_focus() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Active);
+
+        governor.request_suspend(tab);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+        assert!(governor.is_overridden(tab));
+
+        governor.clear_request(tab);
+        assert_eq!(t.applied(tab), Some(TabState::Active));
+    }
+
+    #[test]
+    fn explicit_resume_runs_under_severe_pressure() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Suspended);
+        governor.set_memory_pressure(MemoryPressure::Severe);
+        governor.request_resume(tab);
+
+        t.advance(governor.config().idle_threshold);
+        assert_eq!(t.applied(tab), Some(TabState::Background));
+        assert_eq!(
+            t.budget(tab).map(|budget| budget.tier),
+            Some(BudgetTier::IdleBackground)
+        );
+
+        governor.request_suspend(tab);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+        // Forgetting the tab drops its request along with it.
+        governor.forget_tab(tab);
+        governor.on_tab_state_changed(tab, TabState::Active);
+        assert_eq!(governor.explain(tab), Some(StateReason::Foreground));
+    }
+
+    #[test]
+    fn plan_matches_reconcile_without_engine_calls() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        governor.set_memory_pressure(MemoryPressure::Severe);
+        let calls = t.engine.calls();
+
+        t.clock.advance(governor.config().idle_threshold);
+        let plan = governor.plan(tab).expect("tracked tab");
+        assert_eq!(t.engine.calls(), calls);
+        assert_eq!(plan.state, TabState::Suspended);
+        assert_eq!(plan.inputs.activity, UserActivity::Idle);
+        assert_eq!(plan.inputs.pressure, MemoryPressure::Severe);
+        assert!(!plan.inputs.tab_recent);
+
+        governor.poll();
+        assert_eq!(t.applied(tab), Some(plan.state));
+        assert_eq!(t.budget(tab), Some(plan.budget));
+        assert_eq!(t.hints(tab), Some(plan.hints));
+        assert_eq!(governor.plan(TabId::new(2)), None);
+    }
+
+    #[test]
+    fn audible_background_tab_keeps_running_while_idle() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        governor.set_tab_audible(tab, true);
+
+        t.advance(governor.config().idle_threshold);
+        assert_eq!(t.applied(tab), Some(TabState::Background));
+
+        governor.set_memory_pressure(MemoryPressure::Severe);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+    }
+
+    #[test]
+    fn presentation_tab_is_never_throttled() {
+        let t = Harness::new();
+        let governor = &t.governor;
+        let tab = TabId::new(1);
+        governor.on_tab_state_changed(tab, TabState::Background);
+        governor.set_memory_pressure(MemoryPressure::Severe);
+        governor.set_presentation_tab(Some(tab));
+
+        t.advance(governor.config().idle_threshold);
+
+        let foreground = ExecutionBudget {
+            tier: BudgetTier::Foreground,
+        };
+        assert_eq!(t.applied(tab), Some(TabState::Active));
+        assert_eq!(t.budget(tab), Some(foreground));
+        assert_eq!(
+            t.hints(tab),
+            Some(map_execution_hints(foreground, MemoryPressure::Low))
+        );
+
+        governor.set_presentation_tab(None);
+        assert_eq!(governor.presentation_tab(), None);
+        assert_eq!(t.applied(tab), Some(TabState::Suspended));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use super::*;
+    use crate::testing::RecordingEngine;
+
+    fn round_trip<T>(value: T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        let json = serde_json::to_string(&value).expect("serialize");
+        serde_json::from_str(&json).expect("deserialize")
+    }
+
+    #[test]
+    fn hints_and_budgets_round_trip() {
+        for tier in [
+            BudgetTier::Foreground,
+            BudgetTier::VisibleBackground,
+            BudgetTier::IdleBackground,
+        ] {
+            let budget = ExecutionBudget { tier };
+            assert_eq!(round_trip(budget), budget);
+            for pressure in [
+                MemoryPressure::Low,
+                MemoryPressure::Moderate,
+                MemoryPressure::Severe,
+            ] {
+                assert_eq!(round_trip(pressure), pressure);
+                let hints = map_execution_hints(budget, pressure);
+                assert_eq!(round_trip(hints), hints);
+            }
+        }
+
+        let feedback = EngineExecutionFeedback {
+            worker_count: 4,
+            js_blocking_render: true,
+            ..EngineExecutionFeedback::default()
+        };
+        assert_eq!(round_trip(feedback), feedback);
+    }
+
+    #[test]
+    fn telemetry_line_is_single_line_json() {
+        let governor = ExecutionGovernor::new(Rc::new(RecordingEngine::new()));
+        let tab = TabId::new(7);
+        governor.on_tab_state_changed(tab, TabState::Active);
+
+        let line = governor.telemetry_json_line(tab);
+        assert!(!line.contains('\n'));
+
+        let value: serde_json::Value = serde_json::from_str(&line).expect("valid json");
+        assert_eq!(value["tab"], 7);
+        assert_eq!(value["budget"]["tier"], "Foreground");
+        assert_eq!(value["hints"]["allow_background_js"], true);
+        assert_eq!(value["feedback"]["worker_count"], 0);
+    }
+}
@@ -0,0 +1,133 @@
+//! Prometheus text exposition of the governor's current view of its tabs.
+
+use std::fmt::Write;
+
+use tabs::TabState;
+
+use crate::{BudgetTier, ExecutionGovernor, MemoryPressure};
+
+const STATES: [(TabState, &str); 4] = [
+    (TabState::Active, "active"),
+    (TabState::Background, "background"),
+    (TabState::Frozen, "frozen"),
+    (TabState::Suspended, "suspended"),
+];
+
+const TIERS: [(BudgetTier, &str); 3] = [
+    (BudgetTier::Foreground, "foreground"),
+    (BudgetTier::VisibleBackground, "visible_background"),
+    (BudgetTier::IdleBackground, "idle_background"),
+];
+
+const PRESSURES: [(MemoryPressure, &str); 3] = [
+    (MemoryPressure::Low, "low"),
+    (MemoryPressure::Moderate, "moderate"),
+    (MemoryPressure::Severe, "severe"),
+];
+
+impl ExecutionGovernor {
+    /// Renders current counts in the Prometheus text exposition format.
+    ///
+    /// Every label value is always present, with zero counts included, so
+    /// series do not appear and disappear between scrapes. Feedback counts
+    /// are only exported with the `diagnostics` feature.
+    pub fn metrics(&self) -> String {
+        let mut out = String::new();
+
+        let effective = self.effective_states.borrow();
+        write_header(&mut out, "owl_tabs", "Tracked tabs per effective state.");
+        for (state, label) in STATES {
+            let count = effective.values().filter(|value| **value == state).count();
+            let _ = writeln!(out, "owl_tabs{{state=\"{label}\"}} {count}");
+        }
+        drop(effective);
+
+        let budgets = self.budgets.borrow();
+        write_header(&mut out, "owl_tab_budgets", "Tracked tabs per budget tier.");
+        for (tier, label) in TIERS {
+            let count = budgets
+                .values()
+                .filter(|budget| budget.tier == tier)
+                .count();
+            let _ = writeln!(out, "owl_tab_budgets{{tier=\"{label}\"}} {count}");
+        }
+        drop(budgets);
+
+        let pressure = self.memory_pressure.get();
+        write_header(
+            &mut out,
+            "owl_memory_pressure",
+            "Current memory pressure level; 1 for the active level.",
+        );
+        for (level, label) in PRESSURES {
+            let value = u8::from(level == pressure);
+            let _ = writeln!(out, "owl_memory_pressure{{level=\"{label}\"}} {value}");
+        }
+
+        #[cfg(feature = "diagnostics")]
+        {
+            let feedback = self.feedback.borrow();
+            let long_tasks = feedback
+                .per_tab
+                .values()
+                .filter(|record| record.feedback.has_long_tasks)
+                .count();
+            let wasm_active = feedback
+                .per_tab
+                .values()
+                .filter(|record| record.feedback.wasm_active)
+                .count();
+            write_header(
+                &mut out,
+                "owl_feedback_long_tasks",
+                "Tabs whose latest feedback reports long tasks.",
+            );
+            let _ = writeln!(out, "owl_feedback_long_tasks {long_tasks}");
+            write_header(
+                &mut out,
+                "owl_feedback_wasm_active",
+                "Tabs whose latest feedback reports active WebAssembly.",
+            );
+            let _ = writeln!(out, "owl_feedback_wasm_active {wasm_active}");
+        }
+
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} gauge");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use tabs::{TabId, TabState};
+
+    use crate::testing::RecordingEngine;
+    use crate::{ExecutionGovernor, JSExecutionGovernor, MemoryPressure};
+
+    #[test]
+    fn renders_counts_with_type_lines() {
+        let governor = ExecutionGovernor::new(Rc::new(RecordingEngine::new()));
+        governor.on_tab_state_changed(TabId::new(1), TabState::Background);
+        governor.on_tab_state_changed(TabId::new(2), TabState::Active);
+        governor.set_memory_pressure(MemoryPressure::Moderate);
+
+        let metrics = governor.metrics();
+        let lines: Vec<&str> = metrics.lines().collect();
+
+        assert!(lines.contains(&"# TYPE owl_tabs gauge"));
+        assert!(lines.contains(&"owl_tabs{state=\"active\"} 1"));
+        assert!(lines.contains(&"owl_tabs{state=\"background\"} 0"));
+        // Moderate pressure demotes the background tab while the user is active.
+        assert!(lines.contains(&"owl_tabs{state=\"suspended\"} 1"));
+        assert!(lines.contains(&"owl_tab_budgets{tier=\"foreground\"} 1"));
+        assert!(lines.contains(&"owl_tab_budgets{tier=\"idle_background\"} 1"));
+        assert!(lines.contains(&"owl_memory_pressure{level=\"moderate\"} 1"));
+        assert!(lines.contains(&"owl_memory_pressure{level=\"low\"} 0"));
+        assert!(metrics.ends_with('\n'));
+    }
+}
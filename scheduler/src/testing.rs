@@ -0,0 +1,151 @@
+//! Test doubles for exercising the governor's engine interactions.
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+use tabs::{TabId, TabState};
+
+use crate::{
+    Clock, EngineExecutionFeedback, EngineFeedbackProvider, EngineScheduler, ExecutionBudget,
+    ExecutionBudgetHints,
+};
+
+/// A single call received by [`RecordingEngine`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EngineCall {
+    TabState(TabId, TabState),
+    Budget(TabId, ExecutionBudget),
+    Hints(TabId, ExecutionBudgetHints),
+}
+
+/// Engine that records every scheduler call in order.
+///
+/// Feedback polls return a fixed value set with
+/// [`RecordingEngine::set_feedback`] and are not recorded as calls.
+#[derive(Debug, Default)]
+pub struct RecordingEngine {
+    calls: RefCell<Vec<EngineCall>>,
+    feedback: Cell<EngineExecutionFeedback>,
+    script_pause: Cell<bool>,
+}
+
+impl RecordingEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns every call received so far, oldest first.
+    pub fn calls(&self) -> Vec<EngineCall> {
+        self.calls.borrow().clone()
+    }
+
+    /// Returns the `apply_tab_state` calls, oldest first.
+    pub fn tab_states(&self) -> Vec<(TabId, TabState)> {
+        self.calls
+            .borrow()
+            .iter()
+            .filter_map(|call| match *call {
+                EngineCall::TabState(tab, state) => Some((tab, state)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the `apply_execution_budget` calls, oldest first.
+    pub fn budgets(&self) -> Vec<(TabId, ExecutionBudget)> {
+        self.calls
+            .borrow()
+            .iter()
+            .filter_map(|call| match *call {
+                EngineCall::Budget(tab, budget) => Some((tab, budget)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns the `apply_execution_hints` calls, oldest first.
+    pub fn hints(&self) -> Vec<(TabId, ExecutionBudgetHints)> {
+        self.calls
+            .borrow()
+            .iter()
+            .filter_map(|call| match *call {
+                EngineCall::Hints(tab, hints) => Some((tab, hints)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Forgets all recorded calls.
+    pub fn clear(&self) {
+        self.calls.borrow_mut().clear();
+    }
+
+    /// Sets the feedback returned for every tab.
+    pub fn set_feedback(&self, feedback: EngineExecutionFeedback) {
+        self.feedback.set(feedback);
+    }
+
+    /// Sets what [`EngineScheduler::supports_script_pause`] reports.
+    pub fn set_script_pause(&self, supported: bool) {
+        self.script_pause.set(supported);
+    }
+}
+
+impl EngineFeedbackProvider for RecordingEngine {
+    fn poll_execution_feedback(&self, _tab: TabId) -> EngineExecutionFeedback {
+        self.feedback.get()
+    }
+}
+
+impl EngineScheduler for RecordingEngine {
+    fn apply_tab_state(&self, tab: TabId, state: TabState) {
+        self.calls.borrow_mut().push(EngineCall::TabState(tab, state));
+    }
+
+    fn apply_execution_budget(&self, tab: TabId, budget: ExecutionBudget) {
+        self.calls.borrow_mut().push(EngineCall::Budget(tab, budget));
+    }
+
+    fn apply_execution_hints(&self, tab: TabId, hints: ExecutionBudgetHints) {
+        self.calls.borrow_mut().push(EngineCall::Hints(tab, hints));
+    }
+
+    fn supports_script_pause(&self) -> bool {
+        self.script_pause.get()
+    }
+}
+
+/// Clock that only moves when told to.
+///
+/// Share it with the governor through an `Rc` and call
+/// [`MockClock::advance`] to step time forward.
+#[derive(Debug)]
+pub struct MockClock {
+    now: Cell<Instant>,
+}
+
+impl MockClock {
+    /// Starts the clock at the current system time.
+    pub fn new() -> Self {
+        Self {
+            now: Cell::new(Instant::now()),
+        }
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
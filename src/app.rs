@@ -1,14 +1,51 @@
 use crate::assets::Assets;
-use crate::ipc::{self, IncomingMessage, NavState};
-use crate::state::BrowserState;
+use crate::crash::CrashCounts;
+use crate::dialogs::ScriptDialogs;
+use crate::downloads::Downloads;
+use crate::import;
+use crate::inflight::InFlight;
+use crate::internal;
+use crate::ipc::{self, ForceDarkRule, IncomingCommand, NavState};
+use crate::permissions::PermissionPrompts;
+use crate::session::{self, SessionAutosave, SleepInhibitor};
 use adw::prelude::*;
-use gtk::glib;
-use std::cell::RefCell;
-use std::collections::HashSet;
+use engine::{EngineController, FindOptions, ProxyConfig, SnapshotRegion, WebKitEngine};
+use gtk::{gio, glib};
+use memory::glue;
+use memory::pressure::{
+    DefaultMemoryPressureSource, MemoryPressureMonitorConfig, MemoryPressureReceiver,
+    MemoryPressureThresholds,
+};
+use scheduler::{ExecutionGovernor, HintPolicy, JSExecutionGovernor, MemoryPressure};
+use serde_json::{json, Map, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 use std::net::IpAddr;
+use std::path::PathBuf;
+use storage::file::FileSessionStore;
+use storage::bookmarks::FileBookmarkStore;
+use storage::favicons::FileFaviconCache;
+use storage::hibernation::{Hibernation, HibernationSnapshot};
+use storage::history::FileHistoryStore;
+use storage::journal::SessionJournal;
+use storage::library::{session_slug, FileSessionLibrary};
+use storage::permissions::FilePermissionStore;
+use storage::settings::{
+    proxy_uri, FileSettingsStore, InvalidSetting, NewTabPage, ProxyMode, Settings, TimerClamp,
+    DEFAULT_SEARCH_TEMPLATE,
+};
+use storage::{
+    Bookmark, BookmarkStore, FaviconCache, HistoryStore, SavedSession, SavedTab, SessionLibrary,
+    SessionSnapshot,
+};
+use tabs::redirect::RedirectChain;
+use tabs::state::{BrowserState, SessionState, SessionTab};
+use tabs::TabId;
+use ui::suggest::{self, SuggestionKind};
 use url::Url;
+use util::metrics::{self, Counter};
 use webkit6::prelude::*;
 
 const APP_ID: &str = "com.owl.browser";
@@ -17,6 +54,362 @@ const SIDEBAR_EXPANDED: i32 = 300;
 const SIDEBAR_COLLAPSED: i32 = 60;
 const SIDEBAR_COLLAPSE_THRESHOLD: i32 = 2;
 const SIDEBAR_RESIZE_IDLE_MS: u64 = 120;
+const HISTORY_QUERY_LIMIT: usize = 8;
+const HISTORY_PAGE_LIMIT: usize = 200;
+const DEFAULT_TAB_CAP: usize = 30;
+const PREFETCH_DEBOUNCE_MS: u64 = 150;
+/// Handle to a browser window used to route remote open requests.
+#[derive(Clone)]
+struct WindowHandle {
+    window: adw::ApplicationWindow,
+    state: Rc<RefCell<BrowserState>>,
+    state_sync: Rc<ipc::StateSync>,
+    open_url: Rc<dyn Fn(&str)>,
+}
+
+/// Engine access for the content view.
+///
+/// All tabs share the window's shown content view, so only the active tab is
+/// registered with the engine and the governor; `bind` moves the registration
+/// when the active tab changes.
+struct ContentEngine {
+    engine: Rc<WebKitEngine>,
+    governor: Rc<ExecutionGovernor>,
+    view: RefCell<webkit6::WebView>,
+    bound: Cell<Option<TabId>>,
+    profile: Rc<Profile>,
+}
+
+impl ContentEngine {
+    fn new(view: webkit6::WebView, profile: Rc<Profile>) -> Self {
+        let engine = Rc::new(WebKitEngine::new());
+        // Probes and logs the capabilities once, up front.
+        engine.capabilities();
+        Self {
+            governor: Rc::new(ExecutionGovernor::new(Rc::clone(&engine))),
+            engine,
+            view: RefCell::new(view),
+            bound: Cell::new(None),
+            profile,
+        }
+    }
+
+    /// Points the engine at `view`, dropping the current registration so the
+    /// next `bind` registers the active tab with the new view.
+    fn set_view(&self, view: &webkit6::WebView) {
+        if let Some(previous) = self.bound.take() {
+            self.governor.forget_tab(previous);
+            self.engine.unregister_view(previous);
+        }
+        *self.view.borrow_mut() = view.clone();
+    }
+
+    fn bind(&self, active: Option<u64>) -> Option<TabId> {
+        let tab = active.map(TabId::new);
+        if self.bound.get() != tab {
+            if let Some(previous) = self.bound.replace(tab) {
+                self.governor.forget_tab(previous);
+                self.engine.unregister_view(previous);
+            }
+            if let Some(tab) = tab {
+                self.engine.register_view(tab, &self.view.borrow());
+                self.governor.on_tab_state_changed(tab, tabs::TabState::Active);
+            }
+        }
+        tab
+    }
+
+    /// Binds the active tab and applies its per-tab view settings.
+    ///
+    /// Takes the `RefCell` rather than a borrow because applying settings can
+    /// emit view notifications whose handlers update the state.
+    fn sync_active(&self, state: &RefCell<BrowserState>) -> Option<TabId> {
+        let (active, muted, pinned, audible, reader, zoom) = {
+            let state = state.borrow();
+            let node = state.active.and_then(|id| state.tabs.get(&id));
+            let muted = node.is_some_and(|node| node.is_muted);
+            let pinned = node.is_some_and(|node| node.is_pinned);
+            let audible = node.is_some_and(|node| node.is_audible);
+            let reader = node.is_some_and(|node| node.is_reader && !node.url.starts_with("owl://"));
+            let zoom = node.map_or(100, |node| node.zoom_percent);
+            (state.active, muted, pinned, audible, reader, zoom)
+        };
+        let tab = self.bind(active)?;
+        self.governor.set_tab_pinned(tab, pinned);
+        self.governor.set_tab_audible(tab, audible);
+        self.engine.set_muted(tab, muted);
+        self.engine.set_reader_mode(tab, reader);
+        let zoom_text_only = self.profile.settings.borrow().get().zoom_text_only;
+        self.engine.set_zoom_text_only(tab, zoom_text_only);
+        self.engine.set_zoom_level(tab, f64::from(zoom) / 100.0);
+        // Use the view's uri, which is already the new page's once a load
+        // has started.
+        let host = self
+            .view
+            .borrow()
+            .uri()
+            .and_then(|uri| Url::parse(&uri).ok())
+            .and_then(|url| url.host_str().map(host_key));
+        let force_dark = adw::StyleManager::default().is_dark()
+            && host.is_some_and(|host| self.profile.force_dark.borrow().applies_to(&host));
+        self.engine.set_force_dark(tab, force_dark);
+        Some(tab)
+    }
+
+    fn finish_find(&self) {
+        if let Some(tab) = self.bound.get() {
+            self.engine.find_finish(tab);
+        }
+    }
+}
+
+/// The content views of a window.
+///
+/// Normal tabs share `normal`. Private tabs share a view on an ephemeral
+/// network session, created on first use and dropped once the last private
+/// tab closes so its cookies and site data go with it.
+struct ContentViews {
+    paned: gtk::Paned,
+    engine: Rc<ContentEngine>,
+    normal: webkit6::WebView,
+    private: RefCell<Option<webkit6::WebView>>,
+    wire_private: Box<dyn Fn(&webkit6::WebView)>,
+}
+
+impl ContentViews {
+    /// Returns the view currently shown in the window.
+    fn current(&self) -> webkit6::WebView {
+        self.paned
+            .end_child()
+            .and_downcast::<webkit6::WebView>()
+            .unwrap_or_else(|| self.normal.clone())
+    }
+
+    /// Shows the view for the active tab and returns it.
+    fn show_active(&self, state: &RefCell<BrowserState>) -> webkit6::WebView {
+        let (private, any_private) = {
+            let state = state.borrow();
+            (state.active_is_private(), state.has_private_tabs())
+        };
+        let view = if private {
+            self.private_view()
+        } else {
+            self.normal.clone()
+        };
+        let shown = self.current();
+        if shown != view {
+            // The hidden view must not finish a load on behalf of the new tab.
+            shown.stop_loading();
+            self.paned.set_end_child(Some(&view));
+            self.engine.set_view(&view);
+            self.engine.sync_active(state);
+        }
+        if !any_private {
+            self.private.take();
+        }
+        view
+    }
+
+    fn private_view(&self) -> webkit6::WebView {
+        self.private
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let view = self.engine.engine.create_view_ephemeral();
+                view.set_hexpand(true);
+                view.set_vexpand(true);
+                (self.wire_private)(&view);
+                view
+            })
+            .clone()
+    }
+}
+
+/// Certificate failure awaiting an explicit decision from the user.
+#[derive(Debug)]
+struct TlsFailure {
+    host: String,
+    uri: String,
+    certificate: gio::TlsCertificate,
+}
+
+const TLS_ERROR_DESCRIPTIONS: &[(gio::TlsCertificateFlags, &str)] = &[
+    (
+        gio::TlsCertificateFlags::UNKNOWN_CA,
+        "The certificate was not issued by a trusted authority",
+    ),
+    (
+        gio::TlsCertificateFlags::BAD_IDENTITY,
+        "The certificate does not match this site",
+    ),
+    (
+        gio::TlsCertificateFlags::NOT_ACTIVATED,
+        "The certificate is not valid yet",
+    ),
+    (gio::TlsCertificateFlags::EXPIRED, "The certificate has expired"),
+    (gio::TlsCertificateFlags::REVOKED, "The certificate has been revoked"),
+    (
+        gio::TlsCertificateFlags::INSECURE,
+        "The certificate uses an insecure algorithm",
+    ),
+    (
+        gio::TlsCertificateFlags::GENERIC_ERROR,
+        "The certificate could not be verified",
+    ),
+];
+
+/// Settings for forcing a dark rendering onto pages while the system color
+/// scheme is dark.
+#[derive(Debug, Default)]
+struct ForceDark {
+    enabled: bool,
+    /// Per-host exceptions keyed by host (without a leading `www.`).
+    hosts: HashMap<String, ForceDarkRule>,
+}
+
+impl ForceDark {
+    fn applies_to(&self, host: &str) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        match self.hosts.get(host) {
+            Some(rule) => *rule == ForceDarkRule::Allow,
+            None => !self.hosts.values().any(|rule| *rule == ForceDarkRule::Allow),
+        }
+    }
+}
+
+/// Per-user data shared by every window of the instance.
+#[derive(Debug)]
+struct Profile {
+    history: RefCell<FileHistoryStore>,
+    /// Pending write of visits the history store held back.
+    history_flush: RefCell<Option<glib::SourceId>>,
+    bookmarks: RefCell<FileBookmarkStore>,
+    sessions: RefCell<FileSessionLibrary>,
+    permissions: RefCell<FilePermissionStore>,
+    /// User-Agent overrides keyed by host (without a leading `www.`).
+    user_agents: RefCell<HashMap<String, String>>,
+    force_dark: RefCell<ForceDark>,
+    favicons: Rc<RefCell<Favicons>>,
+    /// User preferences; change them through `Profile::update_settings`.
+    settings: RefCell<FileSettingsStore>,
+    settings_listeners: SettingsListeners,
+}
+
+/// Called with the new settings after every change. Returns `false` once its
+/// window is gone, which drops it.
+type SettingsListener = Box<dyn Fn(&Settings) -> bool>;
+
+#[derive(Default)]
+struct SettingsListeners(RefCell<Vec<SettingsListener>>);
+
+impl std::fmt::Debug for SettingsListeners {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SettingsListeners({})", self.0.borrow().len())
+    }
+}
+
+/// Favicon bookkeeping shared by every window.
+#[derive(Debug)]
+struct Favicons {
+    cache: FileFaviconCache,
+    /// Page uris with a favicon database lookup pending.
+    in_flight: InFlight,
+}
+
+impl Profile {
+    fn open() -> Self {
+        let settings = FileSettingsStore::open(session::settings_path());
+        Self {
+            history: RefCell::new(FileHistoryStore::open(session::history_path())),
+            history_flush: RefCell::new(None),
+            bookmarks: RefCell::new(FileBookmarkStore::open(session::bookmarks_path())),
+            sessions: RefCell::new(FileSessionLibrary::open(session::library_path())),
+            permissions: RefCell::new(FilePermissionStore::open(session::permissions_path())),
+            user_agents: RefCell::new(HashMap::new()),
+            force_dark: RefCell::new(ForceDark {
+                enabled: settings.get().force_dark,
+                ..ForceDark::default()
+            }),
+            favicons: Rc::new(RefCell::new(Favicons {
+                cache: FileFaviconCache::open(
+                    session::favicon_cache_path(),
+                    storage::favicons::DEFAULT_TTL,
+                ),
+                in_flight: InFlight::default(),
+            })),
+            settings: RefCell::new(settings),
+            settings_listeners: SettingsListeners::default(),
+        }
+    }
+
+    /// Records a visit and makes sure it reaches the disk within the store's
+    /// save interval even if no further visit triggers a write.
+    fn record_visit(self: &Rc<Self>, uri: &str, title: &str) {
+        let mut history = self.history.borrow_mut();
+        history.record_visit(uri, title, SystemTime::now());
+        if !history.has_unsaved() || self.history_flush.borrow().is_some() {
+            return;
+        }
+        let profile = Rc::downgrade(self);
+        let source = glib::timeout_add_local_once(storage::history::SAVE_INTERVAL, move || {
+            if let Some(profile) = profile.upgrade() {
+                // The source has already fired; dropping the id is enough.
+                profile.history_flush.borrow_mut().take();
+                profile.history.borrow_mut().flush();
+            }
+        });
+        *self.history_flush.borrow_mut() = Some(source);
+    }
+
+    fn flush_history(&self) {
+        if let Some(source) = self.history_flush.borrow_mut().take() {
+            source.remove();
+        }
+        self.history.borrow_mut().flush();
+    }
+
+    fn add_settings_listener(&self, listener: impl Fn(&Settings) -> bool + 'static) {
+        self.settings_listeners.0.borrow_mut().push(Box::new(listener));
+    }
+
+    /// Applies `update` to a copy of the settings and stores it if valid,
+    /// then tells every window. Returns whether anything changed.
+    fn update_settings(&self, update: impl FnOnce(&mut Settings)) -> Result<bool, InvalidSetting> {
+        let mut settings = self.settings.borrow().get().clone();
+        update(&mut settings);
+        if !self.settings.borrow_mut().set(settings.clone())? {
+            return Ok(false);
+        }
+        self.force_dark.borrow_mut().enabled = settings.force_dark;
+        // Listeners run unborrowed so they can read the profile; any added
+        // meanwhile are kept after the survivors.
+        let listeners = self.settings_listeners.0.take();
+        let mut kept: Vec<_> = listeners
+            .into_iter()
+            .filter(|listener| listener(&settings))
+            .collect();
+        kept.append(&mut self.settings_listeners.0.borrow_mut());
+        *self.settings_listeners.0.borrow_mut() = kept;
+        Ok(true)
+    }
+}
+
+/// Tunables for page loads.
+#[derive(Debug, Clone, Copy)]
+struct LoadConfig {
+    /// Loads that have not committed within this time are stopped; `None`
+    /// waits indefinitely.
+    timeout: Option<Duration>,
+}
+
+impl Default for LoadConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Some(Duration::from_secs(30)),
+        }
+    }
+}
 
 #[derive(Debug)]
 struct UiState {
@@ -24,15 +417,204 @@ struct UiState {
     sidebar_animation: Option<glib::SourceId>,
     sidebar_expanded: i32,
     sidebar_resize_idle: Option<glib::SourceId>,
+    /// A user-initiated navigation is pending and should take focus once
+    /// it commits.
+    focus_on_commit: bool,
+    /// Most navigable tabs kept live before the least recently used are
+    /// suspended; `None` disables the cap.
+    tab_cap: Option<usize>,
+    /// Consecutive web process crashes per tab.
+    crashes: CrashCounts,
+    /// Pending automatic reload after a crash.
+    crash_reload: Option<glib::SourceId>,
+    load_config: LoadConfig,
+    /// Timer that stops the given tab's load once it runs too long.
+    load_timeout: Option<(u64, glib::SourceId)>,
+    /// Pending prefetch of the most recently hovered link.
+    prefetch: Option<glib::SourceId>,
+    /// Next governor poll; its delay adapts to user activity.
+    governor_poll: Option<glib::SourceId>,
+    /// Memory pressure level last reported to the UI.
+    memory_pressure: MemoryPressure,
+    /// Scroll positions of tabs the content view moved away from. Tabs share
+    /// one view, so every background tab comes back by reloading its url.
+    hibernation: Hibernation,
+    /// Snapshot to apply once the selected tab's reload finishes.
+    pending_restore: Option<(u64, HibernationSnapshot)>,
 }
 
 pub fn run() -> glib::ExitCode {
-    let app = adw::Application::builder().application_id(APP_ID).build();
-    app.connect_activate(build_ui);
+    // The application id makes GApplication single-instance: a second launch
+    // forwards its command line to the primary instance and exits.
+    let app = adw::Application::builder()
+        .application_id(APP_ID)
+        .flags(gio::ApplicationFlags::HANDLES_OPEN | gio::ApplicationFlags::HANDLES_COMMAND_LINE)
+        .build();
+    app.set_register_session(true);
+    let windows: Rc<RefCell<Vec<WindowHandle>>> = Rc::new(RefCell::new(Vec::new()));
+    let profile = Rc::new(Profile::open());
+
+    let store = FileSessionStore::new(session::session_path());
+    let windows_for_save = Rc::clone(&windows);
+    let journal = SessionJournal::new(session::journal_path());
+    let autosave = SessionAutosave::new(store, journal, move || {
+        let mut snapshot = SessionSnapshot::default();
+        for (window, handle) in windows_for_save.borrow().iter().enumerate() {
+            // Window positions shift as windows close; the journal then
+            // records their tabs as closed and reopened, which replays to
+            // the same session.
+            let window = u32::try_from(window).unwrap_or(u32::MAX);
+            session::append_snapshot(&mut snapshot, &handle.state.borrow(), window);
+        }
+        snapshot
+    });
+    let autosave_for_save = Rc::clone(&autosave);
+    let profile_for_save = Rc::clone(&profile);
+    let save_session: Rc<dyn Fn()> = Rc::new(move || {
+        autosave_for_save.save_now();
+        profile_for_save.flush_history();
+    });
+
+    // Persist on logout (query-end), normal exit (shutdown), and before the
+    // machine sleeps or powers off (logind delay inhibitor).
+    let sleep_inhibitor: Rc<RefCell<Option<SleepInhibitor>>> = Rc::new(RefCell::new(None));
+    let save_for_startup = Rc::clone(&save_session);
+    let inhibitor_for_startup = Rc::clone(&sleep_inhibitor);
+    let profile_for_startup = Rc::clone(&profile);
+    app.connect_startup(move |_| {
+        *inhibitor_for_startup.borrow_mut() = SleepInhibitor::install(Rc::clone(&save_for_startup));
+        register_internal_pages(Rc::clone(&profile_for_startup));
+    });
+
+    let save_for_query_end = Rc::clone(&save_session);
+    app.connect_query_end(move |_| save_for_query_end());
+
+    let save_for_shutdown = Rc::clone(&save_session);
+    let inhibitor_for_shutdown = Rc::clone(&sleep_inhibitor);
+    let windows_for_shutdown = Rc::clone(&windows);
+    app.connect_shutdown(move |_| {
+        for handle in windows_for_shutdown.borrow().iter() {
+            handle.state_sync.flush();
+        }
+        save_for_shutdown();
+        inhibitor_for_shutdown.borrow_mut().take();
+    });
+
+    let windows_for_activate = Rc::clone(&windows);
+    let profile_for_activate = Rc::clone(&profile);
+    let autosave_for_activate = Rc::clone(&autosave);
+    app.connect_activate(move |app| {
+        open_in_window(
+            app,
+            &windows_for_activate,
+            &profile_for_activate,
+            &autosave_for_activate,
+            &[],
+            false,
+        );
+    });
+
+    let windows_for_open = Rc::clone(&windows);
+    let profile_for_open = Rc::clone(&profile);
+    let autosave_for_open = Rc::clone(&autosave);
+    app.connect_open(move |app, files, _hint| {
+        let urls: Vec<String> = files.iter().map(|file| file.uri().to_string()).collect();
+        open_in_window(
+            app,
+            &windows_for_open,
+            &profile_for_open,
+            &autosave_for_open,
+            &urls,
+            false,
+        );
+    });
+
+    let windows_for_command_line = Rc::clone(&windows);
+    let profile_for_command_line = Rc::clone(&profile);
+    let autosave_for_command_line = Rc::clone(&autosave);
+    app.connect_command_line(move |app, command_line| {
+        let mut new_window = false;
+        let mut urls = Vec::new();
+        for arg in command_line.arguments().iter().skip(1) {
+            let arg = arg.to_string_lossy();
+            if arg == "--new-window" {
+                new_window = true;
+            } else if arg.starts_with("--") {
+                log::warn!("ignoring unknown option: {arg}");
+            } else if arg.contains("://") || arg.starts_with("about:") {
+                urls.push(arg.to_string());
+            } else if std::path::Path::new(&*arg).exists() {
+                urls.push(command_line.create_file_for_arg(&*arg).uri().to_string());
+            } else {
+                urls.push(normalize_url(
+                    &arg,
+                    &profile_for_command_line.settings.borrow().get().search_template,
+                ));
+            }
+        }
+
+        open_in_window(
+            app,
+            &windows_for_command_line,
+            &profile_for_command_line,
+            &autosave_for_command_line,
+            &urls,
+            new_window,
+        );
+        glib::ExitCode::SUCCESS
+    });
+
     app.run()
 }
 
-fn build_ui(app: &adw::Application) {
+/// Opens `urls` as new tabs in the focused window, creating a window when
+/// none exists or when `new_window` is requested.
+fn open_in_window(
+    app: &adw::Application,
+    windows: &Rc<RefCell<Vec<WindowHandle>>>,
+    profile: &Rc<Profile>,
+    autosave: &Rc<SessionAutosave>,
+    urls: &[String],
+    new_window: bool,
+) {
+    let live = app.windows();
+    windows
+        .borrow_mut()
+        .retain(|handle| live.iter().any(|window| window == handle.window.upcast_ref::<gtk::Window>()));
+
+    let existing = if new_window {
+        None
+    } else {
+        let windows_ref = windows.borrow();
+        let focused = app.active_window();
+        windows_ref
+            .iter()
+            .find(|handle| focused.as_ref() == Some(handle.window.upcast_ref::<gtk::Window>()))
+            .or_else(|| windows_ref.last())
+            .cloned()
+    };
+
+    let handle = existing.unwrap_or_else(|| {
+        let handle = build_ui(app, profile);
+        // Every tab change reaches the UI through the state sync, so its
+        // sends double as the autosave trigger.
+        let autosave = Rc::downgrade(autosave);
+        handle.state_sync.connect_changed(move || {
+            if let Some(autosave) = autosave.upgrade() {
+                autosave.schedule();
+            }
+        });
+        windows.borrow_mut().push(handle.clone());
+        handle
+    });
+
+    for url in urls {
+        (handle.open_url)(url);
+    }
+    handle.window.present();
+}
+
+fn build_ui(app: &adw::Application, profile: &Rc<Profile>) -> WindowHandle {
     let style_manager = adw::StyleManager::default();
     style_manager.set_color_scheme(adw::ColorScheme::Default);
 
@@ -44,15 +626,61 @@ fn build_ui(app: &adw::Application) {
         sidebar_animation: None,
         sidebar_expanded: SIDEBAR_EXPANDED,
         sidebar_resize_idle: None,
+        focus_on_commit: false,
+        tab_cap: Some(DEFAULT_TAB_CAP),
+        crashes: CrashCounts::default(),
+        crash_reload: None,
+        load_config: LoadConfig::default(),
+        load_timeout: None,
+        prefetch: None,
+        governor_poll: None,
+        memory_pressure: MemoryPressure::Low,
+        hibernation: Hibernation::new(),
+        pending_restore: None,
     }));
 
     let ui_manager = webkit6::UserContentManager::new();
     if !ui_manager.register_script_message_handler("owl", None) {
-        eprintln!("Failed to register script message handler");
+        log::error!("failed to register script message handler");
     }
 
     let ui_webview = create_webview(Some(&ui_manager));
     let content_webview = create_webview(None);
+    let content_engine = Rc::new(ContentEngine::new(
+        content_webview.clone(),
+        Rc::clone(profile),
+    ));
+    // Forced dark rendering follows the system color scheme live.
+    let engine_for_dark = Rc::downgrade(&content_engine);
+    let state_for_dark = Rc::downgrade(&state);
+    style_manager.connect_dark_notify(move |_| {
+        if let (Some(engine), Some(state)) = (engine_for_dark.upgrade(), state_for_dark.upgrade()) {
+            engine.sync_active(&state);
+        }
+    });
+
+    let content_filter = session::content_filter_path();
+    if content_filter.exists() {
+        content_engine.engine.load_content_filter(&content_filter);
+    }
+    apply_settings(&content_engine, profile.settings.borrow().get());
+    // Settings changed from any window apply here too.
+    let engine_for_settings = Rc::downgrade(&content_engine);
+    let state_for_settings = Rc::downgrade(&state);
+    let ui_for_settings = ui_webview.downgrade();
+    profile.add_settings_listener(move |settings| {
+        let (Some(engine), Some(state), Some(ui_webview)) = (
+            engine_for_settings.upgrade(),
+            state_for_settings.upgrade(),
+            ui_for_settings.upgrade(),
+        ) else {
+            return false;
+        };
+        apply_settings(&engine, settings);
+        engine.sync_active(&state);
+        ipc::send_settings(&ui_webview, settings);
+        true
+    });
     let favicon_db = content_webview
         .network_session()
         .and_then(|session| session.website_data_manager())
@@ -85,28 +713,318 @@ fn build_ui(app: &adw::Application) {
         }
     }
 
+    // Fullscreen requests from page content (e.g. video) fullscreen the whole
+    // window with the chrome hidden, and exempt the tab from throttling.
+    // The handlers return `false` so WebKit still runs its own transition;
+    // without it the element never becomes `document.fullscreenElement`.
+    let content_fullscreen = Rc::new(Cell::new(false));
+    let window_for_enter = window.clone();
+    let header_for_enter = header.clone();
+    let ui_webview_for_enter = ui_webview.clone();
+    let engine_for_enter = Rc::clone(&content_engine);
+    let fullscreen_for_enter = Rc::clone(&content_fullscreen);
+    content_webview.connect_enter_fullscreen(move |_| {
+        fullscreen_for_enter.set(true);
+        set_chrome_visible(&header_for_enter, &ui_webview_for_enter, false);
+        window_for_enter.fullscreen();
+        engine_for_enter
+            .governor
+            .set_presentation_tab(engine_for_enter.bound.get());
+        false
+    });
+
+    let window_for_leave = window.clone();
+    let header_for_leave = header.clone();
+    let ui_webview_for_leave = ui_webview.clone();
+    let engine_for_leave = Rc::clone(&content_engine);
+    let fullscreen_for_leave = Rc::clone(&content_fullscreen);
+    content_webview.connect_leave_fullscreen(move |_| {
+        // Clear the flag first so the window notification below is not
+        // mistaken for a compositor-initiated exit.
+        fullscreen_for_leave.set(false);
+        window_for_leave.unfullscreen();
+        set_chrome_visible(&header_for_leave, &ui_webview_for_leave, true);
+        engine_for_leave.governor.set_presentation_tab(None);
+        false
+    });
+
+    // The compositor (or a window manager shortcut) can leave fullscreen on
+    // its own; tell the page so WebKit emits leave-fullscreen and we restore.
+    let content_for_unfullscreen = content_webview.clone();
+    let fullscreen_for_unfullscreen = Rc::clone(&content_fullscreen);
+    window.connect_fullscreened_notify(move |window| {
+        if !window.is_fullscreen() && fullscreen_for_unfullscreen.get() {
+            exit_content_fullscreen(&content_for_unfullscreen);
+        }
+    });
+
+    let state_for_focus = Rc::clone(&state);
+    let engine_for_focus = Rc::clone(&content_engine);
+    window.connect_is_active_notify(move |window| {
+        let Some(tab) = engine_for_focus.bind(state_for_focus.borrow().active) else {
+            return;
+        };
+        if window.is_active() {
+            engine_for_focus.governor.on_tab_focused(tab);
+        } else {
+            engine_for_focus.governor.on_tab_blurred(tab);
+        }
+    });
+
+    // The monitor glue feeds memory pressure to the governor; governor polls
+    // refresh idle state and report level changes to the sidebar. Threshold
+    // settings are read here, so changes apply to windows opened later.
+    let thresholds = {
+        let settings = profile.settings.borrow();
+        MemoryPressureThresholds {
+            moderate_headroom_per_mille: u16::from(settings.get().memory_moderate_percent) * 10,
+            severe_headroom_per_mille: u16::from(settings.get().memory_severe_percent) * 10,
+        }
+    };
+    let pressure_source = glue::apply_to_governor(
+        Rc::clone(&content_engine.governor),
+        MemoryPressureReceiver::start(
+            DefaultMemoryPressureSource::default(),
+            thresholds,
+            MemoryPressureMonitorConfig::default(),
+        ),
+        glue::DEFAULT_APPLY_INTERVAL,
+    );
+    schedule_governor_poll(&content_engine, &ui_webview, &ui_state);
+    // Removing the glue source drops the receiver, which stops the monitor.
+    let pressure_source = RefCell::new(Some(pressure_source));
+    let ui_state_for_destroy = Rc::clone(&ui_state);
+    window.connect_destroy(move |_| {
+        if let Some(source) = pressure_source.borrow_mut().take() {
+            source.remove();
+        }
+        if let Some(source) = ui_state_for_destroy.borrow_mut().governor_poll.take() {
+            source.remove();
+        }
+    });
+
+    // Minimizing only shows up in the toplevel surface state, which exists
+    // once the window is realized.
+    let engine_for_visibility = Rc::clone(&content_engine);
+    window.connect_realize(move |window| {
+        let Some(toplevel) = window.surface().and_downcast::<gtk::gdk::Toplevel>() else {
+            return;
+        };
+        let engine = Rc::clone(&engine_for_visibility);
+        toplevel.connect_state_notify(move |toplevel| {
+            let minimized = toplevel.state().contains(gtk::gdk::ToplevelState::MINIMIZED);
+            engine.governor.set_window_visible(!minimized);
+        });
+    });
+
+    let escape = gtk::EventControllerKey::new();
+    escape.set_propagation_phase(gtk::PropagationPhase::Capture);
+    let content_for_escape = content_webview.clone();
+    let fullscreen_for_escape = Rc::clone(&content_fullscreen);
+    escape.connect_key_pressed(move |_, key, _, _| {
+        if key == gtk::gdk::Key::Escape && fullscreen_for_escape.get() {
+            exit_content_fullscreen(&content_for_escape);
+            return glib::Propagation::Stop;
+        }
+        glib::Propagation::Proceed
+    });
+    window.add_controller(escape);
+
     window.present();
 
     ui_webview.load_uri(&assets.ui_uri);
-    load_home(&content_webview, &assets.home_uri);
+    load_home(&content_webview);
+
+    let state_sync = ipc::StateSync::new(&ui_webview, &state);
+    let script_dialogs = ScriptDialogs::new(&window);
+
+    // Private tabs only track their title and navigation state: nothing is
+    // recorded in history and no favicons are fetched for them.
+    let ui_webview_for_private = ui_webview.clone();
+    let state_for_private = Rc::clone(&state);
+    let state_sync_for_private = Rc::clone(&state_sync);
+    let engine_for_private = Rc::clone(&content_engine);
+    let profile_for_private = Rc::clone(profile);
+    let ui_state_for_private = Rc::clone(&ui_state);
+    let dialogs_for_private = Rc::clone(&script_dialogs);
+    let wire_private: Box<dyn Fn(&webkit6::WebView)> = Box::new(move |view| {
+        let state = Rc::clone(&state_for_private);
+        let dialogs = Rc::clone(&dialogs_for_private);
+        view.connect_script_dialog(move |view, dialog| {
+            let Some(active) = state.borrow().active else {
+                return false;
+            };
+            dialogs.show(active, view.uri().as_deref(), dialog)
+        });
 
-    let state_for_ui = Rc::clone(&state);
+        let ui_webview = ui_webview_for_private.clone();
+        let state = Rc::clone(&state_for_private);
+        let ui_state = Rc::clone(&ui_state_for_private);
+        view.connect_web_process_terminated(move |view, reason| {
+            recover_from_crash(view, reason, &state, &ui_state, &ui_webview);
+        });
+
+        let state = Rc::clone(&state_for_private);
+        let engine = Rc::clone(&engine_for_private);
+        let profile = Rc::clone(&profile_for_private);
+        view.connect_decide_policy(move |_, decision, decision_type| {
+            if decision_type == webkit6::PolicyDecisionType::NavigationAction {
+                if let Some(uri) = navigation_uri(decision) {
+                    apply_user_agent(&engine, &state, &profile, &uri);
+                }
+            }
+            false
+        });
+
+        let ui_webview = ui_webview_for_private.clone();
+        let state = Rc::clone(&state_for_private);
+        let state_sync = Rc::clone(&state_sync_for_private);
+        let engine = Rc::clone(&engine_for_private);
+        let ui_state = Rc::clone(&ui_state_for_private);
+        view.connect_load_changed(move |view, event| {
+            let active = {
+                let state = state.borrow();
+                state.active.filter(|_| state.active_is_private())
+            };
+            let Some(active) = active else {
+                return;
+            };
+            if event == webkit6::LoadEvent::Started {
+                engine.sync_active(&state);
+                engine.finish_find();
+                ipc::send_find_state(&ui_webview, 0, 0);
+                start_load_timeout(view, &state, &ui_state, &ui_webview);
+            } else if matches!(
+                event,
+                webkit6::LoadEvent::Committed | webkit6::LoadEvent::Finished
+            ) {
+                cancel_load_timeout(&ui_state);
+            }
+            emit_nav_state(&ui_webview, view, event != webkit6::LoadEvent::Finished);
+            if event == webkit6::LoadEvent::Finished {
+                let title = view.title().map(|t| t.to_string());
+                let uri = view.uri().map(|u| u.to_string());
+                state
+                    .borrow_mut()
+                    .update_tab(active, title.as_deref(), uri.as_deref());
+                restore_hibernated(view, active, &ui_state);
+                state_sync.schedule();
+            }
+        });
+    });
+    let content_views = Rc::new(ContentViews {
+        paned: paned.clone(),
+        engine: Rc::clone(&content_engine),
+        normal: content_webview.clone(),
+        private: RefCell::new(None),
+        wire_private,
+    });
+
+    // The network session is shared between windows, so only downloads
+    // started from this window's content view are tracked here.
+    let downloads = Downloads::new(&ui_webview);
+    if let Some(session) = content_webview.network_session() {
+        let downloads = Rc::clone(&downloads);
+        let content_for_downloads = content_webview.clone();
+        session.connect_download_started(move |_, download| {
+            if download.web_view().as_ref() == Some(&content_for_downloads) {
+                downloads.track(download);
+            }
+        });
+    }
+
+    let state_for_open = Rc::clone(&state);
+    let state_sync_for_open = Rc::clone(&state_sync);
+    let content_views_for_open = Rc::clone(&content_views);
+    let open_url: Rc<dyn Fn(&str)> = Rc::new(move |url: &str| {
+        let id = state_for_open
+            .borrow_mut()
+            .create_tab(None, "New Tab", url);
+        state_for_open.borrow_mut().set_active(id);
+        let view = content_views_for_open.show_active(&state_for_open);
+        load_url(&view, url);
+        state_sync_for_open.schedule();
+    });
+
+    let state_sync_for_ui = Rc::clone(&state_sync);
     let content_for_ui = content_webview.clone();
     let loading_for_ui = Rc::new(RefCell::new(false));
     let loading_for_ui_cb = Rc::clone(&loading_for_ui);
     ui_webview.connect_load_changed(move |view, event| {
         if event == webkit6::LoadEvent::Finished {
-            ipc::send_state(view, &state_for_ui.borrow());
+            // A reloaded UI page has lost whatever it was sent before.
+            state_sync_for_ui.resync();
             emit_nav_state(view, &content_for_ui, *loading_for_ui_cb.borrow());
         }
     });
 
     let ui_webview_for_content = ui_webview.clone();
     let state_for_content = Rc::clone(&state);
-    let home_uri_for_content = assets.home_uri.clone();
+    let state_sync_for_content = Rc::clone(&state_sync);
     let loading_for_content = Rc::clone(&loading_for_ui);
     let favicon_db_for_content = favicon_db.clone();
+    let engine_for_content = Rc::clone(&content_engine);
+    let profile_for_content = Rc::clone(profile);
+    let redirects: Rc<RefCell<Option<RedirectChain>>> = Rc::new(RefCell::new(None));
+    let ui_state_for_content = Rc::clone(&ui_state);
+    let state_for_hover = Rc::clone(&state);
+    let engine_for_hover = Rc::clone(&content_engine);
+    let ui_state_for_hover = Rc::clone(&ui_state);
+    content_webview.connect_mouse_target_changed(move |_, hit, _| {
+        if !hit.context_is_link() {
+            return;
+        }
+        if let Some(uri) = hit.link_uri() {
+            schedule_prefetch(
+                &engine_for_hover,
+                &state_for_hover,
+                &ui_state_for_hover,
+                uri.to_string(),
+            );
+        }
+    });
+
     content_webview.connect_load_changed(move |view, event| {
+        // Private tabs load in their own view; see `ContentViews`.
+        if state_for_content.borrow().active_is_private() {
+            return;
+        }
+        if event == webkit6::LoadEvent::Started {
+            engine_for_content.sync_active(&state_for_content);
+            engine_for_content.finish_find();
+            ipc::send_find_state(&ui_webview_for_content, 0, 0);
+            track_redirects(view, &state_for_content, &redirects);
+            start_load_timeout(
+                view,
+                &state_for_content,
+                &ui_state_for_content,
+                &ui_webview_for_content,
+            );
+            let tab_cap = ui_state_for_content.borrow().tab_cap;
+            if let Some(cap) = tab_cap {
+                enforce_tab_cap(
+                    &state_for_content,
+                    &state_sync_for_content,
+                    &ui_webview_for_content,
+                    cap,
+                );
+            }
+        }
+
+        if matches!(
+            event,
+            webkit6::LoadEvent::Committed | webkit6::LoadEvent::Finished
+        ) {
+            cancel_load_timeout(&ui_state_for_content);
+        }
+
+        if event == webkit6::LoadEvent::Committed {
+            let focus = std::mem::take(&mut ui_state_for_content.borrow_mut().focus_on_commit);
+            if focus {
+                view.grab_focus();
+            }
+        }
+
         let is_loading = matches!(
             event,
             webkit6::LoadEvent::Started
@@ -128,25 +1046,37 @@ fn build_ui(app: &adw::Application) {
                 .uri()
                 .map(|u| u.to_string())
                 .unwrap_or_else(|| "owl://home".to_string());
-            let display_uri = if uri == home_uri_for_content {
-                "owl://home".to_string()
-            } else {
-                uri
-            };
+
+            if !uri.starts_with("owl://") {
+                profile_for_content.record_visit(&uri, &title);
+            }
 
             let active = { state_for_content.borrow().active };
             if let Some(active) = active {
+                // A temporary redirect leaves the tab on the url it asked for,
+                // so the redirect is followed afresh next time.
+                let chain = redirects.borrow_mut().take();
+                let stored_uri = chain
+                    .as_ref()
+                    .and_then(|chain| chain.stored_url(active, &uri))
+                    .unwrap_or(&uri);
                 {
                     let mut state_mut = state_for_content.borrow_mut();
-                    state_mut.update_tab(active, Some(&title), Some(&display_uri));
+                    state_mut.update_tab(active, Some(&title), Some(stored_uri));
                 }
-                let state_ref = state_for_content.borrow();
-                ipc::send_state(&ui_webview_for_content, &state_ref);
+                restore_hibernated(view, active, &ui_state_for_content);
+                state_sync_for_content.schedule();
             }
 
             if let Some(db) = &favicon_db_for_content {
                 if let Some(actual_uri) = view.uri().map(|u| u.to_string()) {
-                    refresh_favicon(db, &state_for_content, &ui_webview_for_content, &actual_uri);
+                    refresh_favicon(
+                        db,
+                        &profile_for_content.favicons,
+                        &state_for_content,
+                        &ui_webview_for_content,
+                        &actual_uri,
+                    );
                 }
             }
         }
@@ -155,96 +1085,209 @@ fn build_ui(app: &adw::Application) {
     if let Some(db) = &favicon_db {
         let state_for_favicon = Rc::clone(&state);
         let ui_for_favicon = ui_webview.clone();
-        db.connect_favicon_changed(move |_, page_uri, favicon_uri| {
-            update_favicon_state(
+        let cache_for_favicon = Rc::clone(&profile.favicons);
+        db.connect_favicon_changed(move |db, page_uri, _| {
+            queue_favicon_fetch(
+                db,
+                &cache_for_favicon,
                 &state_for_favicon,
                 &ui_for_favicon,
                 page_uri,
-                Some(favicon_uri.to_string()),
             );
         });
     }
 
-    let ui_webview_for_policy = ui_webview.clone();
     let state_for_policy = Rc::clone(&state);
-    let home_uri_for_policy = assets.home_uri.clone();
+    let state_sync_for_policy = Rc::clone(&state_sync);
+    let profile_for_policy = Rc::clone(profile);
+    let engine_for_policy = Rc::clone(&content_engine);
     content_webview.connect_decide_policy(move |view, decision, decision_type| {
         if decision_type != webkit6::PolicyDecisionType::NavigationAction {
             return false;
         }
-
-        let Some(policy) = decision.dynamic_cast_ref::<webkit6::NavigationPolicyDecision>() else {
-            return false;
-        };
-        let Some(mut action) = policy.navigation_action() else {
-            return false;
-        };
-        let Some(request) = action.request() else {
+        let Some(uri) = navigation_uri(decision) else {
             return false;
         };
-        let Some(uri) = request.uri() else {
-            return false;
-        };
-        let uri = uri.to_string();
 
-        if uri == "owl://home" || uri == "about:home" {
+        if uri == "about:home" {
             decision.ignore();
-            load_home(view, &home_uri_for_policy);
+            load_home(view);
             return true;
         }
 
         if let Some(slug) = uri.strip_prefix("owl://session/") {
             decision.ignore();
-            if let Some(first_url) = open_session(&state_for_policy, slug) {
-                let state_ref = state_for_policy.borrow();
-                ipc::send_state(&ui_webview_for_policy, &state_ref);
-                load_url(view, &first_url, &home_uri_for_policy);
+            let first_url =
+                open_session(&state_for_policy, &profile_for_policy.sessions.borrow(), slug);
+            if let Some(first_url) = first_url {
+                state_sync_for_policy.schedule();
+                load_url(view, &first_url);
             } else {
-                load_home(view, &home_uri_for_policy);
+                load_home(view);
             }
             return true;
         }
 
+        // Set before the request is sent so it carries the override.
+        apply_user_agent(
+            &engine_for_policy,
+            &state_for_policy,
+            &profile_for_policy,
+            &uri,
+        );
         false
     });
 
+    let state_for_muted = Rc::clone(&state);
+    let state_sync_for_muted = Rc::clone(&state_sync);
+    content_webview.connect_is_muted_notify(move |view| {
+        let muted = view.is_muted();
+        {
+            let mut state = state_for_muted.borrow_mut();
+            let Some(active) = state.active else {
+                return;
+            };
+            let Some(node) = state.tabs.get_mut(&active) else {
+                return;
+            };
+            if node.is_muted == muted {
+                return;
+            }
+            node.is_muted = muted;
+        }
+        state_sync_for_muted.schedule();
+    });
+
+    let state_for_audio = Rc::clone(&state);
+    content_webview.connect_is_playing_audio_notify(move |view| {
+        let mut state = state_for_audio.borrow_mut();
+        let Some(active) = state.active else {
+            return;
+        };
+        if let Some(node) = state.tabs.get_mut(&active) {
+            node.is_audible = view.is_playing_audio();
+        }
+    });
+
     let ui_webview_for_failure = ui_webview.clone();
     let loading_for_failure = Rc::clone(&loading_for_ui);
+    let ui_state_for_failure = Rc::clone(&ui_state);
     content_webview.connect_load_failed(move |view, _event, _uri, _error| {
         {
             let mut loading = loading_for_failure.borrow_mut();
             *loading = false;
         }
+        // A failed navigation must not hand its focus to a later load.
+        ui_state_for_failure.borrow_mut().focus_on_commit = false;
+        cancel_load_timeout(&ui_state_for_failure);
         emit_nav_state(&ui_webview_for_failure, view, false);
         false
     });
 
+    let ui_webview_for_crash = ui_webview.clone();
+    let state_for_crash = Rc::clone(&state);
+    let ui_state_for_crash = Rc::clone(&ui_state);
+    content_webview.connect_web_process_terminated(move |view, reason| {
+        recover_from_crash(
+            view,
+            reason,
+            &state_for_crash,
+            &ui_state_for_crash,
+            &ui_webview_for_crash,
+        );
+    });
+
+    // Tabs share the view, so a dialog belongs to whichever tab is active.
+    let state_for_dialogs = Rc::clone(&state);
+    let dialogs_for_content = Rc::clone(&script_dialogs);
+    content_webview.connect_script_dialog(move |view, dialog| {
+        let Some(active) = state_for_dialogs.borrow().active else {
+            return false;
+        };
+        dialogs_for_content.show(active, view.uri().as_deref(), dialog)
+    });
+
+    // Private tabs are left to WebKit, which denies every request, so
+    // nothing they do is remembered.
+    let permission_prompts = PermissionPrompts::new(&ui_webview);
+    let prompts_for_content = Rc::clone(&permission_prompts);
+    let profile_for_permissions = Rc::clone(profile);
+    content_webview.connect_permission_request(move |view, request| {
+        prompts_for_content.request(
+            &*profile_for_permissions.permissions.borrow(),
+            view.uri().as_deref(),
+            request,
+        )
+    });
+
+    let tls_failure: Rc<RefCell<Option<TlsFailure>>> = Rc::new(RefCell::new(None));
+    let ui_webview_for_tls = ui_webview.clone();
+    let tls_failure_for_load = Rc::clone(&tls_failure);
+    content_webview.connect_load_failed_with_tls_errors(move |_, uri, certificate, errors| {
+        let Some(host) = Url::parse(uri)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        else {
+            return false;
+        };
+        ipc::send_tls_error(
+            &ui_webview_for_tls,
+            ipc::TlsErrorState {
+                host: &host,
+                uri,
+                errors: tls_error_descriptions(errors),
+            },
+        );
+        // Only the most recent failure can be overridden, and only for its host.
+        *tls_failure_for_load.borrow_mut() = Some(TlsFailure {
+            host,
+            uri: uri.to_string(),
+            certificate: certificate.clone(),
+        });
+        false
+    });
+
     let ui_webview_for_messages = ui_webview.clone();
-    let content_webview_for_messages = content_webview.clone();
+    let content_views_for_messages = Rc::clone(&content_views);
     let state_for_messages = Rc::clone(&state);
-    let home_uri_for_messages = assets.home_uri.clone();
     let paned_for_messages = paned.clone();
     let ui_state_for_messages = Rc::clone(&ui_state);
     let default_favicon_for_messages = default_favicon.clone();
+    let engine_for_messages = Rc::clone(&content_engine);
+    let state_sync_for_messages = Rc::clone(&state_sync);
+    let downloads_for_messages = Rc::clone(&downloads);
+    let dialogs_for_messages = Rc::clone(&script_dialogs);
+    let prompts_for_messages = Rc::clone(&permission_prompts);
+    let tls_failure_for_messages = Rc::clone(&tls_failure);
+    let profile_for_messages = Rc::clone(profile);
+    let reported_messages = RefCell::new(HashSet::new());
 
     ui_manager.connect_script_message_received(Some("owl"), move |_, value| {
         let raw = value.to_str();
-        let Ok(message) = serde_json::from_str::<IncomingMessage>(&raw) else {
-            eprintln!("Failed to parse message: {raw}");
+        let Some(message) = ipc::parse_message(&raw, &mut reported_messages.borrow_mut()) else {
             return;
         };
 
-        handle_message(
-            message,
+        let response = handle_message(
+            message.command,
             &ui_webview_for_messages,
-            &content_webview_for_messages,
+            &content_views_for_messages,
+            &engine_for_messages,
+            &downloads_for_messages,
+            &dialogs_for_messages,
+            &prompts_for_messages,
+            &tls_failure_for_messages,
+            &profile_for_messages,
             &state_for_messages,
-            &home_uri_for_messages,
+            &state_sync_for_messages,
             &paned_for_messages,
             &ui_state_for_messages,
             &default_favicon_for_messages,
             &favicon_db,
         );
+        if let (Some(id), Some(payload)) = (message.id, response) {
+            ipc::send_response(&ui_webview_for_messages, id, payload);
+        }
     });
 
     let ui_webview_for_resize = ui_webview.clone();
@@ -300,6 +1343,13 @@ fn build_ui(app: &adw::Application) {
 
         state.sidebar_resize_idle = Some(source);
     });
+
+    WindowHandle {
+        window,
+        state,
+        state_sync,
+        open_url,
+    }
 }
 
 fn build_header_bar(assets: &Assets) -> adw::HeaderBar {
@@ -321,175 +1371,1005 @@ fn build_header_bar(assets: &Assets) -> adw::HeaderBar {
     header
 }
 
-fn create_webview(manager: Option<&webkit6::UserContentManager>) -> webkit6::WebView {
-    let settings = webkit6::Settings::builder()
-        .allow_file_access_from_file_urls(true)
-        .enable_javascript(true)
-        .build();
-
-    let mut builder = webkit6::WebView::builder().settings(&settings);
-    if let Some(manager) = manager {
-        builder = builder.user_content_manager(manager);
-    }
+fn set_chrome_visible(header: &adw::HeaderBar, ui_webview: &webkit6::WebView, visible: bool) {
+    header.set_visible(visible);
+    ui_webview.set_visible(visible);
+}
 
-    let webview = builder.build();
-    webview.set_hexpand(true);
-    webview.set_vexpand(true);
+/// Records the active tab's url and scroll position before `view` moves on
+/// to another tab, so selecting it again can put the reader back in place.
+fn hibernate_active(
+    view: &webkit6::WebView,
+    state: &RefCell<BrowserState>,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let tab = {
+        let state = state.borrow();
+        state
+            .active
+            .and_then(|id| state.tabs.get(&id))
+            .filter(|node| !node.is_group)
+            .map(|node| (node.id, node.title.clone()))
+    };
+    let Some((tab, title)) = tab else {
+        return;
+    };
+    let Some(url) = view.uri().map(|uri| uri.to_string()) else {
+        return;
+    };
+    if url.starts_with("owl://") {
+        return;
+    }
+    // Runs on the current document: WebKit handles the script before the
+    // load that follows.
+    let ui_state = Rc::downgrade(ui_state);
+    view.evaluate_javascript(
+        "window.scrollX + ',' + window.scrollY",
+        None,
+        None,
+        None::<&gio::Cancellable>,
+        move |result| {
+            let scroll = match result {
+                Ok(value) => parse_scroll(&value.to_str()),
+                Err(error) => {
+                    log::debug!("no scroll position for tab {tab}: {error}");
+                    None
+                }
+            };
+            let (Some(scroll), Some(ui_state)) = (scroll, ui_state.upgrade()) else {
+                return;
+            };
+            if scroll == (0.0, 0.0) {
+                return;
+            }
+            ui_state
+                .borrow_mut()
+                .hibernation
+                .store(TabId::new(tab), HibernationSnapshot { url, title, scroll });
+        },
+    );
+}
+
+fn parse_scroll(value: &str) -> Option<(f64, f64)> {
+    let (x, y) = value.split_once(',')?;
+    let (x, y) = (x.parse::<f64>().ok()?, y.parse::<f64>().ok()?);
+    (x.is_finite() && y.is_finite()).then_some((x, y))
+}
+
+/// Scrolls a reloaded tab back to where it was hibernated, once its load
+/// finished on the same page.
+fn restore_hibernated(view: &webkit6::WebView, tab: u64, ui_state: &RefCell<UiState>) {
+    let Some((pending, snapshot)) = ui_state.borrow_mut().pending_restore.take() else {
+        return;
+    };
+    let uri = view.uri().map(|uri| uri.to_string()).unwrap_or_default();
+    if pending != tab || !snapshot.applies_to(&uri) {
+        return;
+    }
+    let (x, y) = snapshot.scroll;
+    view.evaluate_javascript(
+        &format!("window.scrollTo({x}, {y});"),
+        None,
+        None,
+        None::<&gio::Cancellable>,
+        |_| {},
+    );
+}
+
+/// Asks the page to leave element fullscreen; WebKit then emits
+/// `leave-fullscreen`, which restores the window.
+fn exit_content_fullscreen(content_webview: &webkit6::WebView) {
+    content_webview.evaluate_javascript(
+        "if (document.fullscreenElement) { document.exitFullscreen(); }",
+        None,
+        None,
+        None::<&gio::Cancellable>,
+        |_| {},
+    );
+}
+
+fn create_webview(manager: Option<&webkit6::UserContentManager>) -> webkit6::WebView {
+    let settings = webkit6::Settings::builder()
+        .allow_file_access_from_file_urls(true)
+        .enable_javascript(true)
+        .build();
+
+    let mut builder = webkit6::WebView::builder().settings(&settings);
+    if let Some(manager) = manager {
+        builder = builder.user_content_manager(manager);
+    }
+
+    let webview = builder.build();
+    webview.set_hexpand(true);
+    webview.set_vexpand(true);
     webview
 }
 
 fn handle_message(
-    message: IncomingMessage,
+    command: IncomingCommand,
     ui_webview: &webkit6::WebView,
-    content_webview: &webkit6::WebView,
+    content_views: &ContentViews,
+    content_engine: &ContentEngine,
+    downloads: &Downloads,
+    script_dialogs: &ScriptDialogs,
+    permission_prompts: &PermissionPrompts,
+    tls_failure: &RefCell<Option<TlsFailure>>,
+    profile: &Profile,
     state: &Rc<RefCell<BrowserState>>,
-    home_uri: &str,
+    state_sync: &Rc<ipc::StateSync>,
     paned: &gtk::Paned,
     ui_state: &Rc<RefCell<UiState>>,
     default_favicon: &str,
     favicon_db: &Option<webkit6::FaviconDatabase>,
-) {
-    match message.r#type.as_str() {
-        "ui.ready" => {
+) -> Option<Value> {
+    let content_webview = &content_views.current();
+    let mut response = None;
+    match command {
+        IncomingCommand::UiReady {} => {
             ipc::send_assets(ui_webview, default_favicon);
-            ipc::send_state(ui_webview, &state.borrow());
+            state_sync.resync();
             ipc::send_sidebar_state(ui_webview, ui_state.borrow().sidebar_collapsed);
+            ipc::send_memory_state(ui_webview, content_engine.governor.memory_pressure());
+            ipc::send_bookmarks(ui_webview, &profile.bookmarks.borrow().list());
+            ipc::send_sessions(ui_webview, &profile.sessions.borrow().list());
+            ipc::send_settings(ui_webview, profile.settings.borrow().get());
             emit_nav_state(ui_webview, content_webview, false);
             if let Some(db) = favicon_db {
-                prefetch_all_favicons(db, state, ui_webview);
+                prefetch_all_favicons(db, &profile.favicons, state, ui_webview);
             }
         }
-        "tab.select" => {
-            if let Some(id) = message.payload.get("id").and_then(|v| v.as_u64()) {
-                let url = { state.borrow().tabs.get(&id).map(|t| t.url.clone()) };
-                if let Some(url) = url {
-                    if let Some(node) = state.borrow_mut().tabs.get_mut(&id) {
-                        node.is_suspended = false;
-                    }
-                    state.borrow_mut().set_active(id);
-                    load_url(content_webview, &url, home_uri);
-                    let state_ref = state.borrow();
-                    ipc::send_state(ui_webview, &state_ref);
+        IncomingCommand::StateResync {} => {
+            state_sync.resync();
+        }
+        IncomingCommand::TabSelect { id } => {
+            let url = { state.borrow().tabs.get(&id).map(|t| t.url.clone()) };
+            response = Some(json!({ "selected": url.is_some() }));
+            if let Some(url) = url {
+                hibernate_active(&content_views.current(), state, ui_state);
+                if let Some(node) = state.borrow_mut().tabs.get_mut(&id) {
+                    node.is_suspended = false;
                 }
+                content_engine.governor.clear_request(TabId::new(id));
+                state.borrow_mut().set_active(id);
+                {
+                    let mut ui_state = ui_state.borrow_mut();
+                    ui_state.crashes.forget(id);
+                    ui_state.pending_restore = ui_state
+                        .hibernation
+                        .take(TabId::new(id), &url)
+                        .map(|snapshot| (id, snapshot));
+                }
+                load_url(&content_views.show_active(state), &url);
+                state_sync.schedule();
             }
         }
-        "tab.toggle" => {
-            if let Some(id) = message.payload.get("id").and_then(|v| v.as_u64()) {
-                state.borrow_mut().toggle_expanded(id);
-                let state_ref = state.borrow();
-                ipc::send_state(ui_webview, &state_ref);
+        IncomingCommand::TabToggle { id } => {
+            state.borrow_mut().toggle_expanded(id);
+            state_sync.schedule();
+        }
+        IncomingCommand::TabCollapseAll {} => {
+            if state.borrow_mut().set_all_expanded(false) {
+                state_sync.schedule();
             }
         }
-        "tab.pin" => {
-            if let Some(id) = message.payload.get("id").and_then(|v| v.as_u64()) {
-                state.borrow_mut().toggle_pin(id);
-                let state_ref = state.borrow();
-                ipc::send_state(ui_webview, &state_ref);
+        IncomingCommand::TabExpandAll {} => {
+            if state.borrow_mut().set_all_expanded(true) {
+                state_sync.schedule();
             }
         }
-        "tab.mute" => {
-            if let Some(id) = message.payload.get("id").and_then(|v| v.as_u64()) {
-                state.borrow_mut().toggle_mute(id);
-                let state_ref = state.borrow();
-                ipc::send_state(ui_webview, &state_ref);
+        IncomingCommand::TabPin { id } => {
+            state.borrow_mut().toggle_pin(id);
+            let is_active = state.borrow().active == Some(id);
+            if is_active {
+                content_engine.sync_active(state);
             }
+            state_sync.schedule();
         }
-        "tab.unload" => {
-            if let Some(id) = message.payload.get("id").and_then(|v| v.as_u64()) {
-                state.borrow_mut().toggle_suspended(id);
-                let state_ref = state.borrow();
-                ipc::send_state(ui_webview, &state_ref);
+        IncomingCommand::TabMute { id } => {
+            // Groups cascade to every tab below them.
+            let muted = state.borrow().tabs.get(&id).map(|node| !node.is_muted);
+            let affected = match muted {
+                Some(muted) => state.borrow_mut().set_muted_recursive(id, muted),
+                None => Vec::new(),
+            };
+            let active = state.borrow().active;
+            if active.is_some_and(|active| affected.contains(&active)) {
+                // The is-muted notification writes the view's real state back.
+                content_engine.sync_active(state);
             }
+            state_sync.schedule();
+            response = Some(json!({ "ids": affected }));
         }
-        "tab.create" => {
-            let id = state
-                .borrow_mut()
-                .create_tab(None, "New Tab", "owl://home");
+        IncomingCommand::TabUnload { id } => {
+            let suspended = state
+                .borrow()
+                .tabs
+                .get(&id)
+                .map(|node| !node.is_suspended);
+            let affected = match suspended {
+                Some(suspended) => state.borrow_mut().set_suspended_recursive(id, suspended),
+                None => Vec::new(),
+            };
+            for tab in &affected {
+                if suspended == Some(true) {
+                    content_engine.governor.request_suspend(TabId::new(*tab));
+                } else {
+                    content_engine.governor.request_resume(TabId::new(*tab));
+                }
+            }
+            state_sync.schedule();
+            response = Some(json!({ "ids": affected }));
+        }
+        IncomingCommand::TabSuspendAll {} => {
+            let affected = state.borrow_mut().suspend_all_background();
+            content_engine.governor.suspend_all_background();
+            if !affected.is_empty() {
+                state_sync.schedule();
+            }
+            response = Some(json!({ "ids": affected }));
+        }
+        IncomingCommand::TabWakeAll {} => {
+            let affected = state.borrow_mut().wake_all();
+            content_engine.governor.wake_all();
+            if !affected.is_empty() {
+                state_sync.schedule();
+            }
+            response = Some(json!({ "ids": affected }));
+        }
+        IncomingCommand::TabCreate { private } => {
+            let new_tab_page = profile.settings.borrow().get().new_tab_page;
+            // Saved sessions are never reopened into a private tab.
+            if new_tab_page == NewTabPage::LastSession && !private {
+                let first_url = {
+                    let sessions = profile.sessions.borrow();
+                    let last = sessions.list().pop();
+                    last.and_then(|session| open_session(state, &sessions, &session.name))
+                };
+                if let Some(first_url) = first_url {
+                    state_sync.schedule();
+                    if let Some(db) = favicon_db {
+                        prefetch_all_favicons(db, &profile.favicons, state, ui_webview);
+                    }
+                    load_url(&content_views.show_active(state), &first_url);
+                    return Some(json!({ "tab": state.borrow().active }));
+                }
+            }
+            let url = match new_tab_page {
+                NewTabPage::Blank => internal::BLANK_URI,
+                NewTabPage::Home | NewTabPage::LastSession => internal::HOME_URI,
+            };
+            let id = if private {
+                state.borrow_mut().create_private_tab("Private Tab", url)
+            } else {
+                state.borrow_mut().create_tab(None, "New Tab", url)
+            };
             state.borrow_mut().set_active(id);
-            load_home(content_webview, home_uri);
-            let state_ref = state.borrow();
-            ipc::send_state(ui_webview, &state_ref);
+            load_url(&content_views.show_active(state), url);
+            state_sync.schedule();
+            response = Some(json!({ "tab": id }));
         }
-        "tab.close" => {
-            if let Some(id) = message.payload.get("id").and_then(|v| v.as_u64()) {
-                state.borrow_mut().remove_tab(id);
+        IncomingCommand::TabDuplicate { id } => {
+            let copy = state.borrow_mut().duplicate_tab(id);
+            if let Some(copy) = copy {
+                state.borrow_mut().set_active(copy);
+                let url = state.borrow().tabs[&copy].url.clone();
+                load_url(&content_views.show_active(state), &url);
+                state_sync.schedule();
+            }
+            response = Some(json!({ "tab": copy }));
+        }
+        IncomingCommand::TabClose { id } => {
+            script_dialogs.dismiss(id);
+            state.borrow_mut().remove_tab(id);
+            ui_state.borrow_mut().crashes.forget(id);
+            ui_state.borrow_mut().hibernation.forget(TabId::new(id));
+            content_engine.governor.clear_request(TabId::new(id));
+            let timing_out = ui_state
+                .borrow()
+                .load_timeout
+                .as_ref()
+                .is_some_and(|(tab, _)| *tab == id);
+            if timing_out {
+                cancel_load_timeout(ui_state);
+            }
+            let content_webview = &content_views.show_active(state);
+            let active = { state.borrow().active };
+            if let Some(active) = active {
+                let url = { state.borrow().tabs.get(&active).map(|t| t.url.clone()) };
+                if let Some(url) = url {
+                    load_url(content_webview, &url);
+                }
+            } else {
+                load_home(content_webview);
+            }
+            state_sync.schedule();
+        }
+        IncomingCommand::TabDedupe {} => {
+            let (closed, active_closed) = {
+                let mut state = state.borrow_mut();
+                let mut closed = Vec::new();
+                let mut active_closed = false;
+                for group in state.find_duplicates() {
+                    // Closing a tab also closes its children, which may
+                    // belong to a later group.
+                    let group: Vec<u64> = group
+                        .into_iter()
+                        .filter(|id| state.tabs.contains_key(id))
+                        .collect();
+                    let Some((keeper, duplicates)) = group.split_first() else {
+                        continue;
+                    };
+                    if duplicates.iter().any(|id| state.active == Some(*id)) {
+                        state.set_active(*keeper);
+                        active_closed = true;
+                    }
+                    for id in duplicates {
+                        state.remove_tab(*id);
+                        closed.push(*id);
+                    }
+                }
+                (closed, active_closed)
+            };
+            {
+                let mut ui_state = ui_state.borrow_mut();
+                for id in &closed {
+                    ui_state.crashes.forget(*id);
+                }
+            }
+            for id in &closed {
+                script_dialogs.dismiss(*id);
+                content_engine.governor.clear_request(TabId::new(*id));
+            }
+            let timing_out = ui_state
+                .borrow()
+                .load_timeout
+                .as_ref()
+                .is_some_and(|(tab, _)| closed.contains(tab));
+            if timing_out {
+                cancel_load_timeout(ui_state);
+            }
+            if active_closed {
+                let content_webview = &content_views.show_active(state);
                 let active = { state.borrow().active };
-                if let Some(active) = active {
-                    let url = { state.borrow().tabs.get(&active).map(|t| t.url.clone()) };
-                    if let Some(url) = url {
-                        load_url(content_webview, &url, home_uri);
+                let url = active.and_then(|active| {
+                    state.borrow().tabs.get(&active).map(|t| t.url.clone())
+                });
+                if let Some(url) = url {
+                    load_url(content_webview, &url);
+                }
+            }
+            if !closed.is_empty() {
+                state_sync.schedule();
+            }
+            response = Some(json!({ "closed": closed.len() }));
+        }
+        IncomingCommand::TabSearch { query } => {
+            let ids = state.borrow().search(&query);
+            response = Some(json!({ "ids": ids }));
+        }
+        IncomingCommand::TabCap { limit } => {
+            ui_state.borrow_mut().tab_cap = limit;
+            if let Some(cap) = limit {
+                enforce_tab_cap(state, state_sync, ui_webview, cap);
+            }
+        }
+        IncomingCommand::TabThumbnail { id, full_document } => {
+            // Only the active tab is rendered, so other tabs have no view to
+            // capture and get an empty thumbnail back.
+            let active = state.borrow().active;
+            if active != Some(id) {
+                ipc::send_thumbnail(ui_webview, id, None);
+                return None;
+            }
+            let Some(tab) = content_engine.bind(active) else {
+                return None;
+            };
+            let region = if full_document {
+                SnapshotRegion::FullDocument
+            } else {
+                SnapshotRegion::Viewport
+            };
+            let ui_view = ui_webview.clone();
+            content_engine
+                .engine
+                .capture_snapshot(tab, region, move |result| match result {
+                    Ok(png) => {
+                        let data_uri =
+                            format!("data:image/png;base64,{}", glib::base64_encode(&png));
+                        ipc::send_thumbnail(&ui_view, id, Some(data_uri));
                     }
-                } else {
-                    load_home(content_webview, home_uri);
+                    Err(error) => {
+                        log::warn!("failed to capture thumbnail for tab {id}: {error}");
+                        ipc::send_thumbnail(&ui_view, id, None);
+                    }
+                });
+        }
+        IncomingCommand::BookmarkAdd { folder } => {
+            let bookmark = {
+                let state = state.borrow();
+                state
+                    .active
+                    .and_then(|active| state.tabs.get(&active))
+                    .filter(|node| !node.is_group)
+                    .map(|node| Bookmark {
+                        title: node.title.clone(),
+                        url: node.url.clone(),
+                        folder: folder.filter(|folder| !folder.trim().is_empty()),
+                        added: Some(SystemTime::now()),
+                    })
+            };
+            let Some(bookmark) = bookmark else {
+                return None;
+            };
+            let added = profile.bookmarks.borrow_mut().add(bookmark);
+            if added {
+                ipc::send_bookmarks(ui_webview, &profile.bookmarks.borrow().list());
+            }
+            response = Some(json!({ "added": added }));
+        }
+        IncomingCommand::BookmarkRemove { url, folder } => {
+            let removed = profile
+                .bookmarks
+                .borrow_mut()
+                .remove(&url, folder.as_deref());
+            if removed {
+                ipc::send_bookmarks(ui_webview, &profile.bookmarks.borrow().list());
+            }
+        }
+        IncomingCommand::BookmarkList {} => {
+            ipc::send_bookmarks(ui_webview, &profile.bookmarks.borrow().list());
+        }
+        IncomingCommand::BookmarkExport { path } => {
+            let bookmarks = profile.bookmarks.borrow().list();
+            let html = storage::export::export_bookmark_list_html(&bookmarks);
+            response = Some(json!({ "exported": write_export(&path, "bookmarks", &html) }));
+        }
+        IncomingCommand::SessionSave { name } => {
+            let name = name.trim();
+            if name.is_empty() {
+                return Some(json!({ "saved": false }));
+            }
+            let mut snapshot = SessionSnapshot::default();
+            session::append_snapshot(&mut snapshot, &state.borrow(), 0);
+            let tabs: Vec<SavedTab> = snapshot
+                .tabs
+                .into_iter()
+                .filter(|tab| !tab.uri.starts_with("owl://"))
+                .map(|tab| SavedTab {
+                    title: tab.title,
+                    url: tab.uri,
+                    group: tab.group,
+                })
+                .collect();
+            let saved = !tabs.is_empty();
+            if saved {
+                let mut sessions = profile.sessions.borrow_mut();
+                sessions.save(SavedSession {
+                    name: name.to_string(),
+                    tabs,
+                });
+                ipc::send_sessions(ui_webview, &sessions.list());
+            }
+            response = Some(json!({ "saved": saved }));
+        }
+        IncomingCommand::SessionList {} => {
+            ipc::send_sessions(ui_webview, &profile.sessions.borrow().list());
+        }
+        IncomingCommand::SessionOpen { name } => {
+            let first_url = open_session(state, &profile.sessions.borrow(), &name);
+            response = Some(json!({ "opened": first_url.is_some() }));
+            if let Some(first_url) = first_url {
+                state_sync.schedule();
+                if let Some(db) = favicon_db {
+                    prefetch_all_favicons(db, &profile.favicons, state, ui_webview);
                 }
-                let state_ref = state.borrow();
-                ipc::send_state(ui_webview, &state_ref);
+                load_url(&content_views.show_active(state), &first_url);
             }
         }
-        "nav.go" => {
-            if let Some(url) = message.payload.get("url").and_then(|v| v.as_str()) {
-                let normalized = normalize_url(url);
-                if let Some(slug) = normalized.strip_prefix("owl://session/") {
-                if let Some(first_url) = open_session(state, slug) {
-                    let state_ref = state.borrow();
-                    ipc::send_state(ui_webview, &state_ref);
+        IncomingCommand::SessionDelete { name } => {
+            let mut sessions = profile.sessions.borrow_mut();
+            if sessions.delete(&name) {
+                ipc::send_sessions(ui_webview, &sessions.list());
+            }
+        }
+        IncomingCommand::SessionImport { path } => {
+            let text = match std::fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(error) => {
+                    log::warn!("failed to read session import {path}: {error}");
+                    return Some(json!({ "groups": 0 }));
+                }
+            };
+            let groups = {
+                let mut state = state.borrow_mut();
+                import::import_onetab(&text)
+                    .iter()
+                    .filter_map(|session| state.add_session(session))
+                    .count()
+            };
+            if groups > 0 {
+                state_sync.schedule();
+                if let Some(db) = favicon_db {
+                    prefetch_all_favicons(db, &profile.favicons, state, ui_webview);
+                }
+            }
+            response = Some(json!({ "groups": groups }));
+        }
+        IncomingCommand::SessionExport { path } => {
+            let mut snapshot = SessionSnapshot::default();
+            session::append_snapshot(&mut snapshot, &state.borrow(), 0);
+            let html = storage::export::export_bookmarks_html(&snapshot);
+            response = Some(json!({ "exported": write_export(&path, "session", &html) }));
+        }
+        IncomingCommand::HistoryQuery { query, limit } => {
+            let limit = limit.unwrap_or(HISTORY_QUERY_LIMIT);
+            let history = profile.history.borrow();
+            let entries = if query.trim().is_empty() {
+                history.recent(limit)
+            } else {
+                history.search(&query, limit)
+            };
+            let matches: Vec<ipc::HistoryMatch> = entries
+                .into_iter()
+                .map(|entry| ipc::HistoryMatch {
+                    url: entry.url,
+                    title: entry.title,
+                    visit_count: entry.visit_count,
+                })
+                .collect();
+            response = Some(json!({ "matches": matches }));
+        }
+        IncomingCommand::MetricsGet {} => {
+            let counters: serde_json::Map<String, Value> = metrics::snapshot()
+                .iter()
+                .map(|(counter, value)| (counter.name().to_string(), json!(value)))
+                .collect();
+            response = Some(json!({
+                "enabled": metrics::enabled(),
+                "counters": counters,
+            }));
+        }
+        IncomingCommand::DownloadCancel { id } => {
+            downloads.cancel(id);
+        }
+        IncomingCommand::DownloadDirectory { path } => {
+            let path = PathBuf::from(path);
+            if path.is_absolute() {
+                downloads.set_directory(path);
+            } else {
+                log::warn!("ignoring relative download directory {}", path.display());
+            }
+        }
+        IncomingCommand::NavGo { url } => {
+            let normalized = normalize_url(&url, &profile.settings.borrow().get().search_template);
+            if let Some(slug) = normalized.strip_prefix("owl://session/") {
+                let first_url = open_session(state, &profile.sessions.borrow(), slug);
+                if let Some(first_url) = first_url {
+                    state_sync.schedule();
                     if let Some(db) = favicon_db {
-                        prefetch_all_favicons(db, state, ui_webview);
+                        prefetch_all_favicons(db, &profile.favicons, state, ui_webview);
                     }
-                    load_url(content_webview, &first_url, home_uri);
+                    load_url(&content_views.show_active(state), &first_url);
                 } else {
-                    load_home(content_webview, home_uri);
-                }
-                    return;
+                    load_home(content_webview);
                 }
-                let active = { state.borrow().active };
+                return None;
+            }
+            let active = { state.borrow().active };
+            if let Some(active) = active {
+                state
+                    .borrow_mut()
+                    .update_tab(active, None, Some(&normalized));
+            }
+            {
+                let mut ui_state = ui_state.borrow_mut();
+                ui_state.focus_on_commit = profile.settings.borrow().get().focus_on_navigate;
                 if let Some(active) = active {
-                    state
-                        .borrow_mut()
-                        .update_tab(active, None, Some(&normalized));
+                    ui_state.crashes.forget(active);
                 }
-                load_url(content_webview, &normalized, home_uri);
-                let state_ref = state.borrow();
-                ipc::send_state(ui_webview, &state_ref);
             }
+            load_url(content_webview, &normalized);
+            state_sync.schedule();
+            response = Some(json!({ "url": normalized }));
         }
-        "nav.back" => {
-            content_webview.go_back();
+        IncomingCommand::NavBack {} => {
+            forget_crashes(state, ui_state);
+            if let Some(item) = history_step(content_webview, -1) {
+                content_webview.go_to_back_forward_list_item(&item);
+            }
         }
-        "nav.forward" => {
-            content_webview.go_forward();
+        IncomingCommand::NavForward {} => {
+            forget_crashes(state, ui_state);
+            if let Some(item) = history_step(content_webview, 1) {
+                content_webview.go_to_back_forward_list_item(&item);
+            }
         }
-        "nav.reload" => {
+        IncomingCommand::NavReload {} => {
+            forget_crashes(state, ui_state);
             content_webview.reload();
         }
-        "nav.stop" => {
+        IncomingCommand::NavStop {} => {
             content_webview.stop_loading();
         }
-        "ui.sidebar.toggle" => {
-            if let Some(collapsed) = message.payload.get("collapsed").and_then(|v| v.as_bool()) {
-                animate_sidebar(paned, ui_state, collapsed);
+        IncomingCommand::NavPrintPdf { path } => {
+            let Some(tab) = content_engine.bind(state.borrow().active) else {
+                ipc::send_print_result(ui_webview, path, Some("no active tab".to_string()));
+                return None;
+            };
+            let ui_view = ui_webview.clone();
+            let destination = PathBuf::from(&path);
+            content_engine
+                .engine
+                .print_to_pdf(tab, &destination, move |result| match result {
+                    Ok(()) => ipc::send_print_result(&ui_view, path, None),
+                    Err(error) => {
+                        log::warn!("failed to print to {path}: {error}");
+                        ipc::send_print_result(&ui_view, path, Some(error.to_string()));
+                    }
+                });
+        }
+        IncomingCommand::NavHistoryList {} => {
+            let entries = content_engine
+                .bind(state.borrow().active)
+                .map(|tab| content_engine.engine.back_forward_list(tab))
+                .unwrap_or_default();
+            let entries: Vec<_> = entries
+                .into_iter()
+                .filter(|entry| entry.is_current || entry.uri != internal::BLANK_URI)
+                .map(|entry| {
+                    json!({
+                        "title": entry.title,
+                        "uri": entry.uri,
+                        "offset": entry.offset,
+                        "current": entry.is_current,
+                    })
+                })
+                .collect();
+            response = Some(json!({ "entries": entries }));
+        }
+        IncomingCommand::NavHistoryGo { offset } => {
+            forget_crashes(state, ui_state);
+            let navigated = content_engine
+                .bind(state.borrow().active)
+                .is_some_and(|tab| content_engine.engine.go_to_offset(tab, offset));
+            response = Some(json!({ "navigated": navigated }));
+        }
+        IncomingCommand::NavPrefetch { url } => {
+            schedule_prefetch(content_engine, state, ui_state, url);
+        }
+        IncomingCommand::NavSuggest { query } => {
+            let suggestions = address_suggestions(&query, &state.borrow(), profile);
+            ipc::send_suggestions(
+                ui_webview,
+                ipc::SuggestionsState {
+                    query: &query,
+                    suggestions,
+                },
+            );
+        }
+        IncomingCommand::NavTlsProceed { host } => {
+            let failure = {
+                let mut pending = tls_failure.borrow_mut();
+                match pending.as_ref() {
+                    Some(failure) if failure.host == host => pending.take(),
+                    _ => None,
+                }
+            };
+            let Some(failure) = failure else {
+                log::warn!("ignoring TLS override for {host}: no pending certificate error");
+                return None;
+            };
+            if let Some(session) = content_webview.network_session() {
+                session.allow_tls_certificate_for_host(&failure.certificate, &failure.host);
+                content_webview.load_uri(&failure.uri);
+            }
+        }
+        IncomingCommand::NavReaderToggle {} => {
+            let reader = {
+                let mut state = state.borrow_mut();
+                let active = state.active;
+                let Some(node) = active.and_then(|id| state.tabs.get_mut(&id)) else {
+                    return None;
+                };
+                if node.is_group || node.url.starts_with("owl://") {
+                    return Some(json!({ "reader": false }));
+                }
+                node.is_reader = !node.is_reader;
+                node.is_reader
+            };
+            content_engine.sync_active(state);
+            response = Some(json!({ "reader": reader }));
+        }
+        IncomingCommand::NavZoom { step } => {
+            let zoom = {
+                let mut state = state.borrow_mut();
+                let active = state.active;
+                active.and_then(|id| state.step_zoom(id, step))
+            };
+            content_engine.sync_active(state);
+            response = Some(json!({ "zoom": zoom }));
+        }
+        IncomingCommand::NavSearchSet { template } => {
+            // `None` restores the default engine; invalid templates are ignored.
+            let template = template.unwrap_or_else(|| DEFAULT_SEARCH_TEMPLATE.to_string());
+            let result = profile.update_settings(|settings| settings.search_template = template);
+            if let Err(error) = result {
+                log::warn!("ignoring search template: {error}");
             }
+            response = Some(json!({ "set": result.is_ok() }));
         }
-        "nav.home" => {
-            load_home(content_webview, home_uri);
+        IncomingCommand::NavUserAgentSet { host, ua } => {
+            let host = host_key(&host);
+            if host.is_empty() {
+                return None;
+            }
+            let ua = ua.filter(|ua| !ua.trim().is_empty());
+            let changed = {
+                let mut user_agents = profile.user_agents.borrow_mut();
+                match ua {
+                    Some(ua) => user_agents.insert(host.clone(), ua.clone()) != Some(ua),
+                    None => user_agents.remove(&host).is_some(),
+                }
+            };
+            // Reload a page on the affected host so it is fetched with the
+            // new User-Agent.
+            let current_host = content_webview
+                .uri()
+                .and_then(|uri| Url::parse(&uri).ok())
+                .and_then(|url| url.host_str().map(host_key));
+            if changed && current_host.as_deref() == Some(host.as_str()) {
+                if let Some(uri) = content_webview.uri() {
+                    apply_user_agent(content_engine, state, profile, &uri);
+                }
+                content_webview.reload();
+            }
+        }
+        IncomingCommand::SidebarToggle { collapsed } => {
+            animate_sidebar(paned, ui_state, collapsed);
+        }
+        IncomingCommand::NavTimeout { seconds } => {
+            ui_state.borrow_mut().load_config.timeout = seconds
+                .filter(|seconds| *seconds > 0)
+                .map(Duration::from_secs);
+        }
+        IncomingCommand::FocusOnNavigate { enabled } => {
+            let _ = profile.update_settings(|settings| settings.focus_on_navigate = enabled);
+            if !enabled {
+                ui_state.borrow_mut().focus_on_commit = false;
+            }
+        }
+        IncomingCommand::NavHome {} => {
+            forget_crashes(state, ui_state);
+            let home_page = profile.settings.borrow().get().home_page.clone();
+            load_url(content_webview, &home_page);
+        }
+        IncomingCommand::ForceDarkSet { enabled } => {
+            // Listeners re-sync every window, this one included.
+            let _ = profile.update_settings(|settings| settings.force_dark = enabled);
+        }
+        IncomingCommand::ForceDarkHost { host, rule } => {
+            let host = host_key(&host);
+            if host.is_empty() {
+                return None;
+            }
+            {
+                let mut force_dark = profile.force_dark.borrow_mut();
+                match rule {
+                    Some(rule) => force_dark.hosts.insert(host, rule),
+                    None => force_dark.hosts.remove(&host),
+                };
+            }
+            content_engine.sync_active(state);
+        }
+        IncomingCommand::ContentFilterSet { enabled } => {
+            let _ = profile.update_settings(|settings| settings.content_filter = enabled);
+            response = Some(json!({ "active": content_engine.engine.content_filter_active() }));
+        }
+        IncomingCommand::PermissionRespond {
+            id,
+            allow,
+            dismissed,
+        } => {
+            let responded = permission_prompts.respond(
+                &mut *profile.permissions.borrow_mut(),
+                id,
+                allow,
+                dismissed,
+            );
+            response = Some(json!({ "responded": responded }));
+        }
+        IncomingCommand::SettingsGet {} => {
+            response = serde_json::to_value(profile.settings.borrow().get()).ok();
+        }
+        IncomingCommand::SettingsSet { changes } => {
+            let current = profile.settings.borrow().get().clone();
+            let result = merge_settings(&current, changes).and_then(|settings| {
+                profile
+                    .update_settings(|current| *current = settings)
+                    .map_err(|error| error.to_string())
+            });
+            response = Some(match result {
+                Ok(_) => json!({ "ok": true }),
+                Err(error) => json!({ "ok": false, "error": error }),
+            });
+        }
+        IncomingCommand::SettingsProxySet { mode, address } => {
+            let result = profile.update_settings(|settings| {
+                settings.proxy_mode = mode;
+                if let Some(address) = address {
+                    settings.proxy_address = address.trim().to_string();
+                }
+            });
+            response = Some(match result {
+                Ok(_) => json!({ "ok": true }),
+                Err(error) => json!({ "ok": false, "error": error.to_string() }),
+            });
+        }
+        IncomingCommand::SettingsA11ySet {
+            default_font_size,
+            minimum_font_size,
+            high_contrast,
+        } => {
+            let result = profile.update_settings(|settings| {
+                let accessibility = &mut settings.accessibility;
+                if let Some(size) = default_font_size {
+                    accessibility.default_font_size = size;
+                }
+                if let Some(size) = minimum_font_size {
+                    accessibility.minimum_font_size = size;
+                }
+                if let Some(enabled) = high_contrast {
+                    accessibility.high_contrast = enabled;
+                }
+            });
+            response = Some(match result {
+                Ok(_) => json!({ "ok": true }),
+                Err(error) => json!({ "ok": false, "error": error.to_string() }),
+            });
+        }
+        IncomingCommand::FindStart {
+            query,
+            case_sensitive,
+        } => {
+            let Some(tab) = content_engine.bind(state.borrow().active) else {
+                ipc::send_find_state(ui_webview, 0, 0);
+                return None;
+            };
+            let options = FindOptions {
+                case_sensitive,
+                ..FindOptions::default()
+            };
+            let ui_view = ui_webview.clone();
+            content_engine.engine.find_in_page(
+                tab,
+                &query,
+                options,
+                Rc::new(move |matches| {
+                    ipc::send_find_state(&ui_view, matches.current, matches.total);
+                }),
+            );
+        }
+        IncomingCommand::FindNext {} => {
+            if let Some(tab) = content_engine.bind(state.borrow().active) {
+                content_engine.engine.find_next(tab);
+            }
+        }
+        IncomingCommand::FindPrev {} => {
+            if let Some(tab) = content_engine.bind(state.borrow().active) {
+                content_engine.engine.find_previous(tab);
+            }
+        }
+        IncomingCommand::FindClose {} => {
+            content_engine.finish_find();
+            ipc::send_find_state(ui_webview, 0, 0);
         }
-        _ => {}
     }
+    response
 }
 
-fn load_home(webview: &webkit6::WebView, home_uri: &str) {
-    webview.load_uri(home_uri);
+/// Writes an exported bookmark file to the absolute `path`, returning whether
+/// it was written. `what` names the export in log messages.
+fn write_export(path: &str, what: &str, html: &str) -> bool {
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        log::warn!("ignoring relative export path {}", path.display());
+        return false;
+    }
+    match std::fs::write(&path, html) {
+        Ok(()) => true,
+        Err(error) => {
+            log::warn!("failed to export {what} to {}: {error}", path.display());
+            false
+        }
+    }
 }
 
-fn load_url(webview: &webkit6::WebView, url: &str, home_uri: &str) {
-    if url.starts_with("owl://") || url == "about:home" {
-        load_home(webview, home_uri);
+/// Overlays `changes` onto `settings`. Unknown keys and values of the wrong
+/// type are rejected; validation is left to the store.
+fn merge_settings(settings: &Settings, changes: Map<String, Value>) -> Result<Settings, String> {
+    let Ok(Value::Object(mut merged)) = serde_json::to_value(settings) else {
+        return Err("settings are not an object".to_string());
+    };
+    for (key, value) in changes {
+        if !merged.contains_key(&key) {
+            return Err(format!("unknown setting: {key}"));
+        }
+        merged.insert(key, value);
+    }
+    serde_json::from_value(Value::Object(merged)).map_err(|error| error.to_string())
+}
+
+/// Applies the settings that live on a window's engine and governor.
+fn apply_settings(content_engine: &ContentEngine, settings: &Settings) {
+    content_engine.engine.set_content_filter_enabled(settings.content_filter);
+    let accessibility = settings.accessibility;
+    content_engine
+        .engine
+        .set_font_sizes(accessibility.default_font_size, accessibility.minimum_font_size);
+    content_engine.engine.set_high_contrast(accessibility.high_contrast);
+    // Validation guarantees a manual proxy has a usable address.
+    let proxy = match settings.proxy_mode {
+        ProxyMode::System => Some(ProxyConfig::System),
+        ProxyMode::None => Some(ProxyConfig::Direct),
+        ProxyMode::Manual => proxy_uri(&settings.proxy_address).map(ProxyConfig::Manual),
+    };
+    if let Some(proxy) = proxy {
+        content_engine.engine.set_proxy(proxy);
+    }
+    let timer_scale = match settings.timer_clamp {
+        TimerClamp::Off => 0.0,
+        TimerClamp::Balanced => 1.0,
+        TimerClamp::Aggressive => 2.0,
+    };
+    content_engine
+        .governor
+        .set_hint_policy(HintPolicy::default().scale_timers(timer_scale));
+}
+
+/// Serves `owl://` pages; see `internal` for the scheme itself.
+fn register_internal_pages(profile: Rc<Profile>) {
+    let assets_dir = Assets::new().dir;
+    internal::register(move |page, file| match (page, file) {
+        ("home", file) => internal::page_file(&assets_dir, "home.html", file),
+        ("blank", "") => Some(internal::blank_page()),
+        ("history", "") => Some(internal::history_page(
+            &profile.history.borrow().recent(HISTORY_PAGE_LIMIT),
+        )),
+        ("bookmarks", "") => Some(internal::bookmarks_page(&profile.bookmarks.borrow().list())),
+        ("settings", "") => Some(internal::settings_page(profile.settings.borrow().get())),
+        _ => None,
+    });
+}
+
+/// The back (`step` -1) or forward (`step` 1) history item, skipping blank
+/// new-tab pages so they never become a stop of their own.
+fn history_step(view: &webkit6::WebView, step: i32) -> Option<webkit6::BackForwardListItem> {
+    let list = view.back_forward_list()?;
+    let mut offset = step;
+    loop {
+        let item = list.nth_item(offset)?;
+        if item.uri().as_deref() != Some(internal::BLANK_URI) {
+            return Some(item);
+        }
+        offset += step;
+    }
+}
+
+fn load_home(webview: &webkit6::WebView) {
+    webview.load_uri(internal::HOME_URI);
+}
+
+fn load_url(webview: &webkit6::WebView, url: &str) {
+    if url == "about:home" {
+        load_home(webview);
     } else {
         webview.load_uri(url);
     }
 }
 
-fn normalize_url(input: &str) -> String {
+/// Turns address bar input into a url, sending anything that does not look
+/// like an address to `search_template`.
+fn normalize_url(input: &str, search_template: &str) -> String {
     let trimmed = input.trim();
     
     // Empty input → home
@@ -497,41 +2377,35 @@ fn normalize_url(input: &str) -> String {
         return "owl://home".to_string();
     }
     
-    // Already has a scheme → return as-is (but validate)
-    if trimmed.contains("://") || trimmed.starts_with("about:") {
+    // Already has a scheme → return as-is (but validate). Only a well-formed
+    // scheme counts, so "what is http://x" is still a search.
+    if has_explicit_scheme(trimmed) || trimmed.starts_with("about:") {
         // Validate URL if possible
-        if let Ok(parsed) = Url::parse::<&str>(trimmed) {
+        if let Ok(parsed) = Url::parse(trimmed) {
             return parsed.to_string();
         }
         return trimmed.to_string();
     }
     
-    // Special owl:// protocol shortcuts
-    if trimmed.starts_with("owl://") {
-        return trimmed.to_string();
-    }
-    
-    // Common protocol shortcuts
-    match trimmed {
-        s if s.starts_with("localhost") || s.starts_with("127.0.0.1") => {
-            return format!("http://{}", trimmed);
-        }
-        _ => {}
+    // Contains spaces → search query
+    if trimmed.contains(char::is_whitespace) {
+        return build_search_url(trimmed, search_template);
     }
     
-    // Contains spaces → search query
-    if trimmed.contains(' ') {
-        return build_search_url(trimmed);
+    // Local development servers
+    let host = trimmed.split(['/', '?', '#']).next().unwrap_or(trimmed);
+    if host == "localhost" || host.starts_with("localhost:") {
+        return format!("http://{}", trimmed);
     }
     
     // IP address detection (v4 or v6)
-    if is_ip_address(trimmed) {
+    if is_ip_address(host) {
         return format!("http://{}", trimmed);
     }
     
     // Check for port number (localhost:3000, example.com:8080)
-    if trimmed.contains(':') && !trimmed.starts_with('[') {
-        if let Some((domain, port)) = trimmed.split_once(':') {
+    if host.contains(':') && !host.starts_with('[') {
+        if let Some((domain, port)) = host.split_once(':') {
             if port.parse::<u16>().is_ok() {
                 let scheme = if domain == "localhost" || domain.starts_with("127.") {
                     "http"
@@ -549,18 +2423,18 @@ fn normalize_url(input: &str) -> String {
         ".dev", ".app", ".tech", ".blog", ".shop", ".online", ".site"
     ];
     
-    let lower = trimmed.to_lowercase();
+    let lower = host.to_lowercase();
     let has_common_tld = COMMON_TLDS.iter().any(|tld| lower.ends_with(tld));
     
     // Has dot and common TLD → likely a domain
-    if trimmed.contains('.') && has_common_tld {
+    if host.contains('.') && has_common_tld {
         return format!("https://{}", trimmed);
     }
     
     // Has dot but uncommon/no TLD → check if valid domain structure
-    if trimmed.contains('.') {
+    if host.contains('.') {
         // Check if it looks like a domain (alphanumeric + hyphens)
-        let parts: Vec<&str> = trimmed.split('.').collect();
+        let parts: Vec<&str> = host.split('.').collect();
         if parts.len() >= 2 && parts.iter().all(|p| is_valid_domain_part(p)) {
             return format!("https://{}", trimmed);
         }
@@ -571,15 +2445,26 @@ fn normalize_url(input: &str) -> String {
     if is_valid_hostname(trimmed) && trimmed.len() > 2 {
         // Could be intranet, but safer to search
         // For intranet support, you might want to make this configurable
-        return build_search_url(trimmed);
+        return build_search_url(trimmed, search_template);
     }
     
     // Default: treat as search query
-    build_search_url(trimmed)
+    build_search_url(trimmed, search_template)
 }
 
-fn build_search_url(query: &str) -> String {
-    format!("https://duckduckgo.com/?q={}", urlencoding::encode(query))
+/// Fills the first `{}` in `template` with the percent-encoded query.
+fn build_search_url(query: &str, template: &str) -> String {
+    template.replacen("{}", &urlencoding::encode(query), 1)
+}
+
+/// Whether `input` starts with `scheme://`, per the RFC 3986 scheme grammar.
+fn has_explicit_scheme(input: &str) -> bool {
+    let Some((scheme, _)) = input.split_once("://") else {
+        return false;
+    };
+    let mut chars = scheme.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
 }
 
 fn is_ip_address(s: &str) -> bool {
@@ -607,56 +2492,184 @@ fn is_valid_hostname(s: &str) -> bool {
         && !s.ends_with('-')
 }
 
-fn open_session(state: &Rc<RefCell<BrowserState>>, slug: &str) -> Option<String> {
-    let (group_title, tabs) = session_template(slug)?;
-    let mut state_mut = state.borrow_mut();
-    let group_id = state_mut.create_group(group_title);
-    let mut first_url: Option<String> = None;
-    let mut first_id: Option<u64> = None;
+/// Opens the session named or slugged `name`, looking in the library before
+/// the built-in templates, activates its first tab, and returns that tab's url.
+fn open_session(
+    state: &Rc<RefCell<BrowserState>>,
+    library: &FileSessionLibrary,
+    name: &str,
+) -> Option<String> {
+    let groups = match library.get(name) {
+        Some(saved) => saved_session_groups(saved),
+        None => {
+            let (_, group_title, tabs) = SESSION_TEMPLATES
+                .iter()
+                .find(|(template_slug, _, _)| *template_slug == name)?;
+            vec![SessionState {
+                name: group_title.to_string(),
+                tabs: tabs
+                    .iter()
+                    .map(|(title, url)| SessionTab {
+                        title: title.to_string(),
+                        url: url.to_string(),
+                        is_pinned: false,
+                    })
+                    .collect(),
+            }]
+        }
+    };
 
-    for (title, url) in tabs {
-        let id = state_mut.create_tab(Some(group_id), title, url);
-        if first_url.is_none() {
-            first_url = Some(url.to_string());
-            first_id = Some(id);
+    let mut state_mut = state.borrow_mut();
+    let mut first_id = None;
+    for group in &groups {
+        if let Some(group_id) = state_mut.add_session(group) {
+            first_id = first_id.or_else(|| state_mut.tabs[&group_id].children.first().copied());
         }
     }
+    let first_id = first_id?;
+    state_mut.set_active(first_id);
+    Some(state_mut.tabs[&first_id].url.clone())
+}
 
-    if let Some(id) = first_id {
-        state_mut.set_active(id);
+/// Splits a saved session into one group per tab group, in order of first
+/// appearance. Ungrouped tabs form a group named after the session.
+fn saved_session_groups(saved: SavedSession) -> Vec<SessionState> {
+    let mut groups: Vec<SessionState> = Vec::new();
+    for tab in saved.tabs {
+        let name = tab.group.unwrap_or_else(|| saved.name.clone());
+        let session_tab = SessionTab {
+            title: tab.title,
+            url: tab.url,
+            is_pinned: false,
+        };
+        match groups.iter_mut().find(|group| group.name == name) {
+            Some(group) => group.tabs.push(session_tab),
+            None => groups.push(SessionState {
+                name,
+                tabs: vec![session_tab],
+            }),
+        }
     }
+    groups
+}
+
+/// Built-in sessions opened via `owl://session/<slug>`: slug, group title,
+/// and the `(title, url)` tabs of the group.
+const SESSION_TEMPLATES: &[(&str, &str, &[(&str, &str)])] = &[
+    (
+        "research-notes",
+        "Research Notes",
+        &[
+            ("WebKitGTK", "https://webkitgtk.org"),
+            ("Rust Book", "https://doc.rust-lang.org/book/"),
+            ("Fedora Docs", "https://docs.fedoraproject.org"),
+        ],
+    ),
+    (
+        "release-planning",
+        "Release Planning",
+        &[
+            ("GNOME Release", "https://release.gnome.org"),
+            ("Fedora Schedule", "https://fedorapeople.org/groups/schedule/"),
+            ("Issue Tracker", "https://gitlab.gnome.org"),
+        ],
+    ),
+    (
+        "wayland-checklist",
+        "Wayland Checklist",
+        &[
+            ("Wayland", "https://wayland.freedesktop.org"),
+            ("GTK4", "https://www.gtk.org"),
+            ("libadwaita", "https://gnome.pages.gitlab.gnome.org/libadwaita/"),
+        ],
+    ),
+];
+
+/// Collects address bar suggestions for `query` from open tabs, history, and
+/// saved then built-in sessions, in that order of preference.
+fn address_suggestions(
+    query: &str,
+    state: &BrowserState,
+    profile: &Profile,
+) -> Vec<suggest::Suggestion> {
+    let mut tab_ids: Vec<u64> = state.tabs.keys().copied().collect();
+    tab_ids.sort_unstable();
+    let tabs = tab_ids
+        .into_iter()
+        .map(|id| &state.tabs[&id])
+        .filter(|node| !node.is_group && !node.url.starts_with("owl://"))
+        .map(|node| (node.title.clone(), node.url.clone(), SuggestionKind::Tab));
+    let visited = profile
+        .history
+        .borrow()
+        .search(query, suggest::SUGGESTION_LIMIT)
+        .into_iter()
+        .map(|entry| (entry.title, entry.url, SuggestionKind::History));
+    let saved = profile
+        .sessions
+        .borrow()
+        .list()
+        .into_iter()
+        .map(|session| {
+            let url = format!("owl://session/{}", session_slug(&session.name));
+            (session.name, url, SuggestionKind::Session)
+        });
+    let templates = SESSION_TEMPLATES.iter().map(|(slug, title, _)| {
+        (
+            title.to_string(),
+            format!("owl://session/{slug}"),
+            SuggestionKind::Session,
+        )
+    });
 
-    first_url
+    suggest::rank(
+        query,
+        tabs.chain(visited).chain(saved).chain(templates),
+        suggest::SUGGESTION_LIMIT,
+    )
 }
 
-fn session_template(slug: &str) -> Option<(&'static str, &'static [(&'static str, &'static str)])> {
-    match slug {
-        "research-notes" => Some((
-            "Research Notes",
-            &[
-                ("WebKitGTK", "https://webkitgtk.org"),
-                ("Rust Book", "https://doc.rust-lang.org/book/"),
-                ("Fedora Docs", "https://docs.fedoraproject.org"),
-            ],
-        )),
-        "release-planning" => Some((
-            "Release Planning",
-            &[
-                ("GNOME Release", "https://release.gnome.org"),
-                ("Fedora Schedule", "https://fedorapeople.org/groups/schedule/"),
-                ("Issue Tracker", "https://gitlab.gnome.org"),
-            ],
-        )),
-        "wayland-checklist" => Some((
-            "Wayland Checklist",
-            &[
-                ("Wayland", "https://wayland.freedesktop.org"),
-                ("GTK4", "https://www.gtk.org"),
-                ("libadwaita", "https://gnome.pages.gitlab.gnome.org/libadwaita/"),
-            ],
-        )),
-        _ => None,
-    }
+/// Returns the target uri of a navigation policy decision.
+fn navigation_uri(decision: &webkit6::PolicyDecision) -> Option<String> {
+    let policy = decision.dynamic_cast_ref::<webkit6::NavigationPolicyDecision>()?;
+    let mut action = policy.navigation_action()?;
+    let request = action.request()?;
+    request.uri().map(|uri| uri.to_string())
+}
+
+/// Normalizes a host for the per-host override maps.
+fn host_key(host: &str) -> String {
+    let host = host.trim().to_ascii_lowercase();
+    host.strip_prefix("www.").map(str::to_string).unwrap_or(host)
+}
+
+/// Applies the User-Agent override for the host of `uri`, or the default
+/// User-Agent when there is none, to the active tab's view.
+fn apply_user_agent(
+    content_engine: &ContentEngine,
+    state: &RefCell<BrowserState>,
+    profile: &Profile,
+    uri: &str,
+) {
+    let Some(tab) = content_engine.sync_active(state) else {
+        return;
+    };
+    let host = Url::parse(uri)
+        .ok()
+        .and_then(|url| url.host_str().map(host_key));
+    let user_agents = profile.user_agents.borrow();
+    let user_agent = host
+        .and_then(|host| user_agents.get(&host))
+        .map(String::as_str);
+    content_engine.engine.set_user_agent(tab, user_agent);
+}
+
+fn tls_error_descriptions(errors: gio::TlsCertificateFlags) -> Vec<&'static str> {
+    TLS_ERROR_DESCRIPTIONS
+        .iter()
+        .filter(|(flag, _)| errors.contains(*flag))
+        .map(|(_, description)| *description)
+        .collect()
 }
 
 fn emit_nav_state(
@@ -665,24 +2678,244 @@ fn emit_nav_state(
     is_loading: bool,
 ) {
     let nav = NavState {
-        can_go_back: content_webview.can_go_back(),
-        can_go_forward: content_webview.can_go_forward(),
+        can_go_back: history_step(content_webview, -1).is_some(),
+        can_go_forward: history_step(content_webview, 1).is_some(),
         is_loading,
+        timed_out: false,
     };
     ipc::send_nav_state(ui_webview, nav);
 }
 
+/// Starts the load timeout for the active tab, replacing any running one.
+///
+/// On expiry the load is stopped, which leaves the previous page in place
+/// because loads that have committed already cancelled the timer.
+fn start_load_timeout(
+    view: &webkit6::WebView,
+    state: &RefCell<BrowserState>,
+    ui_state: &Rc<RefCell<UiState>>,
+    ui_webview: &webkit6::WebView,
+) {
+    cancel_load_timeout(ui_state);
+    let Some(timeout) = ui_state.borrow().load_config.timeout else {
+        return;
+    };
+    let Some(tab) = state.borrow().active else {
+        return;
+    };
+
+    let view = view.clone();
+    let ui_webview = ui_webview.clone();
+    let ui_state_weak = Rc::downgrade(ui_state);
+    let source = glib::timeout_add_local_once(timeout, move || {
+        if let Some(ui_state) = ui_state_weak.upgrade() {
+            ui_state.borrow_mut().load_timeout = None;
+        }
+        log::warn!("load of tab {tab} timed out after {}s", timeout.as_secs());
+        view.stop_loading();
+        ipc::send_nav_state(
+            &ui_webview,
+            NavState {
+                can_go_back: history_step(&view, -1).is_some(),
+                can_go_forward: history_step(&view, 1).is_some(),
+                is_loading: false,
+                timed_out: true,
+            },
+        );
+    });
+    ui_state.borrow_mut().load_timeout = Some((tab, source));
+}
+
+fn cancel_load_timeout(ui_state: &RefCell<UiState>) {
+    if let Some((_, source)) = ui_state.borrow_mut().load_timeout.take() {
+        source.remove();
+    }
+}
+
+/// Prefetches `url` for the active tab once the pointer has rested on it.
+///
+/// Each call replaces the previous pending prefetch, so sweeping across many
+/// links only prefetches the last one. Private tabs are never prefetched.
+fn schedule_prefetch(
+    content_engine: &ContentEngine,
+    state: &RefCell<BrowserState>,
+    ui_state: &Rc<RefCell<UiState>>,
+    url: String,
+) {
+    if let Some(source) = ui_state.borrow_mut().prefetch.take() {
+        source.remove();
+    }
+    if state.borrow().active_is_private() {
+        return;
+    }
+    let Some(tab) = content_engine.bind(state.borrow().active) else {
+        return;
+    };
+
+    let engine = Rc::clone(&content_engine.engine);
+    let ui_state_weak = Rc::downgrade(ui_state);
+    let delay = Duration::from_millis(PREFETCH_DEBOUNCE_MS);
+    let source = glib::timeout_add_local_once(delay, move || {
+        if let Some(ui_state) = ui_state_weak.upgrade() {
+            ui_state.borrow_mut().prefetch = None;
+        }
+        engine.prefetch(tab, &url);
+    });
+    ui_state.borrow_mut().prefetch = Some(source);
+}
+
+/// Polls the governor after the delay it recommends, reports memory
+/// pressure changes to the UI, and schedules the next poll.
+fn schedule_governor_poll(
+    content_engine: &Rc<ContentEngine>,
+    ui_webview: &webkit6::WebView,
+    ui_state: &Rc<RefCell<UiState>>,
+) {
+    let engine = Rc::clone(content_engine);
+    let ui_webview = ui_webview.clone();
+    let ui_state_for_poll = Rc::clone(ui_state);
+    let delay = content_engine.governor.next_poll_delay();
+    let source = glib::timeout_add_local_once(delay, move || {
+        ui_state_for_poll.borrow_mut().governor_poll = None;
+        engine.governor.poll();
+        let pressure = engine.governor.memory_pressure();
+        let previous = std::mem::replace(&mut ui_state_for_poll.borrow_mut().memory_pressure, pressure);
+        if previous != pressure {
+            ipc::send_memory_state(&ui_webview, pressure);
+        }
+        schedule_governor_poll(&engine, &ui_webview, &ui_state_for_poll);
+    });
+    ui_state.borrow_mut().governor_poll = Some(source);
+}
+
+/// Gives the active tab its automatic crash reloads back, as the user
+/// navigated it themselves.
+fn forget_crashes(state: &RefCell<BrowserState>, ui_state: &RefCell<UiState>) {
+    if let Some(active) = state.borrow().active {
+        ui_state.borrow_mut().crashes.forget(active);
+    }
+}
+
+/// Reloads the active tab's last good url after its web process died.
+///
+/// Attempts back off exponentially, and after `crash::MAX_CRASH_RELOADS`
+/// consecutive crashes the tab is left for the user to reload.
+fn recover_from_crash(
+    view: &webkit6::WebView,
+    reason: webkit6::WebProcessTerminationReason,
+    state: &Rc<RefCell<BrowserState>>,
+    ui_state: &Rc<RefCell<UiState>>,
+    ui_webview: &webkit6::WebView,
+) {
+    let reason = match reason {
+        webkit6::WebProcessTerminationReason::Crashed => "crashed",
+        webkit6::WebProcessTerminationReason::ExceededMemoryLimit => "memory_limit",
+        _ => return,
+    };
+    let active = {
+        let state = state.borrow();
+        state
+            .active
+            .and_then(|id| state.tabs.get(&id))
+            .map(|node| (node.id, node.url.clone()))
+    };
+    let Some((id, url)) = active else {
+        return;
+    };
+
+    let mut ui_state_mut = ui_state.borrow_mut();
+    if let Some(source) = ui_state_mut.crash_reload.take() {
+        source.remove();
+    }
+    let recovery = ui_state_mut.crashes.record(id, Instant::now());
+    let crashes = recovery.crashes;
+    log::error!("web process for tab {id} terminated ({reason}), crash {crashes}");
+    ipc::send_crash(
+        ui_webview,
+        ipc::CrashState {
+            id,
+            url: &url,
+            reason,
+            crashes,
+            reloading: recovery.reload_after.is_some(),
+        },
+    );
+    let Some(delay) = recovery.reload_after else {
+        return;
+    };
+
+    let view = view.clone();
+    let state = Rc::downgrade(state);
+    let ui_state_weak = Rc::downgrade(ui_state);
+    ui_state_mut.crash_reload = Some(glib::timeout_add_local_once(delay, move || {
+        if let Some(ui_state) = ui_state_weak.upgrade() {
+            ui_state.borrow_mut().crash_reload = None;
+        }
+        // The user may have moved to another tab in the meantime.
+        if state.upgrade().is_some_and(|state| state.borrow().active == Some(id)) {
+            load_url(&view, &url);
+        }
+    }));
+}
+
+/// Suspends least recently used tabs beyond `cap` and tells the UI.
+fn enforce_tab_cap(
+    state: &RefCell<BrowserState>,
+    state_sync: &Rc<ipc::StateSync>,
+    ui_webview: &webkit6::WebView,
+    cap: usize,
+) {
+    let suspended = state.borrow_mut().suspend_over_cap(cap);
+    if !suspended.is_empty() {
+        ipc::send_auto_suspended(ui_webview, suspended, cap);
+        state_sync.schedule();
+    }
+}
+
+/// Starts a redirect chain for the load that just started in `view` and
+/// feeds it every redirect of the main resource.
+fn track_redirects(
+    view: &webkit6::WebView,
+    state: &RefCell<BrowserState>,
+    redirects: &Rc<RefCell<Option<RedirectChain>>>,
+) {
+    let active = state.borrow().active;
+    let (Some(active), Some(uri)) = (active, view.uri()) else {
+        *redirects.borrow_mut() = None;
+        return;
+    };
+    *redirects.borrow_mut() = Some(RedirectChain::new(active, &uri));
+
+    // Each load gets a fresh main resource, so the handler goes away with it.
+    let Some(resource) = view.main_resource() else {
+        return;
+    };
+    let redirects = Rc::downgrade(redirects);
+    resource.connect_sent_request(move |_, request, redirected_response| {
+        let (Some(redirects), Some(target)) = (redirects.upgrade(), request.uri()) else {
+            return;
+        };
+        if let Some(chain) = redirects.borrow_mut().as_mut() {
+            chain.record_hop(redirected_response.status_code(), &target);
+        }
+    });
+}
+
 fn refresh_favicon(
     favicon_db: &webkit6::FaviconDatabase,
+    favicons: &Rc<RefCell<Favicons>>,
     state: &Rc<RefCell<BrowserState>>,
     ui_webview: &webkit6::WebView,
     page_uri: &str,
 ) {
-    queue_favicon_fetch(favicon_db, state, ui_webview, page_uri);
+    queue_favicon_fetch(favicon_db, favicons, state, ui_webview, page_uri);
 }
 
+/// Fills in favicons for every tab that has none, from the disk cache when
+/// the host is known and from the favicon database otherwise.
 fn prefetch_all_favicons(
     favicon_db: &webkit6::FaviconDatabase,
+    favicons: &Rc<RefCell<Favicons>>,
     state: &Rc<RefCell<BrowserState>>,
     ui_webview: &webkit6::WebView,
 ) {
@@ -709,13 +2942,19 @@ fn prefetch_all_favicons(
             .collect()
     };
 
+    let now = SystemTime::now();
     for url in urls {
-        queue_favicon_fetch(favicon_db, state, ui_webview, &url);
+        let cached = favicon_host(&url).and_then(|host| favicons.borrow().cache.get(&host, now));
+        match cached {
+            Some(icon) => update_favicon_state(state, ui_webview, &url, Some(icon)),
+            None => queue_favicon_fetch(favicon_db, favicons, state, ui_webview, &url),
+        }
     }
 }
 
 fn queue_favicon_fetch(
     favicon_db: &webkit6::FaviconDatabase,
+    favicons: &Rc<RefCell<Favicons>>,
     state: &Rc<RefCell<BrowserState>>,
     ui_webview: &webkit6::WebView,
     page_uri: &str,
@@ -724,13 +2963,9 @@ fn queue_favicon_fetch(
         return;
     }
 
-    if let Some(favicon_uri) = favicon_db.favicon_uri(page_uri) {
-        update_favicon_state(
-            state,
-            ui_webview,
-            page_uri,
-            Some(favicon_uri.to_string()),
-        );
+    // A page that finishes loading while its prefetch is pending would
+    // otherwise be looked up twice.
+    if !favicons.borrow_mut().in_flight.begin(page_uri) {
         return;
     }
 
@@ -738,22 +2973,48 @@ fn queue_favicon_fetch(
     let request_uri = page_uri.clone();
     let state = Rc::clone(state);
     let ui_webview = ui_webview.clone();
-    let db_for_cb = favicon_db.clone();
+    let favicons_for_cb = Rc::clone(favicons);
 
+    metrics::increment(Counter::FaviconFetches);
     favicon_db.favicon(&request_uri, None::<&gtk::gio::Cancellable>, move |result| {
-        if result.is_ok() {
-            if let Some(favicon_uri) = db_for_cb.favicon_uri(&page_uri) {
-                update_favicon_state(
-                    &state,
-                    &ui_webview,
-                    &page_uri,
-                    Some(favicon_uri.to_string()),
-                );
+        favicons_for_cb.borrow_mut().in_flight.finish(&page_uri);
+        let texture = match result {
+            Ok(texture) => texture,
+            Err(error) => {
+                log::debug!("favicon fetch failed page={page_uri}: {error}");
+                return;
             }
-        }
+        };
+        log::debug!("favicon fetched page={page_uri}");
+        let icon = favicon_data_uri(&texture);
+        cache_favicon(&favicons_for_cb, &page_uri, &icon);
+        update_favicon_state(&state, &ui_webview, &page_uri, Some(icon));
     });
 }
 
+/// Inlines the icon itself, so neither the tab strip nor a later session
+/// goes back to the network for it.
+fn favicon_data_uri(texture: &gtk::gdk::Texture) -> String {
+    let png = texture.save_to_png_bytes();
+    format!("data:image/png;base64,{}", glib::base64_encode(&png))
+}
+
+/// Key of the favicon cache: the page's host without a leading `www.`.
+fn favicon_host(page_uri: &str) -> Option<String> {
+    Url::parse(page_uri)
+        .ok()
+        .and_then(|url| url.host_str().map(host_key))
+}
+
+fn cache_favicon(favicons: &RefCell<Favicons>, page_uri: &str, favicon_uri: &str) {
+    if let Some(host) = favicon_host(page_uri) {
+        favicons
+            .borrow_mut()
+            .cache
+            .store(&host, favicon_uri, SystemTime::now());
+    }
+}
+
 fn update_favicon_state(
     state: &Rc<RefCell<BrowserState>>,
     ui_webview: &webkit6::WebView,
@@ -4,7 +4,6 @@ use std::path::{Path, PathBuf};
 pub struct Assets {
     pub dir: PathBuf,
     pub ui_uri: String,
-    pub home_uri: String,
     pub icon_path: PathBuf,
     pub icon_uri: String,
     pub icon_name: String,
@@ -15,7 +14,6 @@ impl Assets {
     pub fn new() -> Self {
         let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets");
         let ui_path = dir.join("ui.html");
-        let home_path = dir.join("home.html");
         let icon_path = dir.join("icon-128x128.ico");
         let default_favicon_path = dir.join("world_wide_web-128x128.ico");
         let icon_uri = file_uri(&icon_path);
@@ -33,7 +31,6 @@ impl Assets {
         Self {
             dir,
             ui_uri: file_uri(&ui_path),
-            home_uri: file_uri(&home_path),
             icon_path,
             icon_uri,
             icon_name,
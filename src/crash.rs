@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Automatic reloads of a tab before the crash page is left up.
+pub const MAX_CRASH_RELOADS: u32 = 3;
+/// Delay before the first automatic reload; it doubles with each crash.
+const CRASH_RELOAD_BACKOFF: Duration = Duration::from_millis(500);
+/// A crash this long after the previous one starts a new count: the page
+/// stayed up in between, so the reload worked.
+const CRASH_GRACE: Duration = Duration::from_secs(30);
+
+/// What to do about a crash; see [`CrashCounts::record`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrashRecovery {
+    /// Crashes in a row, this one included.
+    pub crashes: u32,
+    /// Delay before reloading, or `None` once the reloads are used up.
+    pub reload_after: Option<Duration>,
+}
+
+/// Consecutive web process crashes per tab.
+///
+/// A page that loads and then crashes again must not reload forever, so
+/// finishing a load does not reset the count. Only a navigation or tab
+/// selection by the user, or a page that stays up for a while, does.
+#[derive(Debug, Default)]
+pub struct CrashCounts {
+    tabs: HashMap<u64, (u32, Instant)>,
+}
+
+impl CrashCounts {
+    /// Counts a crash of `tab` at `now` and decides whether to reload it.
+    pub fn record(&mut self, tab: u64, now: Instant) -> CrashRecovery {
+        let (count, last) = self.tabs.entry(tab).or_insert((0, now));
+        if now.saturating_duration_since(*last) >= CRASH_GRACE {
+            *count = 0;
+        }
+        *count += 1;
+        *last = now;
+        let crashes = *count;
+        CrashRecovery {
+            crashes,
+            reload_after: (crashes <= MAX_CRASH_RELOADS)
+                .then(|| CRASH_RELOAD_BACKOFF * (1 << (crashes - 1))),
+        }
+    }
+
+    /// Starts `tab` over, e.g. after the user navigated it or closed it.
+    pub fn forget(&mut self, tab: u64) {
+        self.tabs.remove(&tab);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reloads_back_off_and_stop_until_the_user_steps_in() {
+        let mut counts = CrashCounts::default();
+        let mut now = Instant::now();
+        let delays: Vec<_> = (0..=MAX_CRASH_RELOADS)
+            .map(|_| {
+                now += Duration::from_secs(1);
+                counts.record(1, now).reload_after
+            })
+            .collect();
+        assert_eq!(
+            delays,
+            vec![
+                Some(Duration::from_millis(500)),
+                Some(Duration::from_millis(1000)),
+                Some(Duration::from_millis(2000)),
+                None,
+            ]
+        );
+        // Other tabs keep their own count.
+        assert_eq!(counts.record(2, now).crashes, 1);
+
+        counts.forget(1);
+        assert_eq!(counts.record(1, now).crashes, 1);
+
+        // A page that stayed up past the grace period crashed afresh.
+        let recovery = counts.record(1, now + CRASH_GRACE);
+        assert_eq!(recovery.crashes, 1);
+        assert_eq!(recovery.reload_after, Some(CRASH_RELOAD_BACKOFF));
+    }
+}
@@ -0,0 +1,151 @@
+use adw::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const RESPONSE_OK: &str = "ok";
+const RESPONSE_CANCEL: &str = "cancel";
+
+/// An open dialog and the page request it answers. The request is taken by
+/// whichever comes first: the user's response or a dismissal.
+struct OpenDialog {
+    alert: adw::AlertDialog,
+    request: Rc<RefCell<Option<webkit6::ScriptDialog>>>,
+}
+
+/// Shows page `alert`/`confirm`/`prompt` and `beforeunload` dialogs as
+/// Adwaita dialogs on the browser window.
+///
+/// WebKit keeps the page paused until the request is closed, but the rest of
+/// the browser keeps running while a dialog is open. Dialogs are tracked per
+/// tab so closing a tab can dismiss its dialog.
+pub struct ScriptDialogs {
+    window: gtk::Widget,
+    open: RefCell<HashMap<u64, OpenDialog>>,
+}
+
+impl ScriptDialogs {
+    pub fn new(window: &impl IsA<gtk::Widget>) -> Rc<Self> {
+        Rc::new(Self {
+            window: window.clone().upcast(),
+            open: RefCell::new(HashMap::new()),
+        })
+    }
+
+    /// Handles the `script-dialog` signal for the page of `tab`, shown at
+    /// `uri`. Always returns `true`: the page gets its answer once the user
+    /// responds.
+    pub fn show(
+        self: &Rc<Self>,
+        tab: u64,
+        uri: Option<&str>,
+        request: &webkit6::ScriptDialog,
+    ) -> bool {
+        self.dismiss(tab);
+
+        let message = request
+            .message()
+            .map(|message| message.to_string())
+            .unwrap_or_default();
+        let origin = uri
+            .and_then(|uri| url::Url::parse(uri).ok())
+            .and_then(|url| url.host_str().map(str::to_string));
+        let heading = match &origin {
+            Some(host) => format!("{host} says"),
+            None => "This page says".to_string(),
+        };
+
+        let kind = request.dialog_type();
+        let alert = match kind {
+            webkit6::ScriptDialogType::BeforeUnloadConfirm => {
+                let alert = adw::AlertDialog::new(
+                    Some("Leave this page?"),
+                    Some("Changes you made may not be saved."),
+                );
+                alert.add_response(RESPONSE_CANCEL, "Stay");
+                alert.add_response(RESPONSE_OK, "Leave");
+                alert.set_response_appearance(RESPONSE_OK, adw::ResponseAppearance::Destructive);
+                alert
+            }
+            webkit6::ScriptDialogType::Alert => {
+                let alert = adw::AlertDialog::new(Some(&heading), Some(&message));
+                alert.add_response(RESPONSE_OK, "OK");
+                alert
+            }
+            _ => {
+                let alert = adw::AlertDialog::new(Some(&heading), Some(&message));
+                alert.add_response(RESPONSE_CANCEL, "Cancel");
+                alert.add_response(RESPONSE_OK, "OK");
+                alert.set_response_appearance(RESPONSE_OK, adw::ResponseAppearance::Suggested);
+                alert
+            }
+        };
+        alert.set_default_response(Some(RESPONSE_OK));
+        alert.set_close_response(if kind == webkit6::ScriptDialogType::Alert {
+            RESPONSE_OK
+        } else {
+            RESPONSE_CANCEL
+        });
+
+        let entry = (kind == webkit6::ScriptDialogType::Prompt).then(|| {
+            let entry = gtk::Entry::builder().activates_default(true).build();
+            if let Some(text) = request.prompt_get_default_text() {
+                entry.set_text(&text);
+            }
+            alert.set_extra_child(Some(&entry));
+            // Weak, as the entry is the alert's own child.
+            entry.downgrade()
+        });
+
+        let pending = Rc::new(RefCell::new(Some(request.clone())));
+        let pending_for_response = Rc::clone(&pending);
+        let dialogs = Rc::downgrade(self);
+        alert.connect_response(None, move |alert, response| {
+            if let Some(dialogs) = dialogs.upgrade() {
+                let mut open = dialogs.open.borrow_mut();
+                if open.get(&tab).is_some_and(|open| open.alert == *alert) {
+                    open.remove(&tab);
+                }
+            }
+            let Some(request) = pending_for_response.borrow_mut().take() else {
+                return;
+            };
+            let confirmed = response == RESPONSE_OK;
+            match request.dialog_type() {
+                webkit6::ScriptDialogType::Confirm
+                | webkit6::ScriptDialogType::BeforeUnloadConfirm => {
+                    request.confirm_set_confirmed(confirmed);
+                }
+                // Leaving the text unset answers the prompt with null.
+                webkit6::ScriptDialogType::Prompt if confirmed => {
+                    if let Some(entry) = entry.as_ref().and_then(|entry| entry.upgrade()) {
+                        request.prompt_set_text(&entry.text());
+                    }
+                }
+                _ => {}
+            }
+            request.close();
+        });
+
+        self.open.borrow_mut().insert(
+            tab,
+            OpenDialog {
+                alert: alert.clone(),
+                request: pending,
+            },
+        );
+        alert.present(Some(&self.window));
+        true
+    }
+
+    /// Closes the dialog of `tab`, if any, answering it as cancelled.
+    pub fn dismiss(&self, tab: u64) {
+        let Some(open) = self.open.borrow_mut().remove(&tab) else {
+            return;
+        };
+        if let Some(request) = open.request.borrow_mut().take() {
+            request.close();
+        }
+        open.alert.force_close();
+    }
+}
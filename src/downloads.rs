@@ -0,0 +1,214 @@
+use crate::ipc::{self, DownloadState, DownloadStatus};
+use gtk::glib;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use webkit6::prelude::*;
+
+const FALLBACK_FILENAME: &str = "download";
+
+#[derive(Debug)]
+struct DownloadEntry {
+    download: webkit6::Download,
+    filename: String,
+    received: u64,
+    total: Option<u64>,
+    status: DownloadStatus,
+}
+
+/// Tracks content downloads and reports their progress to the UI.
+///
+/// Entries live only while their download runs.
+#[derive(Debug)]
+pub struct Downloads {
+    ui_webview: webkit6::WebView,
+    directory: RefCell<PathBuf>,
+    entries: RefCell<HashMap<u64, DownloadEntry>>,
+    next_id: Cell<u64>,
+}
+
+impl Downloads {
+    pub fn new(ui_webview: &webkit6::WebView) -> Rc<Self> {
+        Rc::new(Self {
+            ui_webview: ui_webview.clone(),
+            directory: RefCell::new(default_directory()),
+            entries: RefCell::new(HashMap::new()),
+            next_id: Cell::new(1),
+        })
+    }
+
+    /// Sets the directory used for downloads that have not started yet.
+    pub fn set_directory(&self, directory: PathBuf) {
+        *self.directory.borrow_mut() = directory;
+    }
+
+    /// Cancels an in-progress download. Unknown ids are ignored.
+    pub fn cancel(&self, id: u64) {
+        let download = {
+            let mut entries = self.entries.borrow_mut();
+            let Some(entry) = entries.get_mut(&id) else {
+                return;
+            };
+            if entry.status != DownloadStatus::InProgress {
+                return;
+            }
+            entry.status = DownloadStatus::Cancelled;
+            entry.download.clone()
+        };
+        // Cancelling emits `failed` and `finished` synchronously, so the
+        // borrow above must be released first.
+        download.cancel();
+        self.report(id);
+    }
+
+    /// Starts tracking a download reported by the network session.
+    pub fn track(self: &Rc<Self>, download: &webkit6::Download) {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.entries.borrow_mut().insert(
+            id,
+            DownloadEntry {
+                download: download.clone(),
+                filename: FALLBACK_FILENAME.to_string(),
+                received: 0,
+                total: None,
+                status: DownloadStatus::InProgress,
+            },
+        );
+        download.set_allow_overwrite(false);
+
+        let downloads = Rc::downgrade(self);
+        download.connect_decide_destination(move |download, suggested| {
+            let Some(downloads) = downloads.upgrade() else {
+                return false;
+            };
+            let filename = sanitize_filename(suggested);
+            let directory = downloads.directory.borrow().clone();
+            if let Err(error) = std::fs::create_dir_all(&directory) {
+                log::warn!(
+                    "failed to create download directory {}: {error}",
+                    directory.display()
+                );
+            }
+            let destination = unique_destination(&directory, &filename);
+            download.set_destination(&destination.to_string_lossy());
+
+            if let Some(entry) = downloads.entries.borrow_mut().get_mut(&id) {
+                entry.filename = destination
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or(filename);
+            }
+            downloads.report(id);
+            true
+        });
+
+        let downloads = Rc::downgrade(self);
+        download.connect_received_data(move |download, _length| {
+            let Some(downloads) = downloads.upgrade() else {
+                return;
+            };
+            if let Some(entry) = downloads.entries.borrow_mut().get_mut(&id) {
+                entry.received = download.received_data_length();
+                entry.total = download
+                    .response()
+                    .map(|response| response.content_length())
+                    .filter(|length| *length > 0);
+            }
+            downloads.report(id);
+        });
+
+        let downloads = Rc::downgrade(self);
+        download.connect_failed(move |_, error| {
+            let Some(downloads) = downloads.upgrade() else {
+                return;
+            };
+            if let Some(entry) = downloads.entries.borrow_mut().get_mut(&id) {
+                if entry.status == DownloadStatus::InProgress {
+                    log::warn!("download of {} failed: {error}", entry.filename);
+                    entry.status = DownloadStatus::Failed;
+                }
+            }
+            downloads.report(id);
+        });
+
+        let downloads = Rc::downgrade(self);
+        download.connect_finished(move |_| {
+            let Some(downloads) = downloads.upgrade() else {
+                return;
+            };
+            if let Some(entry) = downloads.entries.borrow_mut().get_mut(&id) {
+                // `finished` also follows `failed`; keep the failure status.
+                if entry.status == DownloadStatus::InProgress {
+                    entry.status = DownloadStatus::Finished;
+                }
+            }
+            downloads.report(id);
+            // `finished` is the last signal and its final status has been
+            // reported, so nothing reads the entry any more.
+            downloads.entries.borrow_mut().remove(&id);
+        });
+
+        self.report(id);
+    }
+
+    fn report(&self, id: u64) {
+        let entries = self.entries.borrow();
+        let Some(entry) = entries.get(&id) else {
+            return;
+        };
+        ipc::send_download(
+            &self.ui_webview,
+            DownloadState {
+                id,
+                filename: entry.filename.clone(),
+                received: entry.received,
+                total: entry.total,
+                status: entry.status,
+            },
+        );
+    }
+}
+
+fn default_directory() -> PathBuf {
+    glib::user_special_dir(glib::UserDirectory::Downloads)
+        .unwrap_or_else(|| glib::home_dir().join("Downloads"))
+}
+
+/// Reduces a server-suggested filename to a single safe path component.
+///
+/// Directory parts (either separator) are dropped, as are control characters
+/// and leading dots, so the name can neither escape the download directory
+/// nor become a hidden file.
+fn sanitize_filename(suggested: &str) -> String {
+    let name = suggested.rsplit(['/', '\\']).next().unwrap_or_default();
+    let name: String = name.chars().filter(|c| !c.is_control()).collect();
+    let name = name.trim().trim_start_matches('.');
+    if name.is_empty() {
+        FALLBACK_FILENAME.to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// Picks `name` inside `directory`, adding ` (n)` before the extension when
+/// the file already exists.
+fn unique_destination(directory: &Path, name: &str) -> PathBuf {
+    let candidate = directory.join(name);
+    if !candidate.exists() {
+        return candidate;
+    }
+
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    };
+    (1..)
+        .map(|index| match extension {
+            Some(extension) => directory.join(format!("{stem} ({index}).{extension}")),
+            None => directory.join(format!("{stem} ({index})")),
+        })
+        .find(|path| !path.exists())
+        .unwrap_or(candidate)
+}
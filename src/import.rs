@@ -0,0 +1,99 @@
+use tabs::state::{SessionState, SessionTab};
+use url::Url;
+
+/// Parses a OneTab export: one `url | title` line per tab, with groups
+/// separated by blank lines.
+///
+/// Lines without a title use the url instead; lines that do not start with
+/// a valid url are skipped, and so are groups left empty. Groups are named
+/// "Imported", "Imported 2", and so on.
+pub fn import_onetab(text: &str) -> Vec<SessionState> {
+    let mut groups: Vec<Vec<SessionTab>> = Vec::new();
+    let mut current = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !current.is_empty() {
+                groups.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some(tab) = parse_line(line) {
+            current.push(tab);
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+
+    groups
+        .into_iter()
+        .enumerate()
+        .map(|(index, tabs)| SessionState {
+            name: match index {
+                0 => "Imported".to_string(),
+                _ => format!("Imported {}", index + 1),
+            },
+            tabs,
+        })
+        .collect()
+}
+
+fn parse_line(line: &str) -> Option<SessionTab> {
+    let (url, title) = match line.split_once(" | ") {
+        Some((url, title)) => (url.trim(), title.trim()),
+        None => (line.trim_end_matches('|').trim(), ""),
+    };
+    let url = Url::parse(url).ok()?;
+    if url.cannot_be_a_base() {
+        return None;
+    }
+    let url = url.to_string();
+    Some(SessionTab {
+        title: if title.is_empty() {
+            url.clone()
+        } else {
+            title.to_string()
+        },
+        url,
+        is_pinned: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_blocks_into_groups() {
+        let text = "\
+https://gnome.org/ | GNOME
+not a url | Broken
+https://gtk.org/
+
+\t
+mailto:someone@example.com | Mail
+https://webkit.org/ |
+";
+        let groups = import_onetab(text);
+        assert_eq!(groups.len(), 2);
+
+        assert_eq!(groups[0].name, "Imported");
+        let tabs: Vec<(&str, &str)> = groups[0]
+            .tabs
+            .iter()
+            .map(|tab| (tab.title.as_str(), tab.url.as_str()))
+            .collect();
+        assert_eq!(
+            tabs,
+            vec![
+                ("GNOME", "https://gnome.org/"),
+                ("https://gtk.org/", "https://gtk.org/"),
+            ]
+        );
+
+        assert_eq!(groups[1].name, "Imported 2");
+        assert_eq!(groups[1].tabs.len(), 1);
+        assert_eq!(groups[1].tabs[0].title, "https://webkit.org/");
+    }
+}
@@ -0,0 +1,47 @@
+use std::collections::HashSet;
+
+/// Keys of asynchronous requests that have been started but not completed.
+///
+/// Used to drop a second request for a key while the first is still
+/// pending; the completion callback must call [`InFlight::finish`].
+#[derive(Debug, Default)]
+pub struct InFlight {
+    keys: HashSet<String>,
+}
+
+impl InFlight {
+    /// Marks `key` as in flight. Returns `false` if it already was, in which
+    /// case the caller should not start another request.
+    pub fn begin(&mut self, key: &str) -> bool {
+        if self.keys.contains(key) {
+            return false;
+        }
+        self.keys.insert(key.to_string())
+    }
+
+    /// Marks the request for `key` as completed.
+    pub fn finish(&mut self, key: &str) {
+        self.keys.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_repeated_requests_until_finished() {
+        let mut in_flight = InFlight::default();
+        let mut fetches = 0;
+        for _ in 0..2 {
+            if in_flight.begin("https://gnome.org/") {
+                fetches += 1;
+            }
+        }
+        assert_eq!(fetches, 1);
+        assert!(in_flight.begin("https://webkitgtk.org/"));
+
+        in_flight.finish("https://gnome.org/");
+        assert!(in_flight.begin("https://gnome.org/"));
+    }
+}
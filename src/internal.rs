@@ -0,0 +1,285 @@
+//! Browser-owned pages served under the `owl://` scheme.
+//!
+//! `owl://home` is the static home page from the assets directory; history,
+//! bookmarks and settings are rendered from the profile on every request, so
+//! a reload always shows current data.
+
+use gtk::{gio, glib};
+use std::path::Path;
+use std::time::SystemTime;
+use storage::settings::{NewTabPage, ProxyMode, Settings, TimerClamp};
+use storage::{Bookmark, HistoryEntry};
+use webkit6::prelude::*;
+
+pub const SCHEME: &str = "owl";
+pub const HOME_URI: &str = "owl://home";
+/// Empty new-tab page. Back and forward skip it.
+pub const BLANK_URI: &str = "owl://blank";
+
+/// Static files a page may reference relatively, e.g. `owl://home/home.css`.
+const PAGE_ASSETS: &[(&str, &str)] = &[
+    ("home.css", "text/css"),
+    ("home.js", "text/javascript"),
+    ("icon-128x128.ico", "image/x-icon"),
+];
+
+/// Body and content type for one `owl://` request.
+pub struct InternalResponse {
+    pub body: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+impl InternalResponse {
+    fn html(body: String) -> Self {
+        Self {
+            body: body.into_bytes(),
+            content_type: "text/html",
+        }
+    }
+}
+
+/// Registers the `owl` scheme on the default web context. `resolve` gets the
+/// page (the url host) and the file within it, empty for the page itself,
+/// and returns `None` for anything it does not serve.
+///
+/// Must be called after GTK is initialised and before the first `owl://`
+/// load.
+pub fn register(resolve: impl Fn(&str, &str) -> Option<InternalResponse> + 'static) {
+    let Some(context) = webkit6::WebContext::default() else {
+        log::warn!("no default web context, owl:// pages are unavailable");
+        return;
+    };
+    if let Some(security) = context.security_manager() {
+        // Local, so pages on other origins cannot link to or frame them.
+        security.register_uri_scheme_as_local(SCHEME);
+    }
+
+    context.register_uri_scheme(SCHEME, move |request| {
+        let uri = request.uri().map(|uri| uri.to_string()).unwrap_or_default();
+        let response = split_uri(&uri).and_then(|(page, file)| resolve(page, file));
+        match response {
+            Some(response) => {
+                let length = response.body.len() as i64;
+                let stream =
+                    gio::MemoryInputStream::from_bytes(&glib::Bytes::from_owned(response.body));
+                request.finish(&stream, length, Some(response.content_type));
+            }
+            None => {
+                log::debug!("no internal page for {uri}");
+                let mut error = glib::Error::new(
+                    gio::IOErrorEnum::NotFound,
+                    &format!("No such page: {uri}"),
+                );
+                request.finish_error(&mut error);
+            }
+        }
+    });
+}
+
+/// Splits `owl://page/file?query#fragment` into `(page, file)`.
+fn split_uri(uri: &str) -> Option<(&str, &str)> {
+    let rest = uri.strip_prefix("owl://")?;
+    let rest = rest.split(['?', '#']).next().unwrap_or_default();
+    let (page, file) = rest.split_once('/').unwrap_or((rest, ""));
+    if page.is_empty() {
+        return None;
+    }
+    Some((page, file))
+}
+
+/// Serves `file` from `dir` if it is on the page asset allowlist, or
+/// `index` when `file` is empty.
+pub fn page_file(dir: &Path, index: &str, file: &str) -> Option<InternalResponse> {
+    let (name, content_type) = if file.is_empty() {
+        (index, "text/html")
+    } else {
+        *PAGE_ASSETS.iter().find(|(name, _)| *name == file)?
+    };
+    match std::fs::read(dir.join(name)) {
+        Ok(body) => Some(InternalResponse { body, content_type }),
+        Err(err) => {
+            log::warn!("failed to read {name}: {err}");
+            None
+        }
+    }
+}
+
+pub fn blank_page() -> InternalResponse {
+    InternalResponse::html(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\" />\n<title>New Tab</title>\n\
+         <meta name=\"color-scheme\" content=\"light dark\" />\n</head>\n<body></body>\n</html>\n"
+            .to_string(),
+    )
+}
+
+pub fn history_page(entries: &[HistoryEntry]) -> InternalResponse {
+    let mut rows = String::new();
+    for entry in entries {
+        let title = if entry.title.is_empty() { &entry.url } else { &entry.title };
+        rows.push_str(&format!(
+            "<li><a href=\"{url}\">{title}</a> <span class=\"meta\">{visits} visits, {ago}</span></li>\n",
+            url = escape(&entry.url),
+            title = escape(title),
+            visits = entry.visit_count,
+            ago = time_ago(entry.last_visit),
+        ));
+    }
+    InternalResponse::html(page("History", &list_or_empty(rows, "No history yet.")))
+}
+
+pub fn bookmarks_page(bookmarks: &[Bookmark]) -> InternalResponse {
+    let mut rows = String::new();
+    for bookmark in bookmarks {
+        let folder = bookmark
+            .folder
+            .as_deref()
+            .map(|folder| format!(" <span class=\"meta\">{}</span>", escape(folder)))
+            .unwrap_or_default();
+        rows.push_str(&format!(
+            "<li><a href=\"{url}\">{title}</a>{folder}</li>\n",
+            url = escape(&bookmark.url),
+            title = escape(&bookmark.title),
+        ));
+    }
+    InternalResponse::html(page("Bookmarks", &list_or_empty(rows, "No bookmarks yet.")))
+}
+
+pub fn settings_page(settings: &Settings) -> InternalResponse {
+    let on_off = |enabled: bool| if enabled { "On" } else { "Off" };
+    let new_tab_page = match settings.new_tab_page {
+        NewTabPage::Home => "Home page",
+        NewTabPage::Blank => "Blank page",
+        NewTabPage::LastSession => "Last saved session",
+    };
+    let timer_clamp = match settings.timer_clamp {
+        TimerClamp::Off => "Off",
+        TimerClamp::Balanced => "Balanced",
+        TimerClamp::Aggressive => "Aggressive",
+    };
+    let rows = [
+        ("Search engine", format!("<code>{}</code>", escape(&settings.search_template))),
+        ("Home page", format!("<code>{}</code>", escape(&settings.home_page))),
+        ("New tabs open", new_tab_page.to_string()),
+        ("Focus page after navigating", on_off(settings.focus_on_navigate).to_string()),
+        ("Background timer throttling", timer_clamp.to_string()),
+        ("Dark pages", on_off(settings.force_dark).to_string()),
+        ("High contrast pages", on_off(settings.accessibility.high_contrast).to_string()),
+        (
+            "Text size",
+            match settings.accessibility.minimum_font_size {
+                0 => format!("{} px", settings.accessibility.default_font_size),
+                minimum => format!(
+                    "{} px, at least {minimum} px",
+                    settings.accessibility.default_font_size
+                ),
+            },
+        ),
+        (
+            "Zoom",
+            if settings.zoom_text_only { "Text only" } else { "Whole page" }.to_string(),
+        ),
+        ("Content filtering", on_off(settings.content_filter).to_string()),
+        (
+            "Proxy",
+            match settings.proxy_mode {
+                ProxyMode::System => "System settings".to_string(),
+                ProxyMode::None => "None".to_string(),
+                ProxyMode::Manual => format!("<code>{}</code>", escape(&settings.proxy_address)),
+            },
+        ),
+        (
+            "Memory pressure",
+            format!(
+                "moderate below {}% free, severe below {}% free",
+                settings.memory_moderate_percent, settings.memory_severe_percent
+            ),
+        ),
+    ];
+    let mut body = String::from("<dl>\n");
+    for (label, value) in rows {
+        body.push_str(&format!("<dt>{label}</dt><dd>{value}</dd>\n"));
+    }
+    body.push_str("</dl>\n<p class=\"meta\">Change these from the command palette (Ctrl+K).</p>");
+    InternalResponse::html(page("Settings", &body))
+}
+
+fn list_or_empty(rows: String, empty: &str) -> String {
+    if rows.is_empty() {
+        format!("<p class=\"meta\">{empty}</p>")
+    } else {
+        format!("<ul>\n{rows}</ul>")
+    }
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\" />\n<title>{title}</title>\n\
+         <link rel=\"stylesheet\" href=\"owl://home/home.css\" />\n</head>\n\
+         <body class=\"internal-page\">\n<h1>{title}</h1>\n{body}\n</body>\n</html>\n"
+    )
+}
+
+fn time_ago(time: SystemTime) -> String {
+    let seconds = SystemTime::now()
+        .duration_since(time)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_uri_separates_page_and_file() {
+        assert_eq!(split_uri("owl://home"), Some(("home", "")));
+        assert_eq!(split_uri("owl://home/home.css"), Some(("home", "home.css")));
+        assert_eq!(split_uri("owl://history?q=x#top"), Some(("history", "")));
+        assert_eq!(split_uri("owl://"), None);
+        assert_eq!(split_uri("https://home"), None);
+    }
+
+    #[test]
+    fn page_file_only_serves_allowlisted_assets() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+        assert!(page_file(&dir, "home.html", "").is_some());
+        assert!(page_file(&dir, "home.html", "home.css").is_some());
+        assert!(page_file(&dir, "home.html", "../Cargo.toml").is_none());
+        assert!(page_file(&dir, "home.html", "ui.js").is_none());
+    }
+
+    #[test]
+    fn rendered_pages_escape_profile_data() {
+        let bookmark = Bookmark {
+            title: "<script>".to_string(),
+            url: "https://example.com/?a=1&b=\"2\"".to_string(),
+            folder: None,
+            added: None,
+        };
+        let page = String::from_utf8(bookmarks_page(&[bookmark]).body).unwrap();
+        assert!(page.contains("&lt;script&gt;"));
+        assert!(page.contains("?a=1&amp;b=&quot;2&quot;"));
+        assert!(!page.contains("<script>"));
+    }
+}
@@ -1,17 +1,223 @@
-use crate::state::BrowserState;
+use gtk::glib;
+use scheduler::MemoryPressure;
 use serde::{Deserialize, Serialize};
-use serde_json::json;
+use serde_json::{json, Map, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use storage::library::session_slug;
+use storage::settings::{ProxyMode, Settings};
+use storage::{Bookmark, SavedSession};
+use tabs::state::{BrowserState, UiTabNode};
+use ui::suggest::Suggestion;
+use util::metrics::{self, Counter};
 use webkit6::prelude::*;
 
+/// Command sent from the UI WebView.
+///
+/// Payloads are validated during deserialization, so handlers only ever see
+/// well-formed commands.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum IncomingCommand {
+    #[serde(rename = "ui.ready")]
+    UiReady {},
+    #[serde(rename = "state.resync")]
+    StateResync {},
+    #[serde(rename = "ui.sidebar.toggle")]
+    SidebarToggle { collapsed: bool },
+    #[serde(rename = "ui.focus_on_navigate")]
+    FocusOnNavigate { enabled: bool },
+    #[serde(rename = "tab.select")]
+    TabSelect { id: u64 },
+    #[serde(rename = "tab.toggle")]
+    TabToggle { id: u64 },
+    #[serde(rename = "tab.collapse_all")]
+    TabCollapseAll {},
+    #[serde(rename = "tab.expand_all")]
+    TabExpandAll {},
+    #[serde(rename = "tab.pin")]
+    TabPin { id: u64 },
+    #[serde(rename = "tab.mute")]
+    TabMute { id: u64 },
+    #[serde(rename = "tab.unload")]
+    TabUnload { id: u64 },
+    #[serde(rename = "tab.suspend_all")]
+    TabSuspendAll {},
+    #[serde(rename = "tab.wake_all")]
+    TabWakeAll {},
+    #[serde(rename = "tab.create")]
+    TabCreate {
+        #[serde(default)]
+        private: bool,
+    },
+    #[serde(rename = "tab.close")]
+    TabClose { id: u64 },
+    #[serde(rename = "tab.duplicate")]
+    TabDuplicate { id: u64 },
+    #[serde(rename = "tab.dedupe")]
+    TabDedupe {},
+    #[serde(rename = "tab.search")]
+    TabSearch { query: String },
+    #[serde(rename = "tab.cap")]
+    TabCap {
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    #[serde(rename = "tab.thumbnail")]
+    TabThumbnail {
+        id: u64,
+        #[serde(default)]
+        full_document: bool,
+    },
+    #[serde(rename = "bookmark.add")]
+    BookmarkAdd {
+        #[serde(default)]
+        folder: Option<String>,
+    },
+    #[serde(rename = "bookmark.remove")]
+    BookmarkRemove {
+        url: String,
+        #[serde(default)]
+        folder: Option<String>,
+    },
+    #[serde(rename = "bookmark.list")]
+    BookmarkList {},
+    #[serde(rename = "bookmark.export")]
+    BookmarkExport { path: String },
+    #[serde(rename = "session.save")]
+    SessionSave { name: String },
+    #[serde(rename = "session.list")]
+    SessionList {},
+    #[serde(rename = "session.open")]
+    SessionOpen { name: String },
+    #[serde(rename = "session.delete")]
+    SessionDelete { name: String },
+    #[serde(rename = "session.import")]
+    SessionImport { path: String },
+    #[serde(rename = "session.export")]
+    SessionExport { path: String },
+    #[serde(rename = "history.query")]
+    HistoryQuery {
+        query: String,
+        #[serde(default)]
+        limit: Option<usize>,
+    },
+    #[serde(rename = "metrics.get")]
+    MetricsGet {},
+    #[serde(rename = "download.cancel")]
+    DownloadCancel { id: u64 },
+    #[serde(rename = "download.directory")]
+    DownloadDirectory { path: String },
+    #[serde(rename = "nav.go")]
+    NavGo { url: String },
+    #[serde(rename = "nav.back")]
+    NavBack {},
+    #[serde(rename = "nav.forward")]
+    NavForward {},
+    #[serde(rename = "nav.reload")]
+    NavReload {},
+    #[serde(rename = "nav.stop")]
+    NavStop {},
+    #[serde(rename = "nav.print.pdf")]
+    NavPrintPdf { path: String },
+    #[serde(rename = "nav.history.list")]
+    NavHistoryList {},
+    #[serde(rename = "nav.history.go")]
+    NavHistoryGo { offset: i32 },
+    #[serde(rename = "nav.home")]
+    NavHome {},
+    #[serde(rename = "nav.suggest")]
+    NavSuggest { query: String },
+    #[serde(rename = "nav.prefetch")]
+    NavPrefetch { url: String },
+    #[serde(rename = "nav.tls.proceed")]
+    NavTlsProceed { host: String },
+    #[serde(rename = "nav.timeout")]
+    NavTimeout { seconds: Option<u64> },
+    #[serde(rename = "nav.reader.toggle")]
+    NavReaderToggle {},
+    /// Zooms the active tab `step` preset levels in (positive) or out
+    /// (negative); 0 resets.
+    #[serde(rename = "nav.zoom")]
+    NavZoom { step: i32 },
+    #[serde(rename = "nav.search.set")]
+    NavSearchSet { template: Option<String> },
+    #[serde(rename = "nav.ua.set")]
+    NavUserAgentSet { host: String, ua: Option<String> },
+    #[serde(rename = "force_dark.set")]
+    ForceDarkSet { enabled: bool },
+    #[serde(rename = "force_dark.host")]
+    ForceDarkHost {
+        host: String,
+        rule: Option<ForceDarkRule>,
+    },
+    #[serde(rename = "content_filter.set")]
+    ContentFilterSet { enabled: bool },
+    #[serde(rename = "permission.respond")]
+    PermissionRespond {
+        id: u64,
+        allow: bool,
+        /// The prompt was closed without an answer.
+        #[serde(default)]
+        dismissed: bool,
+    },
+    #[serde(rename = "settings.get")]
+    SettingsGet {},
+    /// Partial update: each key replaces the setting of the same name.
+    #[serde(rename = "settings.set")]
+    SettingsSet { changes: Map<String, Value> },
+    /// Sets the proxy mode; `address` is required for a manual proxy and
+    /// kept for later otherwise.
+    #[serde(rename = "settings.proxy.set")]
+    SettingsProxySet {
+        mode: ProxyMode,
+        #[serde(default)]
+        address: Option<String>,
+    },
+    /// Updates the given accessibility settings, keeping the rest.
+    #[serde(rename = "settings.a11y.set")]
+    SettingsA11ySet {
+        default_font_size: Option<u32>,
+        minimum_font_size: Option<u32>,
+        high_contrast: Option<bool>,
+    },
+    #[serde(rename = "find.start")]
+    FindStart {
+        query: String,
+        #[serde(default)]
+        case_sensitive: bool,
+    },
+    #[serde(rename = "find.next")]
+    FindNext {},
+    #[serde(rename = "find.prev")]
+    FindPrev {},
+    #[serde(rename = "find.close")]
+    FindClose {},
+}
+
+/// Envelope around an incoming command.
+///
+/// `id` is an optional correlation id; commands that produce a direct result
+/// echo it back in a `response` message.
 #[derive(Debug, Deserialize)]
 pub struct IncomingMessage {
-    pub r#type: String,
-    pub payload: serde_json::Value,
+    #[serde(default)]
+    pub id: Option<u64>,
+    #[serde(flatten)]
+    pub command: IncomingCommand,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageType {
+    r#type: String,
 }
 
 #[derive(Debug, Serialize)]
 pub struct OutgoingMessage<'a, T> {
     pub r#type: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<u64>,
     pub payload: T,
 }
 
@@ -20,6 +226,8 @@ pub struct NavState {
     pub can_go_back: bool,
     pub can_go_forward: bool,
     pub is_loading: bool,
+    /// The last load was stopped because it took too long.
+    pub timed_out: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -38,21 +246,352 @@ pub struct FaviconState {
     pub favicon_uri: Option<String>,
 }
 
-pub fn send_state(view: &webkit6::WebView, state: &BrowserState) {
-    let payload = json!({
-        "tabs": state.to_ui_tree(),
-        "active": state.active,
-    });
-    let message = OutgoingMessage {
-        r#type: "state.tabs",
+#[derive(Debug, Serialize)]
+pub struct AutoSuspendedState {
+    pub ids: Vec<u64>,
+    pub cap: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryState {
+    /// `low`, `moderate`, or `severe`.
+    pub level: &'static str,
+}
+
+/// A page asking for `kind` (`geolocation`, `notifications` or `media`).
+#[derive(Debug, Serialize)]
+pub struct PermissionState<'a> {
+    pub id: u64,
+    pub origin: &'a str,
+    pub kind: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FindState {
+    pub current: u32,
+    pub total: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ThumbnailState {
+    pub id: u64,
+    pub data_uri: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PrintState {
+    pub path: String,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestionsState<'a> {
+    pub query: &'a str,
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookmarkState<'a> {
+    pub title: &'a str,
+    pub url: &'a str,
+    pub folder: Option<&'a str>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookmarksState<'a> {
+    pub bookmarks: Vec<BookmarkState<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SavedSessionState<'a> {
+    pub name: &'a str,
+    pub slug: String,
+    pub tabs: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionsState<'a> {
+    pub sessions: Vec<SavedSessionState<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HistoryMatch {
+    pub url: String,
+    pub title: String,
+    pub visit_count: u32,
+}
+
+/// Per-host exception to forced dark rendering.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ForceDarkRule {
+    /// Always force dark; once any host is allowed, only allowed hosts are.
+    Allow,
+    /// Never force dark, e.g. for sites with a good native dark theme.
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    InProgress,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DownloadState {
+    pub id: u64,
+    pub filename: String,
+    pub received: u64,
+    pub total: Option<u64>,
+    pub status: DownloadStatus,
+}
+
+/// A content web process that terminated while showing tab `id`.
+#[derive(Debug, Serialize)]
+pub struct CrashState<'a> {
+    pub id: u64,
+    pub url: &'a str,
+    pub reason: &'static str,
+    /// Consecutive crashes of the tab, including this one.
+    pub crashes: u32,
+    /// Whether the tab will be reloaded automatically.
+    pub reloading: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TlsErrorState<'a> {
+    pub host: &'a str,
+    pub uri: &'a str,
+    pub errors: Vec<&'static str>,
+}
+
+/// Parses a raw UI message into a typed command envelope.
+///
+/// Malformed or unknown messages are dropped. Each offending message type is
+/// logged only the first time it is seen to keep a misbehaving UI from
+/// flooding the log.
+pub fn parse_message(raw: &str, reported: &mut HashSet<String>) -> Option<IncomingMessage> {
+    match serde_json::from_str::<IncomingMessage>(raw) {
+        Ok(message) => Some(message),
+        Err(error) => {
+            let kind = serde_json::from_str::<MessageType>(raw)
+                .map(|message| message.r#type)
+                .unwrap_or_else(|_| "<unknown>".to_string());
+            if reported.insert(kind.clone()) {
+                log::warn!("ignoring invalid UI message type={kind}: {error}");
+            }
+            None
+        }
+    }
+}
+
+/// Coalesces `state.tabs` updates.
+///
+/// `schedule` marks the tab tree dirty and sends it once from an idle
+/// callback, so a burst of changes serializes the tree only once. `flush`
+/// sends immediately for callers that cannot wait for the main loop.
+///
+/// When the tree structure is unchanged since the last send, only the changed
+/// nodes go out as `state.tabs.patch`. Every message carries a sequence number
+/// so the UI can spot a missed patch and ask for a full resync.
+pub struct StateSync {
+    view: webkit6::WebView,
+    state: Rc<RefCell<BrowserState>>,
+    pending: RefCell<Option<glib::SourceId>>,
+    last_sent: RefCell<Option<SentTree>>,
+    seq: Cell<u64>,
+    on_change: RefCell<Option<Box<dyn Fn()>>>,
+}
+
+impl StateSync {
+    pub fn new(view: &webkit6::WebView, state: &Rc<RefCell<BrowserState>>) -> Rc<Self> {
+        Rc::new(Self {
+            view: view.clone(),
+            state: Rc::clone(state),
+            pending: RefCell::new(None),
+            last_sent: RefCell::new(None),
+            seq: Cell::new(0),
+            on_change: RefCell::new(None),
+        })
+    }
+
+    /// Calls `callback` after every send that carried a change, replacing
+    /// any earlier callback.
+    pub fn connect_changed(&self, callback: impl Fn() + 'static) {
+        *self.on_change.borrow_mut() = Some(Box::new(callback));
+    }
+
+    pub fn schedule(self: &Rc<Self>) {
+        if self.pending.borrow().is_some() {
+            return;
+        }
+        let sync = Rc::downgrade(self);
+        let source = glib::idle_add_local_once(move || {
+            if let Some(sync) = sync.upgrade() {
+                // The source has already fired; dropping the id is enough.
+                sync.pending.borrow_mut().take();
+                sync.send();
+            }
+        });
+        *self.pending.borrow_mut() = Some(source);
+    }
+
+    pub fn flush(&self) {
+        if let Some(source) = self.pending.borrow_mut().take() {
+            source.remove();
+        }
+        self.send();
+    }
+
+    /// Sends the full tree regardless of what the UI was sent before.
+    pub fn resync(&self) {
+        self.last_sent.borrow_mut().take();
+        self.flush();
+    }
+
+    fn send(&self) {
+        let state = self.state.borrow();
+        let tree = state.to_ui_tree();
+        let current = SentTree::from_tree(&tree);
+        let mut last_sent = self.last_sent.borrow_mut();
+
+        match last_sent.as_ref() {
+            Some(previous) if previous.structure == current.structure => {
+                let changes = previous.changes(&current);
+                if changes.is_empty() {
+                    return;
+                }
+                let payload = json!({
+                    "seq": self.next_seq(),
+                    "active": state.active,
+                    "changes": changes,
+                });
+                send_to_ui(&self.view, &outgoing("state.tabs.patch", payload));
+            }
+            _ => {
+                let payload = json!({
+                    "seq": self.next_seq(),
+                    "tabs": tree,
+                    "active": state.active,
+                });
+                send_to_ui(&self.view, &outgoing("state.tabs", payload));
+            }
+        }
+
+        *last_sent = Some(current);
+        drop(last_sent);
+        drop(state);
+        if let Some(on_change) = self.on_change.borrow().as_ref() {
+            on_change();
+        }
+    }
+
+    fn next_seq(&self) -> u64 {
+        let seq = self.seq.get() + 1;
+        self.seq.set(seq);
+        seq
+    }
+}
+
+/// Per-node fields of the tree last sent to the UI.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct SentFields {
+    title: String,
+    url: String,
+    favicon_uri: Option<String>,
+    is_expanded: bool,
+    is_active: bool,
+    is_pinned: bool,
+    is_muted: bool,
+    is_suspended: bool,
+    is_group: bool,
+    is_private: bool,
+}
+
+/// Flattened copy of the tree last sent to the UI.
+///
+/// `structure` lists `(id, parent)` in display order; any difference there is
+/// a structural change and requires a full send.
+#[derive(Debug)]
+struct SentTree {
+    structure: Vec<(u64, Option<u64>)>,
+    fields: HashMap<u64, SentFields>,
+}
+
+impl SentTree {
+    fn from_tree(tree: &[UiTabNode]) -> Self {
+        fn visit(nodes: &[UiTabNode], parent: Option<u64>, sent: &mut SentTree) {
+            for node in nodes {
+                sent.structure.push((node.id, parent));
+                sent.fields.insert(
+                    node.id,
+                    SentFields {
+                        title: node.title.clone(),
+                        url: node.url.clone(),
+                        favicon_uri: node.favicon_uri.clone(),
+                        is_expanded: node.is_expanded,
+                        is_active: node.is_active,
+                        is_pinned: node.is_pinned,
+                        is_muted: node.is_muted,
+                        is_suspended: node.is_suspended,
+                        is_group: node.is_group,
+                        is_private: node.is_private,
+                    },
+                );
+                visit(&node.children, Some(node.id), sent);
+            }
+        }
+
+        let mut sent = SentTree {
+            structure: Vec::new(),
+            fields: HashMap::new(),
+        };
+        visit(tree, None, &mut sent);
+        sent
+    }
+
+    /// Returns `{ id, ...changed fields }` for every node that differs.
+    fn changes(&self, current: &SentTree) -> Vec<Value> {
+        let mut changes = Vec::new();
+        for (id, _) in &current.structure {
+            let (Some(old), Some(new)) = (self.fields.get(id), current.fields.get(id)) else {
+                continue;
+            };
+            if old == new {
+                continue;
+            }
+            let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+                (serde_json::to_value(old), serde_json::to_value(new))
+            else {
+                continue;
+            };
+            let mut change: Map<String, Value> = new
+                .into_iter()
+                .filter(|(key, value)| old.get(key) != Some(value))
+                .collect();
+            change.insert("id".to_string(), json!(id));
+            changes.push(Value::Object(change));
+        }
+        changes
+    }
+}
+
+fn outgoing(r#type: &str, payload: Value) -> OutgoingMessage<'_, Value> {
+    OutgoingMessage {
+        r#type,
+        id: None,
         payload,
-    };
-    send_to_ui(view, &message);
+    }
 }
 
 pub fn send_nav_state(view: &webkit6::WebView, nav: NavState) {
     let message = OutgoingMessage {
         r#type: "state.nav",
+        id: None,
         payload: nav,
     };
     send_to_ui(view, &message);
@@ -62,6 +601,7 @@ pub fn send_assets(view: &webkit6::WebView, default_favicon: &str) {
     let payload = AssetsState { default_favicon };
     let message = OutgoingMessage {
         r#type: "state.assets",
+        id: None,
         payload,
     };
     send_to_ui(view, &message);
@@ -71,6 +611,7 @@ pub fn send_sidebar_state(view: &webkit6::WebView, collapsed: bool) {
     let payload = SidebarState { collapsed };
     let message = OutgoingMessage {
         r#type: "state.sidebar",
+        id: None,
         payload,
     };
     send_to_ui(view, &message);
@@ -83,6 +624,168 @@ pub fn send_favicon(view: &webkit6::WebView, ids: Vec<u64>, favicon_uri: Option<
     let payload = FaviconState { ids, favicon_uri };
     let message = OutgoingMessage {
         r#type: "state.favicon",
+        id: None,
+        payload,
+    };
+    send_to_ui(view, &message);
+}
+
+/// Reports tabs suspended automatically because more than `cap` were live.
+pub fn send_auto_suspended(view: &webkit6::WebView, ids: Vec<u64>, cap: usize) {
+    let message = OutgoingMessage {
+        r#type: "state.tabs.auto_suspended",
+        id: None,
+        payload: AutoSuspendedState { ids, cap },
+    };
+    send_to_ui(view, &message);
+}
+
+/// Reports the memory pressure level the governor is applying.
+pub fn send_memory_state(view: &webkit6::WebView, pressure: MemoryPressure) {
+    let level = match pressure {
+        MemoryPressure::Low => "low",
+        MemoryPressure::Moderate => "moderate",
+        MemoryPressure::Severe => "severe",
+    };
+    let message = OutgoingMessage {
+        r#type: "state.memory",
+        id: None,
+        payload: MemoryState { level },
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_find_state(view: &webkit6::WebView, current: u32, total: u32) {
+    let payload = FindState { current, total };
+    let message = OutgoingMessage {
+        r#type: "state.find",
+        id: None,
+        payload,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_thumbnail(view: &webkit6::WebView, id: u64, data_uri: Option<String>) {
+    let payload = ThumbnailState { id, data_uri };
+    let message = OutgoingMessage {
+        r#type: "state.thumbnail",
+        id: None,
+        payload,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_print_result(view: &webkit6::WebView, path: String, error: Option<String>) {
+    let payload = PrintState {
+        path,
+        ok: error.is_none(),
+        error,
+    };
+    let message = OutgoingMessage {
+        r#type: "state.print",
+        id: None,
+        payload,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_bookmarks(view: &webkit6::WebView, bookmarks: &[Bookmark]) {
+    let payload = BookmarksState {
+        bookmarks: bookmarks
+            .iter()
+            .map(|bookmark| BookmarkState {
+                title: &bookmark.title,
+                url: &bookmark.url,
+                folder: bookmark.folder.as_deref(),
+            })
+            .collect(),
+    };
+    let message = OutgoingMessage {
+        r#type: "state.bookmarks",
+        id: None,
+        payload,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_sessions(view: &webkit6::WebView, sessions: &[SavedSession]) {
+    let payload = SessionsState {
+        sessions: sessions
+            .iter()
+            .map(|session| SavedSessionState {
+                name: &session.name,
+                slug: session_slug(&session.name),
+                tabs: session.tabs.len(),
+            })
+            .collect(),
+    };
+    let message = OutgoingMessage {
+        r#type: "state.sessions",
+        id: None,
+        payload,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_permission(view: &webkit6::WebView, permission: PermissionState) {
+    let message = OutgoingMessage {
+        r#type: "state.permission",
+        id: None,
+        payload: permission,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_settings(view: &webkit6::WebView, settings: &Settings) {
+    let message = OutgoingMessage {
+        r#type: "state.settings",
+        id: None,
+        payload: settings,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_suggestions(view: &webkit6::WebView, suggestions: SuggestionsState) {
+    let message = OutgoingMessage {
+        r#type: "state.suggestions",
+        id: None,
+        payload: suggestions,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_download(view: &webkit6::WebView, download: DownloadState) {
+    let message = OutgoingMessage {
+        r#type: "state.download",
+        id: None,
+        payload: download,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_crash(view: &webkit6::WebView, crash: CrashState) {
+    let message = OutgoingMessage {
+        r#type: "state.crash",
+        id: None,
+        payload: crash,
+    };
+    send_to_ui(view, &message);
+}
+
+pub fn send_tls_error(view: &webkit6::WebView, error: TlsErrorState) {
+    let message = OutgoingMessage {
+        r#type: "state.tls_error",
+        id: None,
+        payload: error,
+    };
+    send_to_ui(view, &message);
+}
+
+/// Answers the request carrying correlation id `id` with `payload`.
+pub fn send_response<T: Serialize>(view: &webkit6::WebView, id: u64, payload: T) {
+    let message = OutgoingMessage {
+        r#type: "response",
+        id: Some(id),
         payload,
     };
     send_to_ui(view, &message);
@@ -94,4 +797,5 @@ fn send_to_ui<T: Serialize>(view: &webkit6::WebView, message: &OutgoingMessage<T
     };
     let script = format!("window.__owl_receive({json});");
     view.evaluate_javascript(&script, None, None, None::<&gtk::gio::Cancellable>, |_| {});
+    metrics::increment(Counter::IpcMessagesSent);
 }
@@ -1,8 +1,16 @@
 mod app;
 mod assets;
+mod crash;
+mod dialogs;
+mod downloads;
+mod import;
+mod inflight;
+mod internal;
 mod ipc;
-mod state;
+mod permissions;
+mod session;
 
 fn main() -> gtk::glib::ExitCode {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("warn")).init();
     app::run()
 }
@@ -0,0 +1,130 @@
+use crate::ipc::{self, PermissionState};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+use storage::PermissionStore;
+use url::Url;
+use webkit6::prelude::*;
+
+#[derive(Debug)]
+struct PendingPermission {
+    request: webkit6::PermissionRequest,
+    origin: String,
+    kind: &'static str,
+}
+
+/// Page permission requests waiting for the user's answer.
+///
+/// Requests for which the user already decided are answered at once from
+/// the profile's permission store; the rest are reported to the UI as
+/// `state.permission` and resolved by `permission.respond`.
+#[derive(Debug)]
+pub struct PermissionPrompts {
+    ui_webview: webkit6::WebView,
+    pending: RefCell<HashMap<u64, PendingPermission>>,
+    next_id: Cell<u64>,
+}
+
+impl PermissionPrompts {
+    pub fn new(ui_webview: &webkit6::WebView) -> Rc<Self> {
+        Rc::new(Self {
+            ui_webview: ui_webview.clone(),
+            pending: RefCell::new(HashMap::new()),
+            next_id: Cell::new(1),
+        })
+    }
+
+    /// Handles `permission-request` for a page at `page_uri`. Returns
+    /// `false` for permissions the browser does not prompt for, leaving them
+    /// to WebKit, which denies them.
+    pub fn request(
+        &self,
+        store: &impl PermissionStore,
+        page_uri: Option<&str>,
+        request: &webkit6::PermissionRequest,
+    ) -> bool {
+        let Some(kind) = permission_kind(request) else {
+            return false;
+        };
+        // Pages without a real origin (file://, data:) cannot be remembered
+        // or shown meaningfully, so they never get access.
+        let origin = page_uri
+            .and_then(|uri| Url::parse(uri).ok())
+            .map(|url| url.origin())
+            .filter(|origin| origin.is_tuple())
+            .map(|origin| origin.ascii_serialization());
+        let Some(origin) = origin else {
+            request.deny();
+            return true;
+        };
+
+        match store.decision(&origin, kind) {
+            Some(true) => request.allow(),
+            Some(false) => request.deny(),
+            None => {
+                let id = self.next_id.get();
+                self.next_id.set(id + 1);
+                ipc::send_permission(
+                    &self.ui_webview,
+                    PermissionState {
+                        id,
+                        origin: &origin,
+                        kind,
+                    },
+                );
+                self.pending.borrow_mut().insert(
+                    id,
+                    PendingPermission {
+                        request: request.clone(),
+                        origin,
+                        kind,
+                    },
+                );
+            }
+        }
+        true
+    }
+
+    /// Answers prompt `id`. A dismissed prompt is denied without remembering
+    /// the answer, so the site may ask again on a later visit. Returns
+    /// whether the prompt was still pending.
+    pub fn respond(
+        &self,
+        store: &mut impl PermissionStore,
+        id: u64,
+        allow: bool,
+        dismissed: bool,
+    ) -> bool {
+        let Some(pending) = self.pending.borrow_mut().remove(&id) else {
+            return false;
+        };
+        let allow = allow && !dismissed;
+        if !dismissed {
+            store.decide(&pending.origin, pending.kind, allow);
+        }
+        log::debug!(
+            "permission origin={} kind={} allow={allow} dismissed={dismissed}",
+            pending.origin,
+            pending.kind
+        );
+        if allow {
+            pending.request.allow();
+        } else {
+            pending.request.deny();
+        }
+        true
+    }
+}
+
+/// Name of the permission `request` asks for, if the browser prompts for it.
+fn permission_kind(request: &webkit6::PermissionRequest) -> Option<&'static str> {
+    if request.is::<webkit6::GeolocationPermissionRequest>() {
+        Some("geolocation")
+    } else if request.is::<webkit6::NotificationPermissionRequest>() {
+        Some("notifications")
+    } else if request.is::<webkit6::UserMediaPermissionRequest>() {
+        Some("media")
+    } else {
+        None
+    }
+}
@@ -0,0 +1,322 @@
+use gtk::{gio, glib};
+use gtk::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use storage::file::FileSessionStore;
+use storage::journal::{self, SessionJournal};
+use storage::{SessionSnapshot, TabSnapshot};
+use tabs::state::BrowserState;
+use tabs::{TabId, TabState};
+
+const LOGIND_BUS: &str = "org.freedesktop.login1";
+const LOGIND_PATH: &str = "/org/freedesktop/login1";
+const LOGIND_MANAGER: &str = "org.freedesktop.login1.Manager";
+/// How long autosave waits for further changes before writing.
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// Default location of the persisted session file.
+pub fn session_path() -> PathBuf {
+    glib::user_data_dir().join("owl-browser").join("session.json")
+}
+
+/// Default location of the journal of tab events since the last full save.
+pub fn journal_path() -> PathBuf {
+    glib::user_data_dir().join("owl-browser").join("session.journal")
+}
+
+/// Default location of the browsing history file.
+pub fn history_path() -> PathBuf {
+    glib::user_data_dir().join("owl-browser").join("history.json")
+}
+
+/// Default location of the bookmarks file, next to the session.
+pub fn bookmarks_path() -> PathBuf {
+    glib::user_data_dir().join("owl-browser").join("bookmarks.json")
+}
+
+/// Default location of the per-host favicon cache.
+pub fn favicon_cache_path() -> PathBuf {
+    glib::user_cache_dir().join("owl-browser").join("favicons.json")
+}
+
+/// Location of an optional WebKit content-blocker rule list.
+pub fn content_filter_path() -> PathBuf {
+    glib::user_config_dir().join("owl-browser").join("content-filter.json")
+}
+
+/// Default location of remembered per-site permission decisions.
+pub fn permissions_path() -> PathBuf {
+    glib::user_data_dir().join("owl-browser").join("permissions.json")
+}
+
+/// Default location of the user settings.
+pub fn settings_path() -> PathBuf {
+    glib::user_config_dir().join("owl-browser").join("settings.json")
+}
+
+/// Default location of the library of named sessions.
+pub fn library_path() -> PathBuf {
+    glib::user_data_dir().join("owl-browser").join("sessions.json")
+}
+
+/// Appends the navigable tabs of `state` to `snapshot`.
+///
+/// Group nodes are containers only and are not persisted as tabs; private
+/// tabs are never persisted. Tab ids are only unique within a window, so
+/// they are stored offset by `window << 32`.
+pub fn append_snapshot(snapshot: &mut SessionSnapshot, state: &BrowserState, window: u32) {
+    let stored_id = |id: u64| TabId::new((u64::from(window) << 32) | id);
+    let mut ids: Vec<u64> = state.tabs.keys().copied().collect();
+    ids.sort_unstable();
+
+    for id in ids {
+        let node = &state.tabs[&id];
+        if node.is_group || node.is_private {
+            continue;
+        }
+        let tab_state = if state.active == Some(id) {
+            TabState::Active
+        } else if node.is_suspended {
+            TabState::Suspended
+        } else {
+            TabState::Background
+        };
+        let group = node
+            .parent
+            .and_then(|parent| state.tabs.get(&parent))
+            .filter(|parent| parent.is_group);
+        snapshot.tabs.push(TabSnapshot {
+            id: stored_id(id),
+            uri: node.url.clone(),
+            state: tab_state,
+            title: node.title.clone(),
+            group: group.map(|parent| parent.title.clone()),
+            group_id: group.map(|parent| stored_id(parent.id)),
+        });
+    }
+
+    if snapshot.active.is_none() {
+        snapshot.active = state
+            .active
+            .filter(|_| !state.active_is_private())
+            .map(stored_id);
+    }
+}
+
+/// Saves the session shortly after it changes.
+///
+/// Changes within [`AUTOSAVE_DELAY`] of the first one are coalesced into a
+/// single save. The snapshot is taken on the main thread but written on a
+/// worker thread, so disk I/O never stalls input; [`SessionAutosave::save_now`]
+/// writes synchronously for shutdown paths.
+///
+/// Every change is also journaled at once, so a crash between saves loses at
+/// most one event. The journal is truncated by synchronous saves only; the
+/// background saves leave it alone, which is safe because replaying events
+/// a snapshot already contains does not change it.
+pub struct SessionAutosave {
+    store: FileSessionStore,
+    collect: Box<dyn Fn() -> SessionSnapshot>,
+    journal: RefCell<SessionJournal>,
+    /// Session as of the last journaled event.
+    journaled: RefCell<SessionSnapshot>,
+    pending: RefCell<Option<glib::SourceId>>,
+    /// Number of the latest snapshot taken.
+    generation: Cell<u64>,
+    /// Number of the latest snapshot written. Writers hold the lock while
+    /// writing and skip snapshots older than the last one written.
+    written: Arc<Mutex<u64>>,
+}
+
+impl SessionAutosave {
+    /// Folds a journal left by a crashed run into the stored session before
+    /// journaling anything new.
+    pub fn new(
+        store: FileSessionStore,
+        mut journal: SessionJournal,
+        collect: impl Fn() -> SessionSnapshot + 'static,
+    ) -> Rc<Self> {
+        let journaled = journal.recover(&store);
+        Rc::new(Self {
+            store,
+            collect: Box::new(collect),
+            journal: RefCell::new(journal),
+            journaled: RefCell::new(journaled),
+            pending: RefCell::new(None),
+            generation: Cell::new(0),
+            written: Arc::new(Mutex::new(0)),
+        })
+    }
+
+    /// Journals what changed and schedules a background save unless one is
+    /// already pending.
+    pub fn schedule(self: &Rc<Self>) {
+        self.record();
+        if self.pending.borrow().is_some() {
+            return;
+        }
+        let autosave = Rc::downgrade(self);
+        let source = glib::timeout_add_local_once(AUTOSAVE_DELAY, move || {
+            if let Some(autosave) = autosave.upgrade() {
+                autosave.pending.borrow_mut().take();
+                autosave.save_in_background();
+            }
+        });
+        *self.pending.borrow_mut() = Some(source);
+    }
+
+    /// Cancels any pending autosave and writes the session before returning.
+    pub fn save_now(&self) {
+        if let Some(source) = self.pending.borrow_mut().take() {
+            source.remove();
+        }
+        let (generation, snapshot) = self.take_snapshot();
+        if !write_if_newer(&self.store, &self.written, generation, &snapshot) {
+            return;
+        }
+        if let Err(error) = self.journal.borrow_mut().truncate() {
+            log::warn!("failed to truncate the session journal: {error}");
+        }
+        *self.journaled.borrow_mut() = snapshot;
+    }
+
+    fn record(&self) {
+        let snapshot = (self.collect)();
+        let events = journal::diff(&self.journaled.borrow(), &snapshot);
+        if events.is_empty() {
+            return;
+        }
+        match self.journal.borrow_mut().append(&events) {
+            Ok(()) => *self.journaled.borrow_mut() = snapshot,
+            Err(error) => log::warn!("failed to journal session events: {error}"),
+        }
+    }
+
+    fn save_in_background(&self) {
+        let (generation, snapshot) = self.take_snapshot();
+        let store = self.store.clone();
+        let written = Arc::clone(&self.written);
+        let spawned = std::thread::Builder::new()
+            .name("session-autosave".to_string())
+            .spawn(move || {
+                write_if_newer(&store, &written, generation, &snapshot);
+            });
+        if let Err(error) = spawned {
+            log::warn!("failed to start session autosave: {error}");
+        }
+    }
+
+    fn take_snapshot(&self) -> (u64, SessionSnapshot) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        (generation, (self.collect)())
+    }
+}
+
+/// Writes `snapshot` unless a newer one was written already. Returns whether
+/// the file now holds `snapshot`.
+fn write_if_newer(
+    store: &FileSessionStore,
+    written: &Mutex<u64>,
+    generation: u64,
+    snapshot: &SessionSnapshot,
+) -> bool {
+    let mut written = written.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if *written >= generation {
+        return false;
+    }
+    match store.write(snapshot) {
+        Ok(()) => {
+            *written = generation;
+            true
+        }
+        Err(error) => {
+            log::warn!("failed to save session to {}: {error}", store.path().display());
+            false
+        }
+    }
+}
+
+/// Holds a logind "delay" inhibitor so the session can be saved before the
+/// machine sleeps or shuts down.
+///
+/// The lock is released as soon as `on_prepare` has run and re-acquired when
+/// the system resumes. Dropping the inhibitor unsubscribes from logind.
+pub struct SleepInhibitor {
+    _subscriptions: Vec<gio::SignalSubscription>,
+}
+
+impl SleepInhibitor {
+    /// Registers with logind on the system bus. Returns `None` when the bus is
+    /// unavailable (e.g. inside some sandboxes).
+    pub fn install(on_prepare: Rc<dyn Fn()>) -> Option<Self> {
+        let bus = gio::bus_get_sync(gio::BusType::System, gio::Cancellable::NONE).ok()?;
+        let lock: Rc<RefCell<Option<gio::UnixFDList>>> = Rc::new(RefCell::new(None));
+        take_delay_lock(&bus, &lock);
+
+        let subscriptions = ["PrepareForSleep", "PrepareForShutdown"]
+            .into_iter()
+            .map(|member| {
+                let bus_for_signal = bus.clone();
+                let lock = Rc::clone(&lock);
+                let on_prepare = Rc::clone(&on_prepare);
+                bus.subscribe_to_signal(
+                    Some(LOGIND_BUS),
+                    Some(LOGIND_MANAGER),
+                    Some(member),
+                    Some(LOGIND_PATH),
+                    None,
+                    gio::DBusSignalFlags::NONE,
+                    move |signal| {
+                        let starting = signal
+                            .parameters
+                            .get::<(bool,)>()
+                            .map(|(starting,)| starting)
+                            .unwrap_or(false);
+                        if starting {
+                            on_prepare();
+                            // Dropping the fd list closes the lock so sleep can proceed.
+                            lock.borrow_mut().take();
+                        } else {
+                            take_delay_lock(&bus_for_signal, &lock);
+                        }
+                    },
+                )
+            })
+            .collect();
+
+        Some(Self {
+            _subscriptions: subscriptions,
+        })
+    }
+}
+
+fn take_delay_lock(bus: &gio::DBusConnection, lock: &Rc<RefCell<Option<gio::UnixFDList>>>) {
+    let lock = Rc::clone(lock);
+    let args = (
+        "sleep:shutdown",
+        "OwL Browser",
+        "Saving the browsing session",
+        "delay",
+    )
+        .to_variant();
+    bus.call_with_unix_fd_list(
+        Some(LOGIND_BUS),
+        LOGIND_PATH,
+        LOGIND_MANAGER,
+        "Inhibit",
+        Some(&args),
+        glib::VariantTy::new("(h)").ok(),
+        gio::DBusCallFlags::NONE,
+        -1,
+        None::<&gio::UnixFDList>,
+        gio::Cancellable::NONE,
+        move |result| match result {
+            Ok((_, fds)) => *lock.borrow_mut() = fds,
+            Err(error) => log::warn!("failed to take logind sleep inhibitor: {error}"),
+        },
+    );
+}
@@ -0,0 +1,192 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::write_atomic;
+use crate::{Bookmark, BookmarkStore};
+
+/// JSON file-backed bookmark store.
+///
+/// Bookmarks are kept in insertion order and written back atomically after
+/// every change.
+#[derive(Debug, Clone)]
+pub struct FileBookmarkStore {
+    path: PathBuf,
+    bookmarks: Vec<Bookmark>,
+}
+
+impl FileBookmarkStore {
+    /// Opens the bookmarks at `path`, starting empty if they are missing or
+    /// unreadable.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let bookmarks = match read_bookmarks(&path) {
+            Ok(bookmarks) => bookmarks,
+            Err(error) => {
+                log::warn!("failed to load bookmarks from {}: {error}", path.display());
+                Vec::new()
+            }
+        };
+        Self { path, bookmarks }
+    }
+
+    /// Returns the path of the bookmarks file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn position(&self, url: &str, folder: Option<&str>) -> Option<usize> {
+        self.bookmarks
+            .iter()
+            .position(|bookmark| bookmark.url == url && bookmark.folder.as_deref() == folder)
+    }
+
+    fn save(&self) {
+        let stored = StoredBookmarks {
+            bookmarks: self
+                .bookmarks
+                .iter()
+                .map(|bookmark| StoredBookmark {
+                    title: bookmark.title.clone(),
+                    url: bookmark.url.clone(),
+                    folder: bookmark.folder.clone(),
+                    added: bookmark.added.and_then(|added| {
+                        let since = added.duration_since(UNIX_EPOCH).ok()?;
+                        u64::try_from(since.as_millis()).ok()
+                    }),
+                })
+                .collect(),
+        };
+        let result = serde_json::to_vec(&stored)
+            .map_err(io::Error::from)
+            .and_then(|json| write_atomic(&self.path, &json));
+        if let Err(error) = result {
+            log::warn!(
+                "failed to save bookmarks to {}: {error}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl BookmarkStore for FileBookmarkStore {
+    fn add(&mut self, bookmark: Bookmark) -> bool {
+        if self
+            .position(&bookmark.url, bookmark.folder.as_deref())
+            .is_some()
+        {
+            return false;
+        }
+        self.bookmarks.push(bookmark);
+        self.save();
+        true
+    }
+
+    fn remove(&mut self, url: &str, folder: Option<&str>) -> bool {
+        let Some(index) = self.position(url, folder) else {
+            return false;
+        };
+        self.bookmarks.remove(index);
+        self.save();
+        true
+    }
+
+    fn list(&self) -> Vec<Bookmark> {
+        self.bookmarks.clone()
+    }
+
+    fn move_to(&mut self, url: &str, from: Option<&str>, to: Option<&str>) -> bool {
+        if from == to || self.position(url, to).is_some() {
+            return false;
+        }
+        let Some(index) = self.position(url, from) else {
+            return false;
+        };
+        self.bookmarks[index].folder = to.map(str::to_string);
+        self.save();
+        true
+    }
+}
+
+fn read_bookmarks(path: &Path) -> io::Result<Vec<Bookmark>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let stored: StoredBookmarks = serde_json::from_slice(&bytes)?;
+    Ok(stored
+        .bookmarks
+        .into_iter()
+        .map(|bookmark| Bookmark {
+            title: bookmark.title,
+            url: bookmark.url,
+            folder: bookmark.folder,
+            added: bookmark
+                .added
+                .map(|millis| UNIX_EPOCH + Duration::from_millis(millis)),
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredBookmarks {
+    bookmarks: Vec<StoredBookmark>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredBookmark {
+    title: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    folder: Option<String>,
+    /// Milliseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    added: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(url: &str, folder: Option<&str>) -> Bookmark {
+        Bookmark {
+            title: url.to_string(),
+            url: url.to_string(),
+            folder: folder.map(str::to_string),
+            added: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        }
+    }
+
+    #[test]
+    fn keeps_url_folder_pairs_unique() {
+        let dir = crate::test_dir("bookmarks");
+        let path = dir.join("bookmarks.json");
+
+        let mut store = FileBookmarkStore::open(&path);
+        assert!(store.add(bookmark("https://gnome.org/", None)));
+        assert!(!store.add(bookmark("https://gnome.org/", None)));
+        assert!(store.add(bookmark("https://gnome.org/", Some("Desktop"))));
+        assert!(store.add(bookmark("https://gtk.org/", Some("Desktop"))));
+
+        // Moving onto an existing pair would create a duplicate.
+        assert!(!store.move_to("https://gnome.org/", None, Some("Desktop")));
+        assert!(store.move_to("https://gtk.org/", Some("Desktop"), None));
+        assert!(store.remove("https://gnome.org/", None));
+        assert!(!store.remove("https://gnome.org/", None));
+
+        let reopened = FileBookmarkStore::open(&path);
+        assert_eq!(
+            reopened.list(),
+            vec![
+                bookmark("https://gnome.org/", Some("Desktop")),
+                bookmark("https://gtk.org/", None),
+            ]
+        );
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
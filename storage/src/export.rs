@@ -0,0 +1,220 @@
+//! Netscape bookmark-file export, the HTML format every major browser can
+//! import.
+
+use std::fmt::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tabs::TabId;
+
+use crate::{Bookmark, SessionSnapshot};
+
+/// Serializes the tabs of `session` as a Netscape bookmark file. Tab groups
+/// become folders, one per group even when titles repeat.
+pub fn export_bookmarks_html(session: &SessionSnapshot) -> String {
+    let entries = session.tabs.iter().map(|tab| Entry {
+        title: &tab.title,
+        url: &tab.uri,
+        folder: tab.group.as_deref().map(|title| Folder {
+            group: tab.group_id,
+            title,
+        }),
+        added: None,
+    });
+    write_document(entries)
+}
+
+/// Serializes `bookmarks` as a Netscape bookmark file, keeping their folders.
+pub fn export_bookmark_list_html(bookmarks: &[Bookmark]) -> String {
+    let entries = bookmarks.iter().map(|bookmark| Entry {
+        title: &bookmark.title,
+        url: &bookmark.url,
+        folder: bookmark
+            .folder
+            .as_deref()
+            .map(|title| Folder { group: None, title }),
+        added: bookmark.added,
+    });
+    write_document(entries)
+}
+
+struct Entry<'a> {
+    title: &'a str,
+    url: &'a str,
+    folder: Option<Folder<'a>>,
+    added: Option<SystemTime>,
+}
+
+/// An exported folder. Tab groups are told apart by id; bookmark folders
+/// have none and are identified by title.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Folder<'a> {
+    group: Option<TabId>,
+    title: &'a str,
+}
+
+/// Writes top-level entries and folders in order of first appearance; all
+/// entries of a folder are listed together.
+fn write_document<'a>(entries: impl Iterator<Item = Entry<'a>>) -> String {
+    let mut items: Vec<(Option<Folder<'a>>, Vec<Entry<'a>>)> = Vec::new();
+    for entry in entries {
+        match entry.folder {
+            Some(folder) => match items.iter_mut().find(|(other, _)| *other == Some(folder)) {
+                Some((_, folder_entries)) => folder_entries.push(entry),
+                None => items.push((Some(folder), vec![entry])),
+            },
+            None => items.push((None, vec![entry])),
+        }
+    }
+
+    let mut html = String::from(
+        "<!DOCTYPE NETSCAPE-Bookmark-file-1>\n\
+         <META HTTP-EQUIV=\"Content-Type\" CONTENT=\"text/html; charset=UTF-8\">\n\
+         <TITLE>Bookmarks</TITLE>\n\
+         <H1>Bookmarks</H1>\n\
+         <DL><p>\n",
+    );
+    for (folder, entries) in &items {
+        match folder {
+            Some(folder) => {
+                let _ = writeln!(html, "    <DT><H3>{}</H3>", escape(folder.title));
+                html.push_str("    <DL><p>\n");
+                for entry in entries {
+                    write_entry(&mut html, entry, "        ");
+                }
+                html.push_str("    </DL><p>\n");
+            }
+            None => {
+                for entry in entries {
+                    write_entry(&mut html, entry, "    ");
+                }
+            }
+        }
+    }
+    html.push_str("</DL><p>\n");
+    html
+}
+
+fn write_entry(html: &mut String, entry: &Entry, indent: &str) {
+    let title = if entry.title.is_empty() {
+        entry.url
+    } else {
+        entry.title
+    };
+    let _ = write!(html, "{indent}<DT><A HREF=\"{}\"", escape(entry.url));
+    if let Some(seconds) = entry
+        .added
+        .and_then(|added| added.duration_since(UNIX_EPOCH).ok())
+    {
+        let _ = write!(html, " ADD_DATE=\"{}\"", seconds.as_secs());
+    }
+    let _ = writeln!(html, ">{}</A>", escape(title));
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TabSnapshot;
+    use std::time::Duration;
+    use tabs::TabState;
+
+    fn tab(id: u64, title: &str, uri: &str, group: Option<(u64, &str)>) -> TabSnapshot {
+        TabSnapshot {
+            id: TabId::new(id),
+            uri: uri.to_string(),
+            state: TabState::Background,
+            title: title.to_string(),
+            group: group.map(|(_, title)| title.to_string()),
+            group_id: group.map(|(id, _)| TabId::new(id)),
+        }
+    }
+
+    #[test]
+    fn exports_groups_as_folders() {
+        let session = SessionSnapshot {
+            tabs: vec![
+                tab(1, "Home", "https://example.com/?a=1&b=2", None),
+                tab(2, "GTK <4>", "https://gtk.org/", Some((10, "Reading"))),
+                tab(3, "", "https://gnome.org/", None),
+                tab(4, "WebKit", "https://webkit.org/", Some((10, "Reading"))),
+            ],
+            active: None,
+        };
+
+        let html = export_bookmarks_html(&session);
+        let body: Vec<&str> = html.lines().skip_while(|line| *line != "<DL><p>").collect();
+        assert_eq!(
+            body,
+            vec![
+                "<DL><p>",
+                "    <DT><A HREF=\"https://example.com/?a=1&amp;b=2\">Home</A>",
+                "    <DT><H3>Reading</H3>",
+                "    <DL><p>",
+                "        <DT><A HREF=\"https://gtk.org/\">GTK &lt;4&gt;</A>",
+                "        <DT><A HREF=\"https://webkit.org/\">WebKit</A>",
+                "    </DL><p>",
+                "    <DT><A HREF=\"https://gnome.org/\">https://gnome.org/</A>",
+                "</DL><p>",
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_sharing_a_title_stay_separate_folders() {
+        let session = SessionSnapshot {
+            tabs: vec![
+                tab(1, "GTK", "https://gtk.org/", Some((10, "Reading"))),
+                tab(2, "WebKit", "https://webkit.org/", Some((11, "Reading"))),
+                tab(3, "GNOME", "https://gnome.org/", Some((10, "Reading"))),
+            ],
+            active: None,
+        };
+
+        let html = export_bookmarks_html(&session);
+        let body: Vec<&str> = html.lines().skip_while(|line| *line != "<DL><p>").collect();
+        assert_eq!(
+            body,
+            vec![
+                "<DL><p>",
+                "    <DT><H3>Reading</H3>",
+                "    <DL><p>",
+                "        <DT><A HREF=\"https://gtk.org/\">GTK</A>",
+                "        <DT><A HREF=\"https://gnome.org/\">GNOME</A>",
+                "    </DL><p>",
+                "    <DT><H3>Reading</H3>",
+                "    <DL><p>",
+                "        <DT><A HREF=\"https://webkit.org/\">WebKit</A>",
+                "    </DL><p>",
+                "</DL><p>",
+            ]
+        );
+    }
+
+    #[test]
+    fn exports_bookmark_add_dates() {
+        let bookmarks = [Bookmark {
+            title: "Say \"hi\"".to_string(),
+            url: "https://example.com/".to_string(),
+            folder: None,
+            added: Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000)),
+        }];
+        let html = export_bookmark_list_html(&bookmarks);
+        assert!(html.contains(
+            "<DT><A HREF=\"https://example.com/\" ADD_DATE=\"1700000000\">Say &quot;hi&quot;</A>"
+        ));
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::write_atomic;
+use crate::FaviconCache;
+
+/// Default time after which a cached favicon is looked up again.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// JSON file-backed favicon cache mapping hosts to icon uris.
+///
+/// The browser stores `data:` uris holding the icon itself, so a cached
+/// icon is shown without fetching it again.
+///
+/// Several instances may share the file, so every write merges the entries
+/// on disk first and keeps the most recently stored icon per host.
+#[derive(Debug, Clone)]
+pub struct FileFaviconCache {
+    path: PathBuf,
+    ttl: Duration,
+    entries: HashMap<String, CachedIcon>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CachedIcon {
+    icon: String,
+    stored: SystemTime,
+}
+
+impl CachedIcon {
+    /// Entries stored "in the future" (clock changes) count as fresh.
+    fn is_fresh(&self, now: SystemTime, ttl: Duration) -> bool {
+        now.duration_since(self.stored)
+            .map_or(true, |age| age < ttl)
+    }
+}
+
+impl FileFaviconCache {
+    /// Opens the cache at `path`, starting empty if it is missing or
+    /// unreadable. Entries older than `ttl` are treated as missing.
+    pub fn open(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        let path = path.into();
+        let entries = match read_entries(&path) {
+            Ok(entries) => entries,
+            Err(error) => {
+                log::warn!(
+                    "failed to load favicon cache from {}: {error}",
+                    path.display()
+                );
+                HashMap::new()
+            }
+        };
+        Self { path, ttl, entries }
+    }
+
+    /// Returns the path of the cache file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save(&mut self, now: SystemTime) {
+        match read_entries(&self.path) {
+            Ok(on_disk) => {
+                for (host, entry) in on_disk {
+                    match self.entries.get(&host) {
+                        Some(ours) if ours.stored >= entry.stored => {}
+                        _ => {
+                            self.entries.insert(host, entry);
+                        }
+                    }
+                }
+            }
+            Err(error) => {
+                log::warn!(
+                    "failed to merge favicon cache from {}: {error}",
+                    self.path.display()
+                );
+            }
+        }
+        let ttl = self.ttl;
+        self.entries.retain(|_, entry| entry.is_fresh(now, ttl));
+
+        let stored = StoredCache {
+            icons: self
+                .entries
+                .iter()
+                .map(|(host, entry)| StoredIcon::from_entry(host, entry))
+                .collect(),
+        };
+        let result = serde_json::to_vec(&stored)
+            .map_err(io::Error::from)
+            .and_then(|json| write_atomic(&self.path, &json));
+        if let Err(error) = result {
+            log::warn!(
+                "failed to save favicon cache to {}: {error}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl FaviconCache for FileFaviconCache {
+    fn get(&self, host: &str, now: SystemTime) -> Option<String> {
+        self.entries
+            .get(host)
+            .filter(|entry| entry.is_fresh(now, self.ttl))
+            .map(|entry| entry.icon.clone())
+    }
+
+    fn store(&mut self, host: &str, icon: &str, now: SystemTime) {
+        // Refreshing an unchanged icon only matters once it is half expired.
+        let unchanged = self.entries.get(host).is_some_and(|entry| {
+            entry.icon == icon
+                && now
+                    .duration_since(entry.stored)
+                    .is_ok_and(|age| age < self.ttl / 2)
+        });
+        if unchanged {
+            return;
+        }
+        self.entries.insert(
+            host.to_string(),
+            CachedIcon {
+                icon: icon.to_string(),
+                stored: now,
+            },
+        );
+        self.save(now);
+    }
+}
+
+fn read_entries(path: &Path) -> io::Result<HashMap<String, CachedIcon>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(error) => return Err(error),
+    };
+    let stored: StoredCache = serde_json::from_slice(&bytes)?;
+    Ok(stored
+        .icons
+        .into_iter()
+        .map(StoredIcon::into_entry)
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredCache {
+    icons: Vec<StoredIcon>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredIcon {
+    host: String,
+    icon: String,
+    /// Milliseconds since the Unix epoch.
+    stored_at: u64,
+}
+
+impl StoredIcon {
+    fn from_entry(host: &str, entry: &CachedIcon) -> Self {
+        let stored_at = entry
+            .stored
+            .duration_since(UNIX_EPOCH)
+            .map(|since| u64::try_from(since.as_millis()).unwrap_or(u64::MAX))
+            .unwrap_or(0);
+        Self {
+            host: host.to_string(),
+            icon: entry.icon.clone(),
+            stored_at,
+        }
+    }
+
+    fn into_entry(self) -> (String, CachedIcon) {
+        (
+            self.host,
+            CachedIcon {
+                icon: self.icon,
+                stored: UNIX_EPOCH + Duration::from_millis(self.stored_at),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expires_entries_and_merges_concurrent_writers() {
+        let dir = crate::test_dir("favicons");
+        let path = dir.join("favicons.json");
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let at = |secs| start + Duration::from_secs(secs);
+        let ttl = Duration::from_secs(100);
+
+        let mut first = FileFaviconCache::open(&path, ttl);
+        let mut second = FileFaviconCache::open(&path, ttl);
+        first.store("gnome.org", "https://gnome.org/favicon.ico", at(0));
+        second.store("webkitgtk.org", "https://webkitgtk.org/favicon.png", at(1));
+        second.store("gnome.org", "https://gnome.org/icon.svg", at(2));
+
+        let reopened = FileFaviconCache::open(&path, ttl);
+        assert_eq!(
+            reopened.get("webkitgtk.org", at(10)).as_deref(),
+            Some("https://webkitgtk.org/favicon.png")
+        );
+        // The later store wins over the earlier one from another writer.
+        assert_eq!(
+            reopened.get("gnome.org", at(10)).as_deref(),
+            Some("https://gnome.org/icon.svg")
+        );
+        assert_eq!(reopened.get("gnome.org", at(200)), None);
+        assert_eq!(reopened.get("fedoraproject.org", at(10)), None);
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
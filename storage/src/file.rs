@@ -0,0 +1,209 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tabs::{TabId, TabState};
+
+use crate::{SessionSnapshot, SessionStore, TabSnapshot};
+
+/// JSON file-backed session store.
+///
+/// Writes go to a temporary sibling file that is renamed into place, so a
+/// crash mid-write never leaves a truncated session behind.
+#[derive(Debug, Clone)]
+pub struct FileSessionStore {
+    path: PathBuf,
+}
+
+impl FileSessionStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the session file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Reads the stored session. Returns `Ok(None)` when no session exists yet.
+    pub fn read(&self) -> io::Result<Option<SessionSnapshot>> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(error) => return Err(error),
+        };
+        let stored: StoredSession = serde_json::from_slice(&bytes)?;
+        Ok(Some(stored.into_snapshot()))
+    }
+
+    /// Writes the session atomically.
+    pub fn write(&self, session: &SessionSnapshot) -> io::Result<()> {
+        let json = serde_json::to_vec(&StoredSession::from_snapshot(session))?;
+        write_atomic(&self.path, &json)
+    }
+}
+
+impl SessionStore for FileSessionStore {
+    fn load(&self) -> Option<SessionSnapshot> {
+        match self.read() {
+            Ok(session) => session,
+            Err(error) => {
+                log::warn!("failed to load session from {}: {error}", self.path.display());
+                None
+            }
+        }
+    }
+
+    fn save(&self, session: &SessionSnapshot) {
+        if let Err(error) = self.write(session) {
+            log::warn!("failed to save session to {}: {error}", self.path.display());
+        }
+    }
+}
+
+pub(crate) fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Per-process temporary name, so two instances saving the same file do
+    // not write into each other's temporary file.
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}.tmp", std::process::id()));
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, bytes)?;
+    fs::rename(&tmp, path)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSession {
+    tabs: Vec<StoredTab>,
+    active: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct StoredTab {
+    id: u64,
+    uri: String,
+    state: StoredTabState,
+    #[serde(default)]
+    title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum StoredTabState {
+    Active,
+    Background,
+    Frozen,
+    Suspended,
+}
+
+impl StoredSession {
+    fn from_snapshot(session: &SessionSnapshot) -> Self {
+        Self {
+            tabs: session.tabs.iter().map(StoredTab::from).collect(),
+            active: session.active.map(TabId::get),
+        }
+    }
+
+    fn into_snapshot(self) -> SessionSnapshot {
+        SessionSnapshot {
+            tabs: self.tabs.into_iter().map(TabSnapshot::from).collect(),
+            active: self.active.map(TabId::new),
+        }
+    }
+}
+
+impl From<&TabSnapshot> for StoredTab {
+    fn from(tab: &TabSnapshot) -> Self {
+        Self {
+            id: tab.id.get(),
+            uri: tab.uri.clone(),
+            state: StoredTabState::from(tab.state),
+            title: tab.title.clone(),
+            group: tab.group.clone(),
+        }
+    }
+}
+
+impl From<StoredTab> for TabSnapshot {
+    fn from(tab: StoredTab) -> Self {
+        Self {
+            id: TabId::new(tab.id),
+            uri: tab.uri,
+            state: tab.state.into(),
+            title: tab.title,
+            group: tab.group,
+            group_id: None,
+        }
+    }
+}
+
+impl From<TabState> for StoredTabState {
+    fn from(state: TabState) -> Self {
+        match state {
+            TabState::Active => Self::Active,
+            TabState::Background => Self::Background,
+            TabState::Frozen => Self::Frozen,
+            TabState::Suspended => Self::Suspended,
+        }
+    }
+}
+
+impl From<StoredTabState> for TabState {
+    fn from(state: StoredTabState) -> Self {
+        match state {
+            StoredTabState::Active => Self::Active,
+            StoredTabState::Background => Self::Background,
+            StoredTabState::Frozen => Self::Frozen,
+            StoredTabState::Suspended => Self::Suspended,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_session() {
+        let dir = crate::test_dir("session");
+        let store = FileSessionStore::new(dir.join("session.json"));
+        assert!(store.read().unwrap().is_none());
+
+        let session = SessionSnapshot {
+            tabs: vec![
+                TabSnapshot {
+                    id: TabId::new(1),
+                    uri: "https://example.com/".to_string(),
+                    state: TabState::Active,
+                    title: "Example".to_string(),
+                    group: Some("Reading".to_string()),
+                    group_id: Some(TabId::new(3)),
+                },
+                TabSnapshot {
+                    id: TabId::new(2),
+                    uri: "owl://home".to_string(),
+                    state: TabState::Suspended,
+                    title: "Home".to_string(),
+                    group: None,
+                    group_id: None,
+                },
+            ],
+            active: Some(TabId::new(1)),
+        };
+        store.write(&session).unwrap();
+
+        let loaded = store.read().unwrap().expect("stored session");
+        assert_eq!(loaded.active, Some(TabId::new(1)));
+        assert_eq!(loaded.tabs.len(), 2);
+        assert_eq!(loaded.tabs[1].uri, "owl://home");
+        assert_eq!(loaded.tabs[1].state, TabState::Suspended);
+        assert_eq!(loaded.tabs[0].group.as_deref(), Some("Reading"));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
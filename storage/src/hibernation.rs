@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use tabs::TabId;
+
+/// Where a tab was when its view went away, so reselecting it can reload
+/// the page and put the reader back at the same position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HibernationSnapshot {
+    pub url: String,
+    pub title: String,
+    /// Page scroll offset in CSS pixels, `(x, y)`.
+    pub scroll: (f64, f64),
+}
+
+impl HibernationSnapshot {
+    /// Whether the snapshot still describes a page loaded from `url`. The
+    /// fragment is ignored; anything else differing means the tab navigated
+    /// since and the saved position is meaningless.
+    pub fn applies_to(&self, url: &str) -> bool {
+        without_fragment(&self.url) == without_fragment(url)
+    }
+}
+
+fn without_fragment(url: &str) -> &str {
+    url.split_once('#').map_or(url, |(url, _)| url)
+}
+
+/// Snapshots of hibernated tabs, one per tab, kept in memory only.
+#[derive(Debug, Default)]
+pub struct Hibernation {
+    snapshots: HashMap<TabId, HibernationSnapshot>,
+}
+
+impl Hibernation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records `snapshot` for `tab`, replacing any earlier one.
+    pub fn store(&mut self, tab: TabId, snapshot: HibernationSnapshot) {
+        self.snapshots.insert(tab, snapshot);
+    }
+
+    /// Removes and returns the snapshot of `tab` if it applies to `url`.
+    /// A stale snapshot is dropped either way.
+    pub fn take(&mut self, tab: TabId, url: &str) -> Option<HibernationSnapshot> {
+        self.snapshots
+            .remove(&tab)
+            .filter(|snapshot| snapshot.applies_to(url))
+    }
+
+    /// Drops the snapshot of a closed tab.
+    pub fn forget(&mut self, tab: TabId) {
+        self.snapshots.remove(&tab);
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(url: &str) -> HibernationSnapshot {
+        HibernationSnapshot {
+            url: url.to_string(),
+            title: "Example".to_string(),
+            scroll: (0.0, 840.0),
+        }
+    }
+
+    #[test]
+    fn take_only_returns_snapshots_for_the_same_page() {
+        let mut hibernation = Hibernation::new();
+        let tab = TabId::new(3);
+
+        hibernation.store(tab, snapshot("https://example.com/a#intro"));
+        let restored = hibernation.take(tab, "https://example.com/a");
+        assert_eq!(restored.map(|snapshot| snapshot.scroll), Some((0.0, 840.0)));
+        assert!(hibernation.is_empty());
+
+        hibernation.store(tab, snapshot("https://example.com/a"));
+        assert_eq!(hibernation.take(tab, "https://example.com/b"), None);
+        assert!(hibernation.is_empty());
+
+        hibernation.store(tab, snapshot("https://example.com/a"));
+        hibernation.forget(tab);
+        assert_eq!(hibernation.take(tab, "https://example.com/a"), None);
+    }
+}
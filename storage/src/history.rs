@@ -0,0 +1,273 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::write_atomic;
+use crate::{HistoryEntry, HistoryStore};
+
+/// Number of distinct URLs kept; the least recently visited are dropped first.
+const MAX_ENTRIES: usize = 5000;
+
+/// Shortest time between two writes caused by recorded visits.
+pub const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// JSON file-backed history store.
+///
+/// The whole history is kept in memory and written back atomically. A
+/// visit only triggers a write once [`SAVE_INTERVAL`] has passed since the
+/// last one, so visits in between wait for the next write or
+/// [`FileHistoryStore::flush`].
+#[derive(Debug, Clone)]
+pub struct FileHistoryStore {
+    path: PathBuf,
+    /// Ordered by last visit, oldest first.
+    entries: Vec<HistoryEntry>,
+    /// Visit time of the last write caused by a visit.
+    saved_at: Option<SystemTime>,
+    /// Whether `entries` holds visits that are not on disk yet.
+    unsaved: bool,
+}
+
+impl FileHistoryStore {
+    /// Opens the history at `path`, starting empty if it is missing or
+    /// unreadable.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let entries = match read_entries(&path) {
+            Ok(entries) => entries,
+            Err(error) => {
+                log::warn!("failed to load history from {}: {error}", path.display());
+                Vec::new()
+            }
+        };
+        Self {
+            path,
+            entries,
+            saved_at: None,
+            unsaved: false,
+        }
+    }
+
+    /// Returns the path of the history file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns whether visits are waiting to be written.
+    pub fn has_unsaved(&self) -> bool {
+        self.unsaved
+    }
+
+    /// Writes pending visits now, e.g. before the browser exits.
+    pub fn flush(&mut self) {
+        if self.unsaved {
+            self.save();
+        }
+    }
+
+    fn save(&mut self) {
+        self.unsaved = false;
+        let stored = StoredHistory {
+            entries: self.entries.iter().map(StoredEntry::from_entry).collect(),
+        };
+        let result = serde_json::to_vec(&stored)
+            .map_err(io::Error::from)
+            .and_then(|json| write_atomic(&self.path, &json));
+        if let Err(error) = result {
+            log::warn!("failed to save history to {}: {error}", self.path.display());
+        }
+    }
+}
+
+impl HistoryStore for FileHistoryStore {
+    fn record_visit(&mut self, url: &str, title: &str, timestamp: SystemTime) {
+        match self.entries.last_mut() {
+            Some(last) if last.url == url => {
+                if !title.is_empty() {
+                    last.title = title.to_string();
+                }
+                last.last_visit = timestamp;
+            }
+            _ => {
+                let previous = self
+                    .entries
+                    .iter()
+                    .position(|entry| entry.url == url)
+                    .map(|index| self.entries.remove(index));
+                let visit_count = previous.as_ref().map_or(0, |entry| entry.visit_count);
+                let title = match previous {
+                    Some(previous) if title.is_empty() => previous.title,
+                    _ => title.to_string(),
+                };
+                self.entries.push(HistoryEntry {
+                    url: url.to_string(),
+                    title,
+                    last_visit: timestamp,
+                    visit_count: visit_count.saturating_add(1),
+                });
+                if self.entries.len() > MAX_ENTRIES {
+                    let excess = self.entries.len() - MAX_ENTRIES;
+                    self.entries.drain(..excess);
+                }
+            }
+        }
+        self.unsaved = true;
+        // A clock that went backwards counts as due, so it cannot hold
+        // writes back indefinitely.
+        let due = self.saved_at.is_none_or(|saved| {
+            !timestamp
+                .duration_since(saved)
+                .is_ok_and(|since| since < SAVE_INTERVAL)
+        });
+        if due {
+            self.saved_at = Some(timestamp);
+            self.save();
+        }
+    }
+
+    fn recent(&self, limit: usize) -> Vec<HistoryEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+
+    fn search(&self, query: &str, limit: usize) -> Vec<HistoryEntry> {
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut matches: Vec<&HistoryEntry> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| {
+                let url = entry.url.to_lowercase();
+                let title = entry.title.to_lowercase();
+                terms
+                    .iter()
+                    .all(|term| url.contains(term.as_str()) || title.contains(term.as_str()))
+            })
+            .collect();
+        // Stable, so equally visited entries stay most recent first.
+        matches.sort_by_key(|entry| std::cmp::Reverse(entry.visit_count));
+        matches.into_iter().take(limit).cloned().collect()
+    }
+}
+
+fn read_entries(path: &Path) -> io::Result<Vec<HistoryEntry>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let stored: StoredHistory = serde_json::from_slice(&bytes)?;
+    Ok(stored
+        .entries
+        .into_iter()
+        .map(StoredEntry::into_entry)
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredHistory {
+    entries: Vec<StoredEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredEntry {
+    url: String,
+    title: String,
+    /// Milliseconds since the Unix epoch.
+    visited_at: u64,
+    visits: u32,
+}
+
+impl StoredEntry {
+    fn from_entry(entry: &HistoryEntry) -> Self {
+        let visited_at = entry
+            .last_visit
+            .duration_since(UNIX_EPOCH)
+            .map(|since| u64::try_from(since.as_millis()).unwrap_or(u64::MAX))
+            .unwrap_or(0);
+        Self {
+            url: entry.url.clone(),
+            title: entry.title.clone(),
+            visited_at,
+            visits: entry.visit_count,
+        }
+    }
+
+    fn into_entry(self) -> HistoryEntry {
+        HistoryEntry {
+            url: self.url,
+            title: self.title,
+            last_visit: UNIX_EPOCH + Duration::from_millis(self.visited_at),
+            visit_count: self.visits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_dedupes_and_searches_visits() {
+        let dir = crate::test_dir("history");
+        let path = dir.join("history.json");
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let mut store = FileHistoryStore::open(&path);
+        store.record_visit("https://gnome.org/", "GNOME", at(0));
+        store.record_visit("https://gnome.org/", "GNOME", at(1));
+        store.record_visit("https://webkitgtk.org/", "WebKitGTK", at(2));
+        store.record_visit("https://gnome.org/", "GNOME Project", at(3));
+        store.flush();
+
+        let recent = store.recent(10);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].url, "https://gnome.org/");
+        assert_eq!(recent[0].title, "GNOME Project");
+        // The reload right after the first visit did not count.
+        assert_eq!(recent[0].visit_count, 2);
+        assert_eq!(recent[1].visit_count, 1);
+
+        let reopened = FileHistoryStore::open(&path);
+        assert_eq!(reopened.recent(10), recent);
+
+        let matches = reopened.search("webkit ORG", 10);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, "WebKitGTK");
+        assert_eq!(reopened.search("org", 1)[0].url, "https://gnome.org/");
+        assert!(reopened.search("mozilla", 10).is_empty());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn batches_writes_until_the_interval_passes_or_a_flush() {
+        let dir = crate::test_dir("history");
+        let path = dir.join("history.json");
+        let start = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let at = |secs| start + Duration::from_secs(secs);
+        let on_disk = || FileHistoryStore::open(&path).recent(10).len();
+
+        let mut store = FileHistoryStore::open(&path);
+        store.record_visit("https://gnome.org/", "GNOME", at(0));
+        assert_eq!(on_disk(), 1);
+
+        store.record_visit("https://webkitgtk.org/", "WebKitGTK", at(1));
+        assert!(store.has_unsaved());
+        assert_eq!(on_disk(), 1);
+
+        store.record_visit("https://fedoraproject.org/", "Fedora", at(10));
+        assert!(!store.has_unsaved());
+        assert_eq!(on_disk(), 3);
+
+        store.record_visit("https://kernel.org/", "Linux", at(11));
+        store.flush();
+        assert!(!store.has_unsaved());
+        assert_eq!(on_disk(), 4);
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,303 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tabs::TabId;
+
+use crate::file::StoredTab;
+use crate::{SessionSnapshot, SessionStore, TabSnapshot};
+
+/// A change to the session since the last full snapshot.
+///
+/// Events carry the resulting values rather than deltas, so replaying a
+/// journal over a snapshot that already contains some of its events still
+/// ends in the right state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JournalEvent {
+    /// A tab appeared, or changed in a way other than navigating.
+    Created(TabSnapshot),
+    Navigated { id: TabId, uri: String, title: String },
+    Closed { id: TabId },
+    Activated { id: Option<TabId> },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StoredEvent {
+    Created(StoredTab),
+    Navigated { id: u64, uri: String, title: String },
+    Closed { id: u64 },
+    Activated { id: Option<u64> },
+}
+
+impl From<&JournalEvent> for StoredEvent {
+    fn from(event: &JournalEvent) -> Self {
+        match event {
+            JournalEvent::Created(tab) => Self::Created(StoredTab::from(tab)),
+            JournalEvent::Navigated { id, uri, title } => Self::Navigated {
+                id: id.get(),
+                uri: uri.clone(),
+                title: title.clone(),
+            },
+            JournalEvent::Closed { id } => Self::Closed { id: id.get() },
+            JournalEvent::Activated { id } => Self::Activated {
+                id: id.map(TabId::get),
+            },
+        }
+    }
+}
+
+impl From<StoredEvent> for JournalEvent {
+    fn from(event: StoredEvent) -> Self {
+        match event {
+            StoredEvent::Created(tab) => Self::Created(tab.into()),
+            StoredEvent::Navigated { id, uri, title } => Self::Navigated {
+                id: TabId::new(id),
+                uri,
+                title,
+            },
+            StoredEvent::Closed { id } => Self::Closed { id: TabId::new(id) },
+            StoredEvent::Activated { id } => Self::Activated {
+                id: id.map(TabId::new),
+            },
+        }
+    }
+}
+
+/// Append-only log of session events, one JSON record per line.
+///
+/// Each event is written as soon as it is appended, so a crash loses at
+/// most the event being written. A record cut short by a crash ends the
+/// replay instead of failing it.
+#[derive(Debug)]
+pub struct SessionJournal {
+    path: PathBuf,
+    file: Option<File>,
+}
+
+impl SessionJournal {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            file: None,
+        }
+    }
+
+    /// Returns the path of the journal file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends `events` and writes them out before returning.
+    pub fn append(&mut self, events: &[JournalEvent]) -> io::Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut records = Vec::new();
+        for event in events {
+            serde_json::to_writer(&mut records, &StoredEvent::from(event))?;
+            records.push(b'\n');
+        }
+        let file = match &mut self.file {
+            Some(file) => file,
+            file => {
+                if let Some(parent) = self.path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let opened = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                file.insert(opened)
+            }
+        };
+        file.write_all(&records)?;
+        file.flush()
+    }
+
+    /// Applies the journal to `snapshot`, stopping at the first record that
+    /// cannot be read.
+    pub fn replay(&self, mut snapshot: SessionSnapshot) -> io::Result<SessionSnapshot> {
+        let contents = match fs::read(&self.path) {
+            Ok(contents) => contents,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(snapshot),
+            Err(error) => return Err(error),
+        };
+        for line in contents.split(|byte| *byte == b'\n') {
+            if line.is_empty() {
+                continue;
+            }
+            match serde_json::from_slice::<StoredEvent>(line) {
+                Ok(event) => apply(&mut snapshot, JournalEvent::from(event)),
+                Err(error) => {
+                    log::warn!(
+                        "ignoring the rest of session journal {}: {error}",
+                        self.path.display()
+                    );
+                    break;
+                }
+            }
+        }
+        Ok(snapshot)
+    }
+
+    /// Empties the journal once its events are part of a full snapshot.
+    pub fn truncate(&mut self) -> io::Result<()> {
+        self.file = None;
+        match fs::remove_file(&self.path) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        }
+    }
+
+    /// Rebuilds the session from the last full snapshot in `store` and the
+    /// journal, saves the result as the new full snapshot and truncates the
+    /// journal. Returns the recovered session.
+    ///
+    /// Must run before the first `append`: new records must not follow a
+    /// truncated one.
+    pub fn recover(&mut self, store: &impl SessionStore) -> SessionSnapshot {
+        let snapshot = store.load().unwrap_or_default();
+        if !self.path.exists() {
+            return snapshot;
+        }
+        let recovered = match self.replay(snapshot.clone()) {
+            Ok(recovered) => recovered,
+            Err(error) => {
+                log::warn!("failed to read session journal {}: {error}", self.path.display());
+                snapshot
+            }
+        };
+        store.save(&recovered);
+        if let Err(error) = self.truncate() {
+            log::warn!("failed to truncate session journal {}: {error}", self.path.display());
+        }
+        recovered
+    }
+}
+
+/// Applies one event to `snapshot`. Tabs created since the snapshot are
+/// appended, so their order is approximate.
+pub fn apply(snapshot: &mut SessionSnapshot, event: JournalEvent) {
+    match event {
+        JournalEvent::Created(tab) => {
+            match snapshot.tabs.iter_mut().find(|existing| existing.id == tab.id) {
+                Some(existing) => *existing = tab,
+                None => snapshot.tabs.push(tab),
+            }
+        }
+        JournalEvent::Navigated { id, uri, title } => {
+            if let Some(tab) = snapshot.tabs.iter_mut().find(|tab| tab.id == id) {
+                tab.uri = uri;
+                tab.title = title;
+            }
+        }
+        JournalEvent::Closed { id } => {
+            snapshot.tabs.retain(|tab| tab.id != id);
+            if snapshot.active == Some(id) {
+                snapshot.active = None;
+            }
+        }
+        JournalEvent::Activated { id } => snapshot.active = id,
+    }
+}
+
+/// Lists the events that turn `from` into `to`: closes first, then creates
+/// and navigations in tab order, then the activation.
+pub fn diff(from: &SessionSnapshot, to: &SessionSnapshot) -> Vec<JournalEvent> {
+    let mut events: Vec<JournalEvent> = from
+        .tabs
+        .iter()
+        .filter(|tab| !to.tabs.iter().any(|current| current.id == tab.id))
+        .map(|tab| JournalEvent::Closed { id: tab.id })
+        .collect();
+    for tab in &to.tabs {
+        let previous = from.tabs.iter().find(|previous| previous.id == tab.id);
+        match previous {
+            Some(previous) if previous == tab => {}
+            Some(previous) if previous.state == tab.state && previous.group == tab.group => {
+                events.push(JournalEvent::Navigated {
+                    id: tab.id,
+                    uri: tab.uri.clone(),
+                    title: tab.title.clone(),
+                });
+            }
+            _ => events.push(JournalEvent::Created(tab.clone())),
+        }
+    }
+    if from.active != to.active {
+        events.push(JournalEvent::Activated { id: to.active });
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::FileSessionStore;
+    use tabs::TabState;
+
+    fn tab(id: u64, uri: &str) -> TabSnapshot {
+        TabSnapshot {
+            id: TabId::new(id),
+            uri: uri.to_string(),
+            state: TabState::Background,
+            title: String::new(),
+            group: None,
+            group_id: None,
+        }
+    }
+
+    #[test]
+    fn recovers_snapshot_plus_journal_despite_a_truncated_record() {
+        let dir = crate::test_dir("journal");
+        let store = FileSessionStore::new(dir.join("session.json"));
+        let saved = SessionSnapshot {
+            tabs: vec![tab(1, "https://a.example/"), tab(2, "https://b.example/")],
+            active: Some(TabId::new(1)),
+        };
+        store.write(&saved).unwrap();
+
+        let mut current = saved.clone();
+        current.tabs.remove(0);
+        current.tabs[0].uri = "https://b.example/next".to_string();
+        current.tabs.push(tab(3, "https://c.example/"));
+        current.active = Some(TabId::new(3));
+
+        let mut journal = SessionJournal::new(dir.join("session.journal"));
+        journal.append(&diff(&saved, &current)).unwrap();
+        // A crash in the middle of writing the next record.
+        OpenOptions::new()
+            .append(true)
+            .open(journal.path())
+            .unwrap()
+            .write_all(b"{\"closed\":{\"i")
+            .unwrap();
+
+        let recovered = journal.recover(&store);
+        assert_eq!(recovered, current);
+        assert_eq!(store.read().unwrap(), Some(current));
+        assert!(!journal.path().exists());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn replaying_events_already_in_the_snapshot_is_harmless() {
+        let from = SessionSnapshot {
+            tabs: vec![tab(1, "https://a.example/")],
+            active: None,
+        };
+        let mut to = from.clone();
+        to.tabs.push(tab(2, "https://b.example/"));
+        to.tabs[0].state = TabState::Suspended;
+        let events = diff(&from, &to);
+
+        let mut replayed = to.clone();
+        for event in events {
+            apply(&mut replayed, event);
+        }
+        assert_eq!(replayed, to);
+    }
+}
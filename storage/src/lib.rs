@@ -1,15 +1,33 @@
+use std::time::SystemTime;
 use tabs::{TabId, TabState};
 
+pub mod bookmarks;
+pub mod export;
+pub mod favicons;
+pub mod file;
+pub mod hibernation;
+pub mod history;
+pub mod journal;
+pub mod library;
+pub mod permissions;
+pub mod settings;
+
 /// Snapshot of a single tab for session restore.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TabSnapshot {
     pub id: TabId,
     pub uri: String,
     pub state: TabState,
+    pub title: String,
+    /// Title of the group the tab belongs to, if any.
+    pub group: Option<String>,
+    /// Id of that group when the snapshot comes from a live window. Groups
+    /// can share a title, so this is what tells them apart.
+    pub group_id: Option<TabId>,
 }
 
 /// Snapshot of a browsing session.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct SessionSnapshot {
     pub tabs: Vec<TabSnapshot>,
     pub active: Option<TabId>,
@@ -24,6 +42,37 @@ pub trait SessionStore {
     fn save(&self, session: &SessionSnapshot);
 }
 
+/// A tab in a named session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedTab {
+    pub title: String,
+    pub url: String,
+    /// Title of the group the tab belongs to, if any.
+    pub group: Option<String>,
+}
+
+/// A named set of tabs that can be reopened later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SavedSession {
+    pub name: String,
+    pub tabs: Vec<SavedTab>,
+}
+
+/// Interface for the library of named sessions.
+pub trait SessionLibrary {
+    /// Stores `session`, replacing any session with the same name.
+    fn save(&mut self, session: SavedSession);
+
+    /// Returns all sessions in the order they were first saved.
+    fn list(&self) -> Vec<SavedSession>;
+
+    /// Looks a session up by exact name, then by its slug.
+    fn get(&self, name: &str) -> Option<SavedSession>;
+
+    /// Deletes the session called `name`. Returns whether it existed.
+    fn delete(&mut self, name: &str) -> bool;
+}
+
 /// No-op session store used during scaffolding.
 #[derive(Debug, Default)]
 pub struct NoopSessionStore;
@@ -37,3 +86,86 @@ impl SessionStore for NoopSessionStore {
         // TODO: Persist session state to disk.
     }
 }
+
+/// A saved page. `folder` is `None` for bookmarks at the top level; folders
+/// do not nest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bookmark {
+    pub title: String,
+    pub url: String,
+    pub folder: Option<String>,
+    /// When the bookmark was created, if known.
+    pub added: Option<SystemTime>,
+}
+
+/// Interface for bookmarks. A url appears at most once per folder.
+pub trait BookmarkStore {
+    /// Adds a bookmark. Returns `false` if its url is already in that folder.
+    fn add(&mut self, bookmark: Bookmark) -> bool;
+
+    /// Removes the bookmark for `url` in `folder`. Returns whether one existed.
+    fn remove(&mut self, url: &str, folder: Option<&str>) -> bool;
+
+    /// Returns all bookmarks in insertion order.
+    fn list(&self) -> Vec<Bookmark>;
+
+    /// Moves the bookmark for `url` from one folder to another. Returns
+    /// `false` if it does not exist or `to` already holds that url.
+    fn move_to(&mut self, url: &str, from: Option<&str>, to: Option<&str>) -> bool;
+}
+
+/// Interface for the per-host favicon cache.
+pub trait FaviconCache {
+    /// Returns the cached icon uri for `host`, unless it expired by `now`.
+    fn get(&self, host: &str, now: SystemTime) -> Option<String>;
+
+    /// Caches `icon` as the favicon of `host`.
+    fn store(&mut self, host: &str, icon: &str, now: SystemTime);
+}
+
+/// A visited page, aggregated over all visits to the same URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub url: String,
+    pub title: String,
+    pub last_visit: SystemTime,
+    pub visit_count: u32,
+}
+
+/// Interface for remembered per-origin permission decisions. `kind` names
+/// the permission, e.g. `geolocation`.
+pub trait PermissionStore {
+    /// Returns whether `origin` was allowed `kind`, if the user decided.
+    fn decision(&self, origin: &str, kind: &str) -> Option<bool>;
+
+    /// Remembers the user's decision, replacing any earlier one.
+    fn decide(&mut self, origin: &str, kind: &str, allow: bool);
+
+    /// Forgets every decision for `origin`. Returns whether any existed.
+    fn forget(&mut self, origin: &str) -> bool;
+}
+
+/// Interface for browsing history.
+pub trait HistoryStore {
+    /// Records a visit. A visit to the same URL as the previous one (e.g. a
+    /// reload) only refreshes that entry instead of counting again.
+    fn record_visit(&mut self, url: &str, title: &str, timestamp: SystemTime);
+
+    /// Returns up to `limit` entries, most recently visited first.
+    fn recent(&self, limit: usize) -> Vec<HistoryEntry>;
+
+    /// Returns up to `limit` entries whose URL or title contains every
+    /// whitespace-separated term of `query`, most visited first.
+    fn search(&self, query: &str, limit: usize) -> Vec<HistoryEntry>;
+}
+
+/// Returns a path for a test's files that no other test of any run uses, so
+/// tests running in parallel never share files.
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> std::path::PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let index = NEXT.fetch_add(1, Ordering::Relaxed);
+    std::env::temp_dir().join(format!("owl-{name}-{}-{index}", std::process::id()))
+}
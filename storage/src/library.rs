@@ -0,0 +1,229 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::write_atomic;
+use crate::{SavedSession, SavedTab, SessionLibrary};
+
+/// JSON file-backed session library.
+///
+/// Sessions are kept in the order they were first saved and written back
+/// atomically after every change.
+#[derive(Debug, Clone)]
+pub struct FileSessionLibrary {
+    path: PathBuf,
+    sessions: Vec<SavedSession>,
+}
+
+impl FileSessionLibrary {
+    /// Opens the library at `path`, starting empty if it is missing or
+    /// unreadable.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let sessions = match read_sessions(&path) {
+            Ok(sessions) => sessions,
+            Err(error) => {
+                log::warn!(
+                    "failed to load session library from {}: {error}",
+                    path.display()
+                );
+                Vec::new()
+            }
+        };
+        Self { path, sessions }
+    }
+
+    /// Returns the path of the library file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save_file(&self) {
+        let stored = StoredLibrary {
+            sessions: self
+                .sessions
+                .iter()
+                .map(|session| StoredSession {
+                    name: session.name.clone(),
+                    tabs: session
+                        .tabs
+                        .iter()
+                        .map(|tab| StoredTab {
+                            title: tab.title.clone(),
+                            url: tab.url.clone(),
+                            group: tab.group.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+        };
+        let result = serde_json::to_vec(&stored)
+            .map_err(io::Error::from)
+            .and_then(|json| write_atomic(&self.path, &json));
+        if let Err(error) = result {
+            log::warn!(
+                "failed to save session library to {}: {error}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+impl SessionLibrary for FileSessionLibrary {
+    fn save(&mut self, session: SavedSession) {
+        match self
+            .sessions
+            .iter_mut()
+            .find(|existing| existing.name == session.name)
+        {
+            Some(existing) => *existing = session,
+            None => self.sessions.push(session),
+        }
+        self.save_file();
+    }
+
+    fn list(&self) -> Vec<SavedSession> {
+        self.sessions.clone()
+    }
+
+    fn get(&self, name: &str) -> Option<SavedSession> {
+        self.sessions
+            .iter()
+            .find(|session| session.name == name)
+            .or_else(|| {
+                self.sessions
+                    .iter()
+                    .find(|session| session_slug(&session.name) == name)
+            })
+            .cloned()
+    }
+
+    fn delete(&mut self, name: &str) -> bool {
+        let before = self.sessions.len();
+        self.sessions.retain(|session| session.name != name);
+        if self.sessions.len() == before {
+            return false;
+        }
+        self.save_file();
+        true
+    }
+}
+
+/// Turns a session name into the slug used in `owl://session/<slug>` urls:
+/// lowercase alphanumerics with single dashes in between.
+pub fn session_slug(name: &str) -> String {
+    let mut slug = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+fn read_sessions(path: &Path) -> io::Result<Vec<SavedSession>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let stored: StoredLibrary = serde_json::from_slice(&bytes)?;
+    Ok(stored
+        .sessions
+        .into_iter()
+        .map(|session| SavedSession {
+            name: session.name,
+            tabs: session
+                .tabs
+                .into_iter()
+                .map(|tab| SavedTab {
+                    title: tab.title,
+                    url: tab.url,
+                    group: tab.group,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredLibrary {
+    sessions: Vec<StoredSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredSession {
+    name: String,
+    tabs: Vec<StoredTab>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTab {
+    title: String,
+    url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(name: &str, urls: &[&str]) -> SavedSession {
+        SavedSession {
+            name: name.to_string(),
+            tabs: urls
+                .iter()
+                .map(|url| SavedTab {
+                    title: url.to_string(),
+                    url: url.to_string(),
+                    group: Some("Docs".to_string()),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn saves_replaces_and_deletes_by_name() {
+        let dir = crate::test_dir("library");
+        let path = dir.join("sessions.json");
+
+        let mut library = FileSessionLibrary::open(&path);
+        library.save(session("Morning Reads", &["https://gnome.org/"]));
+        library.save(session("Work", &["https://gtk.org/"]));
+        library.save(session(
+            "Morning Reads",
+            &["https://lwn.net/", "https://webkit.org/"],
+        ));
+
+        let reopened = FileSessionLibrary::open(&path);
+        let names: Vec<String> = reopened.list().into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["Morning Reads", "Work"]);
+        let morning = reopened.get("morning-reads").expect("found by slug");
+        assert_eq!(morning.tabs.len(), 2);
+        assert_eq!(morning.tabs[0].group.as_deref(), Some("Docs"));
+
+        let mut library = reopened;
+        assert!(library.delete("Work"));
+        assert!(!library.delete("Work"));
+        assert!(library.get("Work").is_none());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn slugs_collapse_separators() {
+        assert_eq!(
+            session_slug("  Release / Planning 2024! "),
+            "release-planning-2024"
+        );
+        assert_eq!(session_slug("Research Notes"), "research-notes");
+    }
+}
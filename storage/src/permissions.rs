@@ -0,0 +1,137 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::write_atomic;
+use crate::PermissionStore;
+
+/// JSON file-backed permission decisions, written back atomically after
+/// every change.
+#[derive(Debug, Clone)]
+pub struct FilePermissionStore {
+    path: PathBuf,
+    decisions: Vec<StoredDecision>,
+}
+
+impl FilePermissionStore {
+    /// Opens the decisions at `path`, starting empty if they are missing or
+    /// unreadable.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let decisions = match read_decisions(&path) {
+            Ok(decisions) => decisions,
+            Err(error) => {
+                log::warn!("failed to load permissions from {}: {error}", path.display());
+                Vec::new()
+            }
+        };
+        Self { path, decisions }
+    }
+
+    /// Returns the path of the permissions file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save(&self) {
+        let stored = StoredPermissions {
+            decisions: self.decisions.clone(),
+        };
+        let result = serde_json::to_vec(&stored)
+            .map_err(io::Error::from)
+            .and_then(|json| write_atomic(&self.path, &json));
+        if let Err(error) = result {
+            log::warn!("failed to save permissions to {}: {error}", self.path.display());
+        }
+    }
+}
+
+impl PermissionStore for FilePermissionStore {
+    fn decision(&self, origin: &str, kind: &str) -> Option<bool> {
+        self.decisions
+            .iter()
+            .find(|decision| decision.origin == origin && decision.kind == kind)
+            .map(|decision| decision.allow)
+    }
+
+    fn decide(&mut self, origin: &str, kind: &str, allow: bool) {
+        match self
+            .decisions
+            .iter_mut()
+            .find(|decision| decision.origin == origin && decision.kind == kind)
+        {
+            Some(decision) if decision.allow == allow => return,
+            Some(decision) => decision.allow = allow,
+            None => self.decisions.push(StoredDecision {
+                origin: origin.to_string(),
+                kind: kind.to_string(),
+                allow,
+            }),
+        }
+        self.save();
+    }
+
+    fn forget(&mut self, origin: &str) -> bool {
+        let before = self.decisions.len();
+        self.decisions.retain(|decision| decision.origin != origin);
+        if self.decisions.len() == before {
+            return false;
+        }
+        self.save();
+        true
+    }
+}
+
+fn read_decisions(path: &Path) -> io::Result<Vec<StoredDecision>> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(error),
+    };
+    let stored: StoredPermissions = serde_json::from_slice(&bytes)?;
+    Ok(stored.decisions)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredPermissions {
+    decisions: Vec<StoredDecision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredDecision {
+    origin: String,
+    kind: String,
+    allow: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decisions_are_per_origin_and_kind_and_persist() {
+        let dir = crate::test_dir("permissions");
+        let path = dir.join("permissions.json");
+
+        let mut store = FilePermissionStore::open(&path);
+        store.decide("https://maps.example", "geolocation", true);
+        store.decide("https://maps.example", "notifications", false);
+        store.decide("https://news.example", "notifications", true);
+        store.decide("https://maps.example", "geolocation", false);
+
+        let reopened = FilePermissionStore::open(&path);
+        assert_eq!(reopened.decision("https://maps.example", "geolocation"), Some(false));
+        assert_eq!(reopened.decision("https://maps.example", "notifications"), Some(false));
+        assert_eq!(reopened.decision("https://maps.example", "media"), None);
+
+        store.decide("https://maps.example", "media", true);
+        assert!(store.forget("https://maps.example"));
+        assert!(!store.forget("https://maps.example"));
+        assert_eq!(store.decision("https://maps.example", "media"), None);
+        assert_eq!(store.decision("https://news.example", "notifications"), Some(true));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
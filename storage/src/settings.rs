@@ -0,0 +1,350 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::file::write_atomic;
+
+/// Search engine used until the user picks another one.
+pub const DEFAULT_SEARCH_TEMPLATE: &str = "https://duckduckgo.com/?q={}";
+
+/// Page opened by the home button.
+pub const DEFAULT_HOME_PAGE: &str = "owl://home";
+
+/// How hard background timers are throttled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerClamp {
+    /// No timer clamps, only suspension.
+    Off,
+    /// The scheduler's default clamps.
+    #[default]
+    Balanced,
+    /// Clamp intervals doubled, for battery or low-end machines.
+    Aggressive,
+}
+
+/// What a new tab opens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewTabPage {
+    #[default]
+    Home,
+    /// An empty page that never touches the network.
+    Blank,
+    /// The most recently created saved session, or home if there is none.
+    LastSession,
+}
+
+/// Where page requests are sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// The desktop's proxy configuration.
+    #[default]
+    System,
+    /// Straight to the network, ignoring any system proxy.
+    None,
+    /// Through the proxy in `Settings::proxy_address`.
+    Manual,
+}
+
+/// Schemes a manual proxy may use; an address without one is an http proxy.
+const PROXY_SCHEMES: &[&str] = &["http", "https", "socks", "socks4", "socks4a", "socks5"];
+
+/// Turns a manual proxy address, `host:port` with an optional scheme from
+/// [`PROXY_SCHEMES`], into a proxy uri. Returns `None` for anything else.
+pub fn proxy_uri(address: &str) -> Option<String> {
+    let address = address.trim();
+    let (scheme, authority) = match address.split_once("://") {
+        Some((scheme, authority)) => (scheme.to_ascii_lowercase(), authority),
+        None => ("http".to_string(), address),
+    };
+    if !PROXY_SCHEMES.contains(&scheme.as_str()) {
+        return None;
+    }
+    let authority = authority.strip_suffix('/').unwrap_or(authority);
+    let (host, port) = authority.rsplit_once(':')?;
+    // Bracketed IPv6 literals keep their colons inside the brackets.
+    let host_ok = match host.strip_prefix('[') {
+        Some(literal) => literal.strip_suffix(']').is_some_and(|literal| {
+            !literal.is_empty() && literal.chars().all(|c| c.is_ascii_hexdigit() || c == ':')
+        }),
+        None => {
+            !host.is_empty()
+                && host
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-')
+        }
+    };
+    if !host_ok || port.parse::<u16>().map_or(true, |port| port == 0) {
+        return None;
+    }
+    Some(format!("{scheme}://{authority}"))
+}
+
+/// Text size and contrast preferences for page content, independent of zoom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Font size, in pixels, for text without an explicit size.
+    pub default_font_size: u32,
+    /// Smallest font size, in pixels, pages may use; 0 for no minimum.
+    pub minimum_font_size: u32,
+    /// Overrides page colors with a high-contrast stylesheet.
+    pub high_contrast: bool,
+}
+
+impl Default for Accessibility {
+    fn default() -> Self {
+        Self {
+            default_font_size: 16,
+            minimum_font_size: 0,
+            high_contrast: false,
+        }
+    }
+}
+
+/// User preferences. Fields missing from the file keep their defaults, so
+/// settings written by older versions still load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Search url for address bar input; `{}` is replaced by the query.
+    pub search_template: String,
+    pub home_page: String,
+    pub new_tab_page: NewTabPage,
+    /// Moves keyboard focus to the page once a navigation the user started
+    /// commits.
+    pub focus_on_navigate: bool,
+    pub timer_clamp: TimerClamp,
+    pub force_dark: bool,
+    /// Zooming scales only text instead of the whole page.
+    pub zoom_text_only: bool,
+    pub accessibility: Accessibility,
+    pub content_filter: bool,
+    pub proxy_mode: ProxyMode,
+    /// `host:port`, optionally with a scheme; only used by
+    /// [`ProxyMode::Manual`].
+    pub proxy_address: String,
+    /// Free memory, in percent, below which pressure is moderate.
+    pub memory_moderate_percent: u8,
+    /// Free memory, in percent, below which pressure is severe.
+    pub memory_severe_percent: u8,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            search_template: DEFAULT_SEARCH_TEMPLATE.to_string(),
+            home_page: DEFAULT_HOME_PAGE.to_string(),
+            new_tab_page: NewTabPage::default(),
+            focus_on_navigate: true,
+            timer_clamp: TimerClamp::default(),
+            force_dark: false,
+            zoom_text_only: false,
+            accessibility: Accessibility::default(),
+            content_filter: true,
+            proxy_mode: ProxyMode::default(),
+            proxy_address: String::new(),
+            memory_moderate_percent: 20,
+            memory_severe_percent: 10,
+        }
+    }
+}
+
+/// Why a settings update was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidSetting {
+    /// Not an http(s) url, or no `{}` placeholder for the query.
+    SearchTemplate,
+    /// Not an http(s), file or `owl://` url.
+    HomePage,
+    /// Severe must be below moderate, and both within 1-99.
+    MemoryThresholds,
+    /// The default font size must be within 6-72 and not below the minimum.
+    FontSizes,
+    /// A manual proxy without a valid `host:port` address.
+    ProxyAddress,
+}
+
+impl fmt::Display for InvalidSetting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            Self::SearchTemplate => "search template must be an http(s) url containing {}",
+            Self::HomePage => "home page must be an http(s), file or owl:// url",
+            Self::MemoryThresholds => "memory thresholds must satisfy 0 < severe < moderate < 100",
+            Self::FontSizes => "font sizes must satisfy minimum <= default and 6 <= default <= 72",
+            Self::ProxyAddress => "proxy address must be host:port with an optional proxy scheme",
+        };
+        f.write_str(message)
+    }
+}
+
+impl Settings {
+    /// Checks every field, reporting the first invalid one.
+    pub fn validate(&self) -> Result<(), InvalidSetting> {
+        let template = self.search_template.trim();
+        if !template.contains("{}") || !has_scheme(template, &["http", "https"]) {
+            return Err(InvalidSetting::SearchTemplate);
+        }
+        if !has_scheme(self.home_page.trim(), &["http", "https", "file", "owl"]) {
+            return Err(InvalidSetting::HomePage);
+        }
+        let (moderate, severe) = (self.memory_moderate_percent, self.memory_severe_percent);
+        if severe == 0 || severe >= moderate || moderate >= 100 {
+            return Err(InvalidSetting::MemoryThresholds);
+        }
+        let fonts = self.accessibility;
+        if !(6..=72).contains(&fonts.default_font_size)
+            || fonts.minimum_font_size > fonts.default_font_size
+        {
+            return Err(InvalidSetting::FontSizes);
+        }
+        if self.proxy_mode == ProxyMode::Manual && proxy_uri(&self.proxy_address).is_none() {
+            return Err(InvalidSetting::ProxyAddress);
+        }
+        Ok(())
+    }
+}
+
+/// Whether `url` is `<scheme>://` followed by something, for one of `schemes`.
+fn has_scheme(url: &str, schemes: &[&str]) -> bool {
+    url.split_once("://").is_some_and(|(scheme, rest)| {
+        !rest.is_empty() && schemes.iter().any(|allowed| scheme.eq_ignore_ascii_case(allowed))
+    })
+}
+
+/// JSON file-backed settings, written back atomically on every change.
+#[derive(Debug, Clone)]
+pub struct FileSettingsStore {
+    path: PathBuf,
+    settings: Settings,
+}
+
+impl FileSettingsStore {
+    /// Opens the settings at `path`. Missing, unreadable or invalid files
+    /// fall back to the defaults.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let settings = match read_settings(&path) {
+            Ok(settings) if settings.validate().is_ok() => settings,
+            Ok(_) => {
+                log::warn!("ignoring invalid settings in {}", path.display());
+                Settings::default()
+            }
+            Err(error) => {
+                log::warn!("failed to load settings from {}: {error}", path.display());
+                Settings::default()
+            }
+        };
+        Self { path, settings }
+    }
+
+    /// Returns the path of the settings file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Replaces the settings if they are valid. Returns whether anything
+    /// changed.
+    pub fn set(&mut self, settings: Settings) -> Result<bool, InvalidSetting> {
+        settings.validate()?;
+        if settings == self.settings {
+            return Ok(false);
+        }
+        self.settings = settings;
+        self.save();
+        Ok(true)
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_vec_pretty(&self.settings)
+            .map_err(io::Error::from)
+            .and_then(|json| write_atomic(&self.path, &json));
+        if let Err(error) = result {
+            log::warn!("failed to save settings to {}: {error}", self.path.display());
+        }
+    }
+}
+
+fn read_settings(path: &Path) -> io::Result<Settings> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Settings::default()),
+        Err(error) => return Err(error),
+    };
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_updates_and_persists_valid_ones() {
+        let dir = crate::test_dir("settings");
+        let path = dir.join("settings.json");
+
+        let mut store = FileSettingsStore::open(&path);
+        assert_eq!(store.get(), &Settings::default());
+
+        let mut update = store.get().clone();
+        update.search_template = "https://example.com/search".to_string();
+        assert_eq!(store.set(update), Err(InvalidSetting::SearchTemplate));
+
+        let mut update = store.get().clone();
+        update.memory_severe_percent = 30;
+        assert_eq!(store.set(update), Err(InvalidSetting::MemoryThresholds));
+
+        let mut update = store.get().clone();
+        update.accessibility.minimum_font_size = 20;
+        assert_eq!(store.set(update), Err(InvalidSetting::FontSizes));
+
+        let mut update = store.get().clone();
+        update.search_template = "https://example.com/?q={}".to_string();
+        update.timer_clamp = TimerClamp::Aggressive;
+        update.accessibility.high_contrast = true;
+        assert_eq!(store.set(update.clone()), Ok(true));
+        assert_eq!(store.set(update.clone()), Ok(false));
+
+        assert_eq!(FileSettingsStore::open(&path).get(), &update);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn proxy_addresses_need_a_host_and_port() {
+        assert_eq!(proxy_uri("proxy.lan:3128").as_deref(), Some("http://proxy.lan:3128"));
+        assert_eq!(
+            proxy_uri(" SOCKS5://127.0.0.1:9050/ ").as_deref(),
+            Some("socks5://127.0.0.1:9050")
+        );
+        assert_eq!(proxy_uri("[::1]:8080").as_deref(), Some("http://[::1]:8080"));
+        assert_eq!(proxy_uri("proxy.lan"), None);
+        assert_eq!(proxy_uri("proxy.lan:0"), None);
+        assert_eq!(proxy_uri("proxy.lan:99999"), None);
+        assert_eq!(proxy_uri("ftp://proxy.lan:21"), None);
+        assert_eq!(proxy_uri("user@proxy.lan:3128"), None);
+
+        let settings = Settings {
+            proxy_mode: ProxyMode::Manual,
+            ..Settings::default()
+        };
+        assert_eq!(settings.validate(), Err(InvalidSetting::ProxyAddress));
+    }
+
+    #[test]
+    fn missing_fields_keep_their_defaults() {
+        let settings: Settings = serde_json::from_str(r#"{"force_dark":true}"#).unwrap();
+        assert!(settings.force_dark);
+        assert_eq!(settings.search_template, DEFAULT_SEARCH_TEMPLATE);
+        assert_eq!(settings.new_tab_page, NewTabPage::Home);
+        assert!(settings.focus_on_navigate);
+    }
+}
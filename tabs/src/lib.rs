@@ -2,6 +2,9 @@ use std::fmt;
 
 use util::IdGenerator;
 
+pub mod redirect;
+pub mod state;
+
 /// Stable identifier for a browser tab.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct TabId(u64);
@@ -29,6 +32,9 @@ impl fmt::Display for TabId {
 pub enum TabState {
     Active,
     Background,
+    /// Hidden with JavaScript paused but the DOM retained; resumes cheaply.
+    Frozen,
+    /// Hidden with JavaScript disabled; reserved for forced reclamation.
     Suspended,
 }
 
@@ -37,6 +43,16 @@ pub enum TabState {
 pub struct TabEntry {
     pub id: TabId,
     pub state: TabState,
+    /// Group the tab belongs to; `None` for ungrouped tabs.
+    pub group: Option<TabId>,
+}
+
+/// A named set of tabs. Group ids come from the same sequence as tab ids,
+/// so the two never collide.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabGroup {
+    pub id: TabId,
+    pub title: String,
 }
 
 /// Interface for tab lifecycle and state management.
@@ -55,12 +71,62 @@ pub trait TabManager {
 
     /// Returns the ordered list of tabs.
     fn tabs(&self) -> &[TabEntry];
+
+    /// Returns the record for a tab, if it exists.
+    fn get(&self, id: TabId) -> Option<&TabEntry> {
+        self.tabs().iter().find(|tab| tab.id == id)
+    }
+
+    /// Returns the lifecycle state of a tab, if it exists.
+    ///
+    /// State changes go through [`TabManager::set_state`] so the active tab
+    /// bookkeeping stays consistent; there is intentionally no `get_mut`.
+    fn state_of(&self, id: TabId) -> Option<TabState> {
+        self.get(id).map(|tab| tab.state)
+    }
+
+    /// Iterates over the tabs in `state`, in tab order.
+    fn tabs_in_state(&self, state: TabState) -> impl Iterator<Item = &TabEntry>
+    where
+        Self: Sized,
+    {
+        self.tabs().iter().filter(move |tab| tab.state == state)
+    }
+
+    /// Iterates over the `Active` tabs; there is at most one.
+    fn active_tabs(&self) -> impl Iterator<Item = &TabEntry>
+    where
+        Self: Sized,
+    {
+        self.tabs_in_state(TabState::Active)
+    }
+
+    /// Iterates over the `Background` tabs, in tab order.
+    fn background_tabs(&self) -> impl Iterator<Item = &TabEntry>
+    where
+        Self: Sized,
+    {
+        self.tabs_in_state(TabState::Background)
+    }
+
+    /// Iterates over the `Suspended` tabs, in tab order.
+    fn suspended_tabs(&self) -> impl Iterator<Item = &TabEntry>
+    where
+        Self: Sized,
+    {
+        self.tabs_in_state(TabState::Suspended)
+    }
 }
 
 /// Minimal in-memory tab manager suitable for early scaffolding.
+///
+/// Creating a tab demotes only the previously `Active` tab to `Background`.
+/// Activating a tab moves every other tab to `Background` unless it is
+/// `Suspended`. Suspended tabs keep their state either way.
 #[derive(Debug, Default)]
 pub struct BasicTabManager {
     tabs: Vec<TabEntry>,
+    groups: Vec<TabGroup>,
     active: Option<TabId>,
     ids: IdGenerator,
 }
@@ -76,20 +142,85 @@ impl BasicTabManager {
         let next_idx = (idx + 1) % self.tabs.len();
         Some(self.tabs[next_idx].id)
     }
-}
 
-impl TabManager for BasicTabManager {
-    fn create_tab(&mut self) -> TabEntry {
+    /// Returns the next tab after the active one that satisfies `pred`, wrapping.
+    ///
+    /// The active tab itself is never returned; `None` means no other tab qualifies.
+    pub fn next_tab_filtered(&self, pred: impl Fn(&TabEntry) -> bool) -> Option<TabId> {
+        let active = self.active?;
+        let idx = self.tabs.iter().position(|tab| tab.id == active)?;
+        let len = self.tabs.len();
+        (1..len)
+            .map(|offset| &self.tabs[(idx + offset) % len])
+            .find(|tab| pred(tab))
+            .map(|tab| tab.id)
+    }
+
+    /// Creates an empty group and returns its id.
+    pub fn create_group(&mut self, title: &str) -> TabId {
+        let id = TabId::new(self.ids.next());
+        self.groups.push(TabGroup {
+            id,
+            title: title.to_string(),
+        });
+        id
+    }
+
+    /// Returns the groups in creation order.
+    pub fn groups(&self) -> &[TabGroup] {
+        &self.groups
+    }
+
+    /// Moves `tab` into `group`, or out of any group for `None`. Returns
+    /// `false` if the tab or the group does not exist.
+    pub fn set_group(&mut self, tab: TabId, group: Option<TabId>) -> bool {
+        if group.is_some_and(|group| !self.groups.iter().any(|known| known.id == group)) {
+            return false;
+        }
+        let Some(tab) = self.tabs.iter_mut().find(|entry| entry.id == tab) else {
+            return false;
+        };
+        tab.group = group;
+        true
+    }
+
+    /// Iterates over the tabs in `group`, in tab order.
+    pub fn tabs_in_group(&self, group: TabId) -> impl Iterator<Item = &TabEntry> {
+        self.tabs.iter().filter(move |tab| tab.group == Some(group))
+    }
+
+    /// Removes `group`, leaving its tabs ungrouped. Returns whether it existed.
+    pub fn remove_group(&mut self, group: TabId) -> bool {
+        let Some(index) = self.groups.iter().position(|known| known.id == group) else {
+            return false;
+        };
+        self.groups.remove(index);
+        for tab in &mut self.tabs {
+            if tab.group == Some(group) {
+                tab.group = None;
+            }
+        }
+        true
+    }
+
+    fn demote_active(&mut self) {
         for tab in &mut self.tabs {
             if tab.state == TabState::Active {
                 tab.state = TabState::Background;
             }
         }
+    }
+}
+
+impl TabManager for BasicTabManager {
+    fn create_tab(&mut self) -> TabEntry {
+        self.demote_active();
 
         let id = TabId::new(self.ids.next());
         let entry = TabEntry {
             id,
             state: TabState::Active,
+            group: None,
         };
         self.tabs.push(entry.clone());
         self.active = Some(id);
@@ -136,3 +267,121 @@ impl TabManager for BasicTabManager {
         &self.tabs
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_returns_present_tabs_only() {
+        let mut manager = BasicTabManager::new();
+        let first = manager.create_tab().id;
+        let second = manager.create_tab().id;
+
+        assert_eq!(manager.get(first).map(|tab| tab.id), Some(first));
+        assert_eq!(manager.state_of(first), Some(TabState::Background));
+        assert_eq!(manager.state_of(second), Some(TabState::Active));
+        assert!(manager.get(TabId::new(999)).is_none());
+        assert_eq!(manager.state_of(TabId::new(999)), None);
+    }
+
+    #[test]
+    fn groups_collect_tabs_until_removed() {
+        let mut manager = BasicTabManager::new();
+        let first = manager.create_tab().id;
+        let second = manager.create_tab().id;
+        let group = manager.create_group("Reading");
+        assert_ne!(group, first);
+        assert_ne!(group, second);
+
+        assert!(manager.set_group(second, Some(group)));
+        assert!(!manager.set_group(first, Some(TabId::new(999))));
+        assert!(!manager.set_group(TabId::new(999), Some(group)));
+        let grouped: Vec<TabId> = manager.tabs_in_group(group).map(|tab| tab.id).collect();
+        assert_eq!(grouped, vec![second]);
+        assert_eq!(manager.get(first).and_then(|tab| tab.group), None);
+
+        assert!(manager.remove_group(group));
+        assert!(manager.groups().is_empty());
+        assert_eq!(manager.get(second).and_then(|tab| tab.group), None);
+        assert!(!manager.remove_group(group));
+    }
+
+    #[test]
+    fn next_tab_filtered_skips_suspended() {
+        let mut manager = BasicTabManager::new();
+        let first = manager.create_tab().id;
+        let middle = manager.create_tab().id;
+        let last = manager.create_tab().id;
+        manager.set_state(middle, TabState::Suspended);
+        manager.set_active(first);
+
+        let not_suspended = |tab: &TabEntry| tab.state != TabState::Suspended;
+        assert_eq!(manager.next_tab(), Some(middle));
+        assert_eq!(manager.next_tab_filtered(not_suspended), Some(last));
+
+        manager.set_active(last);
+        assert_eq!(manager.next_tab_filtered(not_suspended), Some(first));
+    }
+
+    #[test]
+    fn next_tab_filtered_returns_none_without_candidates() {
+        let mut manager = BasicTabManager::new();
+        let only = manager.create_tab().id;
+        assert_eq!(manager.next_tab_filtered(|_| true), None);
+
+        let other = manager.create_tab().id;
+        manager.set_state(other, TabState::Suspended);
+        manager.set_active(only);
+        assert_eq!(
+            manager.next_tab_filtered(|tab| tab.state != TabState::Suspended),
+            None
+        );
+    }
+
+    #[test]
+    fn state_iterators_filter_in_tab_order() {
+        let mut manager = BasicTabManager::new();
+        let first = manager.create_tab().id;
+        let suspended = manager.create_tab().id;
+        let third = manager.create_tab().id;
+        let active = manager.create_tab().id;
+        manager.set_state(suspended, TabState::Suspended);
+
+        let ids = |tabs: Vec<&TabEntry>| -> Vec<TabId> { tabs.iter().map(|tab| tab.id).collect() };
+        assert_eq!(ids(manager.active_tabs().collect()), vec![active]);
+        assert_eq!(ids(manager.background_tabs().collect()), vec![first, third]);
+        assert_eq!(ids(manager.suspended_tabs().collect()), vec![suspended]);
+        assert_eq!(manager.tabs_in_state(TabState::Frozen).count(), 0);
+    }
+
+    #[test]
+    fn create_tab_keeps_suspended_tabs_suspended() {
+        let mut manager = BasicTabManager::new();
+        let suspended = manager.create_tab().id;
+        let previous = manager.create_tab().id;
+        manager.set_state(suspended, TabState::Suspended);
+
+        let created = manager.create_tab().id;
+
+        assert_eq!(manager.state_of(suspended), Some(TabState::Suspended));
+        assert_eq!(manager.state_of(previous), Some(TabState::Background));
+        assert_eq!(manager.state_of(created), Some(TabState::Active));
+    }
+
+    #[test]
+    fn set_active_backgrounds_every_other_live_tab() {
+        let mut manager = BasicTabManager::new();
+        let frozen = manager.create_tab().id;
+        let suspended = manager.create_tab().id;
+        let target = manager.create_tab().id;
+        manager.set_state(frozen, TabState::Frozen);
+        manager.set_state(suspended, TabState::Suspended);
+
+        assert!(manager.set_active(target));
+
+        assert_eq!(manager.state_of(frozen), Some(TabState::Background));
+        assert_eq!(manager.state_of(suspended), Some(TabState::Suspended));
+        assert_eq!(manager.state_of(target), Some(TabState::Active));
+    }
+}
@@ -0,0 +1,97 @@
+/// Follows the redirects of one main-frame load to decide which url a tab
+/// should remember.
+///
+/// Permanent redirects (301, 308) move the tab to their target so a restored
+/// session skips the hop. Once a temporary redirect is seen, later hops are
+/// ignored: the tab keeps the last permanent location and the temporary
+/// chain is followed afresh on every load.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectChain {
+    tab: u64,
+    /// Last url reached through permanent redirects only.
+    canonical: String,
+    temporary: bool,
+}
+
+impl RedirectChain {
+    /// Starts tracking a load of `requested` for `tab`.
+    pub fn new(tab: u64, requested: &str) -> Self {
+        Self {
+            tab,
+            canonical: requested.to_string(),
+            temporary: false,
+        }
+    }
+
+    /// Records a redirect with HTTP `status` that continued to `target`.
+    pub fn record_hop(&mut self, status: u32, target: &str) {
+        if self.temporary {
+            return;
+        }
+        if is_permanent_redirect(status) {
+            self.canonical = target.to_string();
+        } else {
+            self.temporary = true;
+        }
+    }
+
+    /// Returns the url `tab` should store once the load finished at
+    /// `final_url`, or `None` if the load belongs to another tab.
+    pub fn stored_url<'a>(&'a self, tab: u64, final_url: &'a str) -> Option<&'a str> {
+        if tab != self.tab {
+            None
+        } else if self.temporary {
+            Some(&self.canonical)
+        } else {
+            Some(final_url)
+        }
+    }
+}
+
+fn is_permanent_redirect(status: u32) -> bool {
+    matches!(status, 301 | 308)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUESTED: &str = "http://example.com/";
+
+    #[test]
+    fn permanent_redirects_move_the_tab() {
+        let mut chain = RedirectChain::new(1, REQUESTED);
+        chain.record_hop(301, "https://example.com/");
+        chain.record_hop(308, "https://www.example.com/");
+        assert_eq!(
+            chain.stored_url(1, "https://www.example.com/"),
+            Some("https://www.example.com/")
+        );
+    }
+
+    #[test]
+    fn temporary_redirects_keep_the_last_permanent_url() {
+        let mut chain = RedirectChain::new(1, REQUESTED);
+        chain.record_hop(302, "https://example.com/login");
+        assert_eq!(
+            chain.stored_url(1, "https://example.com/login"),
+            Some(REQUESTED)
+        );
+
+        let mut chain = RedirectChain::new(1, REQUESTED);
+        chain.record_hop(301, "https://example.com/");
+        chain.record_hop(307, "https://example.com/maintenance");
+        chain.record_hop(301, "https://status.example.com/");
+        assert_eq!(
+            chain.stored_url(1, "https://status.example.com/"),
+            Some("https://example.com/")
+        );
+    }
+
+    #[test]
+    fn loads_are_attributed_to_their_tab() {
+        let mut chain = RedirectChain::new(1, REQUESTED);
+        chain.record_hop(301, "https://example.com/");
+        assert_eq!(chain.stored_url(2, "https://example.com/"), None);
+    }
+}
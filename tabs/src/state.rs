@@ -0,0 +1,896 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use url::Url;
+use util::metrics::{self, Counter};
+
+/// Zoom levels, in percent, that zooming in and out steps through.
+const ZOOM_LEVELS: [u16; 13] = [30, 50, 67, 80, 90, 100, 110, 125, 150, 175, 200, 250, 300];
+const DEFAULT_ZOOM: u16 = 100;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TabNode {
+    pub id: u64,
+    pub parent: Option<u64>,
+    pub title: String,
+    pub url: String,
+    pub favicon_uri: Option<String>,
+    pub children: Vec<u64>,
+    pub is_expanded: bool,
+    pub is_pinned: bool,
+    pub is_muted: bool,
+    pub is_suspended: bool,
+    pub is_group: bool,
+    /// Whether the tab is currently playing audio.
+    pub is_audible: bool,
+    /// Private tabs load in an ephemeral network session and are never
+    /// written to history or the session store.
+    pub is_private: bool,
+    /// Reader mode is on and re-applies as the tab navigates.
+    pub is_reader: bool,
+    /// Page zoom in percent, kept as the tab navigates.
+    pub zoom_percent: u16,
+    /// Value of the activation counter when the tab was last created or
+    /// selected; lower means less recently used.
+    pub last_active: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrowserState {
+    next_id: u64,
+    activations: u64,
+    pub tabs: HashMap<u64, TabNode>,
+    pub roots: Vec<u64>,
+    pub active: Option<u64>,
+    pub recently_closed: Vec<ClosedTab>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UiTabNode {
+    pub id: u64,
+    pub title: String,
+    pub url: String,
+    pub favicon_uri: Option<String>,
+    pub is_expanded: bool,
+    pub is_active: bool,
+    pub is_pinned: bool,
+    pub is_muted: bool,
+    pub is_suspended: bool,
+    pub is_group: bool,
+    pub is_private: bool,
+    pub children: Vec<UiTabNode>,
+}
+
+/// Controls which fields [`BrowserState::structural_eq_with`] compares.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StructuralCompare {
+    /// Compare `favicon_uri`, which is filled in asynchronously as pages load.
+    pub favicons: bool,
+    /// Compare `last_active`, which moves whenever a tab is selected.
+    pub timestamps: bool,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedTab {
+    pub title: String,
+    pub url: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionState {
+    pub name: String,
+    pub tabs: Vec<SessionTab>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SessionTab {
+    pub title: String,
+    pub url: String,
+    pub is_pinned: bool,
+}
+
+impl Default for BrowserState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BrowserState {
+    pub fn new() -> Self {
+        let mut state = Self {
+            next_id: 1,
+            activations: 0,
+            tabs: HashMap::new(),
+            roots: Vec::new(),
+            active: None,
+            recently_closed: Vec::new(),
+        };
+
+        let _home_id = state.create_tab(None, "Home", "owl://home");
+        let group_id = state.create_group("Reading");
+        if let Some(group) = state.tabs.get_mut(&group_id) {
+            group.is_expanded = true;
+        }
+
+        let webkit_id = state.create_tab(Some(group_id), "WebKitGTK", "https://webkitgtk.org");
+        state.create_tab(Some(group_id), "GNOME", "https://www.gnome.org");
+        state.create_tab(Some(group_id), "Fedora", "https://fedoraproject.org");
+
+        state.set_active(webkit_id);
+        state
+    }
+
+    pub fn create_tab(&mut self, parent: Option<u64>, title: &str, url: &str) -> u64 {
+        self.create_tab_internal(parent, title, url, false)
+    }
+
+    /// Creates a top-level private tab.
+    pub fn create_private_tab(&mut self, title: &str, url: &str) -> u64 {
+        let id = self.create_tab(None, title, url);
+        if let Some(node) = self.tabs.get_mut(&id) {
+            node.is_private = true;
+        }
+        id
+    }
+
+    pub fn create_group(&mut self, title: &str) -> u64 {
+        self.create_tab_internal(None, title, "owl://group", true)
+    }
+
+    fn create_tab_internal(
+        &mut self,
+        parent: Option<u64>,
+        title: &str,
+        url: &str,
+        is_group: bool,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.activations += 1;
+        let node = TabNode {
+            id,
+            parent,
+            title: title.to_string(),
+            url: url.to_string(),
+            favicon_uri: None,
+            children: Vec::new(),
+            is_expanded: true,
+            is_pinned: false,
+            is_muted: false,
+            is_suspended: false,
+            is_group,
+            is_audible: false,
+            is_private: false,
+            is_reader: false,
+            zoom_percent: DEFAULT_ZOOM,
+            last_active: self.activations,
+        };
+        self.tabs.insert(id, node);
+        if !is_group {
+            metrics::increment(Counter::TabsCreated);
+        }
+
+        if let Some(parent_id) = parent {
+            if let Some(parent_node) = self.tabs.get_mut(&parent_id) {
+                parent_node.children.push(id);
+            }
+        } else {
+            self.roots.push(id);
+        }
+
+        id
+    }
+
+    /// Adds `session` as a new top-level group and returns the group id, or
+    /// `None` if the session has no tabs.
+    pub fn add_session(&mut self, session: &SessionState) -> Option<u64> {
+        if session.tabs.is_empty() {
+            return None;
+        }
+        let group_id = self.create_group(&session.name);
+        for tab in &session.tabs {
+            let id = self.create_tab(Some(group_id), &tab.title, &tab.url);
+            if tab.is_pinned {
+                self.toggle_pin(id);
+            }
+        }
+        Some(group_id)
+    }
+
+    /// Copies the tab, or a group with its whole subtree, into a sibling
+    /// placed right after the original, and returns the copy's id.
+    ///
+    /// Copies keep title, url, pinned and private state; favicons and
+    /// navigation history start fresh, and every copied node gets a new id.
+    pub fn duplicate_tab(&mut self, id: u64) -> Option<u64> {
+        let parent = self.tabs.get(&id)?.parent;
+        let copy = self.copy_subtree(id, parent)?;
+
+        // The copy was appended to its siblings; move it next to the original.
+        let siblings = match parent {
+            Some(parent_id) => &mut self.tabs.get_mut(&parent_id)?.children,
+            None => &mut self.roots,
+        };
+        siblings.retain(|sibling| *sibling != copy);
+        let position = siblings
+            .iter()
+            .position(|sibling| *sibling == id)
+            .map_or(siblings.len(), |index| index + 1);
+        siblings.insert(position, copy);
+        Some(copy)
+    }
+
+    fn copy_subtree(&mut self, id: u64, parent: Option<u64>) -> Option<u64> {
+        let source = self.tabs.get(&id)?.clone();
+        let copy = self.create_tab_internal(parent, &source.title, &source.url, source.is_group);
+        if let Some(node) = self.tabs.get_mut(&copy) {
+            node.is_expanded = source.is_expanded;
+            node.is_pinned = source.is_pinned;
+            node.is_private = source.is_private;
+        }
+        for child in source.children {
+            self.copy_subtree(child, Some(copy));
+        }
+        Some(copy)
+    }
+
+    pub fn remove_tab(&mut self, id: u64) {
+        if let Some(node) = self.tabs.get(&id).cloned() {
+            for child in node.children.clone() {
+                self.remove_tab(child);
+            }
+
+            if let Some(parent_id) = node.parent {
+                if let Some(parent_node) = self.tabs.get_mut(&parent_id) {
+                    parent_node.children.retain(|child_id| *child_id != id);
+                }
+            } else {
+                self.roots.retain(|root_id| *root_id != id);
+            }
+
+            self.tabs.remove(&id);
+            if !node.is_group {
+                metrics::increment(Counter::TabsClosed);
+            }
+            if !node.is_private {
+                self.recently_closed.push(ClosedTab {
+                    title: node.title,
+                    url: node.url,
+                });
+            }
+            if self.active == Some(id) {
+                self.active = self.roots.first().copied();
+            }
+        }
+    }
+
+    pub fn has_private_tabs(&self) -> bool {
+        self.tabs.values().any(|node| node.is_private)
+    }
+
+    /// Whether the active tab is private.
+    pub fn active_is_private(&self) -> bool {
+        self.active
+            .and_then(|id| self.tabs.get(&id))
+            .is_some_and(|node| node.is_private)
+    }
+
+    pub fn set_active(&mut self, id: u64) {
+        if let Some(node) = self.tabs.get_mut(&id) {
+            self.activations += 1;
+            node.last_active = self.activations;
+            self.active = Some(id);
+        }
+    }
+
+    /// Suspends the least recently used tabs until at most `cap` navigable
+    /// tabs are live, returning the suspended ids.
+    ///
+    /// The active tab and pinned or audible tabs are never suspended, so more
+    /// than `cap` tabs can stay live when too many are exempt.
+    pub fn suspend_over_cap(&mut self, cap: usize) -> Vec<u64> {
+        let live: Vec<&TabNode> = self
+            .tabs
+            .values()
+            .filter(|node| !node.is_group && !node.is_suspended)
+            .collect();
+        if live.len() <= cap {
+            return Vec::new();
+        }
+        let excess = live.len() - cap;
+
+        let mut candidates: Vec<&TabNode> = live
+            .into_iter()
+            .filter(|node| self.active != Some(node.id) && !node.is_pinned && !node.is_audible)
+            .collect();
+        candidates.sort_by_key(|node| node.last_active);
+        let victims: Vec<u64> = candidates
+            .into_iter()
+            .take(excess)
+            .map(|node| node.id)
+            .collect();
+
+        for id in &victims {
+            if let Some(node) = self.tabs.get_mut(id) {
+                node.is_suspended = true;
+            }
+        }
+        victims
+    }
+
+    /// Suspends every navigable tab except the active one and pinned or
+    /// audible tabs, returning the ids that changed in ascending order.
+    pub fn suspend_all_background(&mut self) -> Vec<u64> {
+        let active = self.active;
+        let mut changed: Vec<u64> = self
+            .tabs
+            .values_mut()
+            .filter(|node| {
+                !node.is_group
+                    && !node.is_suspended
+                    && active != Some(node.id)
+                    && !node.is_pinned
+                    && !node.is_audible
+            })
+            .map(|node| {
+                node.is_suspended = true;
+                node.id
+            })
+            .collect();
+        changed.sort_unstable();
+        changed
+    }
+
+    /// Wakes every suspended tab, returning the ids that changed in
+    /// ascending order.
+    pub fn wake_all(&mut self) -> Vec<u64> {
+        let mut changed: Vec<u64> = self
+            .tabs
+            .values_mut()
+            .filter(|node| node.is_suspended)
+            .map(|node| {
+                node.is_suspended = false;
+                node.id
+            })
+            .collect();
+        changed.sort_unstable();
+        changed
+    }
+
+    /// Moves the zoom of tab `id` `step` levels up or down the preset
+    /// levels, stopping at either end; a `step` of 0 resets it. Returns the
+    /// new zoom, or `None` for groups and unknown tabs.
+    pub fn step_zoom(&mut self, id: u64, step: i32) -> Option<u16> {
+        let node = self.tabs.get_mut(&id).filter(|node| !node.is_group)?;
+        node.zoom_percent = if step == 0 {
+            DEFAULT_ZOOM
+        } else {
+            // Zoom between presets snaps to the nearest one in the direction
+            // of the step.
+            let current = node.zoom_percent;
+            let below = ZOOM_LEVELS.partition_point(|level| *level < current);
+            let above = ZOOM_LEVELS.partition_point(|level| *level <= current);
+            // The step comes from the UI, so it may be anything.
+            let last = ZOOM_LEVELS.len() - 1;
+            let distance = step.unsigned_abs() as usize;
+            let index = if step > 0 {
+                above.saturating_add(distance - 1).min(last)
+            } else {
+                below.saturating_sub(distance)
+            };
+            ZOOM_LEVELS[index]
+        };
+        Some(node.zoom_percent)
+    }
+
+    /// Expands or collapses every node that has children, returning whether
+    /// any node changed.
+    pub fn set_all_expanded(&mut self, expanded: bool) -> bool {
+        let mut changed = false;
+        for node in self.tabs.values_mut() {
+            if !node.children.is_empty() && node.is_expanded != expanded {
+                node.is_expanded = expanded;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    pub fn toggle_expanded(&mut self, id: u64) {
+        if let Some(node) = self.tabs.get_mut(&id) {
+            if !node.children.is_empty() {
+                node.is_expanded = !node.is_expanded;
+            }
+        }
+    }
+
+    pub fn update_tab(&mut self, id: u64, title: Option<&str>, url: Option<&str>) {
+        if let Some(node) = self.tabs.get_mut(&id) {
+            if let Some(title) = title {
+                node.title = title.to_string();
+            }
+            if let Some(url) = url {
+                node.url = url.to_string();
+                node.favicon_uri = None;
+            }
+        }
+    }
+
+    pub fn toggle_pin(&mut self, id: u64) {
+        if let Some(node) = self.tabs.get_mut(&id) {
+            node.is_pinned = !node.is_pinned;
+        }
+    }
+
+    /// Mutes or unmutes `id` and every tab below it, returning the ids whose
+    /// state changed.
+    pub fn set_muted_recursive(&mut self, id: u64, muted: bool) -> Vec<u64> {
+        self.set_recursive(id, |node| &mut node.is_muted, muted)
+    }
+
+    /// Suspends or resumes `id` and every tab below it, returning the ids
+    /// whose state changed.
+    pub fn set_suspended_recursive(&mut self, id: u64, suspended: bool) -> Vec<u64> {
+        self.set_recursive(id, |node| &mut node.is_suspended, suspended)
+    }
+
+    fn set_recursive(
+        &mut self,
+        id: u64,
+        flag: impl Fn(&mut TabNode) -> &mut bool,
+        value: bool,
+    ) -> Vec<u64> {
+        let mut changed = Vec::new();
+        let mut pending = vec![id];
+        while let Some(current) = pending.pop() {
+            let Some(node) = self.tabs.get_mut(&current) else {
+                continue;
+            };
+            let slot = flag(node);
+            if *slot != value {
+                *slot = value;
+                changed.push(current);
+            }
+            pending.extend(node.children.iter().rev());
+        }
+        changed
+    }
+
+    pub fn set_favicon_for_url(&mut self, url: &str, favicon_uri: Option<String>) -> Vec<u64> {
+        let mut updated = Vec::new();
+        for (id, node) in self.tabs.iter_mut() {
+            if node.url == url {
+                node.favicon_uri = favicon_uri.clone();
+                updated.push(*id);
+            }
+        }
+        updated
+    }
+
+    /// Returns the ids of all nodes (groups included) matching `predicate`,
+    /// in sidebar order.
+    pub fn tabs_by_predicate(&self, mut predicate: impl FnMut(&TabNode) -> bool) -> Vec<u64> {
+        fn visit(
+            state: &BrowserState,
+            ids: &[u64],
+            predicate: &mut dyn FnMut(&TabNode) -> bool,
+            matches: &mut Vec<u64>,
+        ) {
+            for id in state.ordered_children(ids) {
+                let node = &state.tabs[&id];
+                if predicate(node) {
+                    matches.push(id);
+                }
+                visit(state, &node.children, predicate, matches);
+            }
+        }
+
+        let mut matches = Vec::new();
+        visit(self, &self.roots, &mut predicate, &mut matches);
+        matches
+    }
+
+    /// Returns the ids of nodes whose title or url contains `query`,
+    /// ignoring case. Groups only match by title, since their url is a
+    /// placeholder. An empty query matches every node.
+    pub fn search(&self, query: &str) -> Vec<u64> {
+        let query = query.trim().to_lowercase();
+        self.tabs_by_predicate(|node| {
+            query.is_empty()
+                || node.title.to_lowercase().contains(&query)
+                || (!node.is_group && node.url.to_lowercase().contains(&query))
+        })
+    }
+
+    /// Groups tabs that show the same page, in sidebar order of their first
+    /// member. Each group lists its keeper first: the first pinned tab, or
+    /// else the first tab. Private tabs are only compared with each other.
+    pub fn find_duplicates(&self) -> Vec<Vec<u64>> {
+        let mut groups: Vec<Vec<u64>> = Vec::new();
+        let mut by_key: HashMap<(bool, String), usize> = HashMap::new();
+        for id in self.tabs_by_predicate(|node| !node.is_group) {
+            let node = &self.tabs[&id];
+            let key = (node.is_private, duplicate_key(&node.url));
+            match by_key.get(&key) {
+                Some(&index) => groups[index].push(id),
+                None => {
+                    by_key.insert(key, groups.len());
+                    groups.push(vec![id]);
+                }
+            }
+        }
+
+        groups.retain(|group| group.len() > 1);
+        for group in &mut groups {
+            if let Some(pinned) = group.iter().position(|id| self.tabs[id].is_pinned) {
+                let keeper = group.remove(pinned);
+                group.insert(0, keeper);
+            }
+        }
+        groups
+    }
+
+    fn ordered_children(&self, ids: &[u64]) -> Vec<u64> {
+        let mut pinned = Vec::new();
+        let mut normal = Vec::new();
+        for id in ids {
+            if let Some(node) = self.tabs.get(id) {
+                if node.is_pinned {
+                    pinned.push(*id);
+                } else {
+                    normal.push(*id);
+                }
+            }
+        }
+        pinned.extend(normal);
+        pinned
+    }
+
+    /// Compares the tab trees, ignoring the id allocator, favicons and
+    /// activation timestamps.
+    pub fn structural_eq(&self, other: &BrowserState) -> bool {
+        self.structural_eq_with(other, StructuralCompare::default())
+    }
+
+    /// Compares the tab trees, ignoring the id allocator and any fields
+    /// `compare` leaves out. Whether a tab is audible is never compared.
+    pub fn structural_eq_with(&self, other: &BrowserState, compare: StructuralCompare) -> bool {
+        let node_eq = |a: &TabNode, b: &TabNode| {
+            a.id == b.id
+                && a.parent == b.parent
+                && a.title == b.title
+                && a.url == b.url
+                && a.children == b.children
+                && a.is_expanded == b.is_expanded
+                && a.is_pinned == b.is_pinned
+                && a.is_muted == b.is_muted
+                && a.is_suspended == b.is_suspended
+                && a.is_group == b.is_group
+                && a.is_private == b.is_private
+                && a.is_reader == b.is_reader
+                && a.zoom_percent == b.zoom_percent
+                && (!compare.favicons || a.favicon_uri == b.favicon_uri)
+                && (!compare.timestamps || a.last_active == b.last_active)
+        };
+
+        self.roots == other.roots
+            && self.active == other.active
+            && self.recently_closed == other.recently_closed
+            && self.tabs.len() == other.tabs.len()
+            && self.tabs.iter().all(|(id, node)| {
+                other
+                    .tabs
+                    .get(id)
+                    .is_some_and(|other_node| node_eq(node, other_node))
+            })
+    }
+
+    pub fn to_ui_tree(&self) -> Vec<UiTabNode> {
+        fn build_node(state: &BrowserState, id: u64) -> UiTabNode {
+            let node = state.tabs.get(&id).expect("tab node exists");
+            let children = state
+                .ordered_children(&node.children)
+                .iter()
+                .map(|child_id| build_node(state, *child_id))
+                .collect();
+
+            UiTabNode {
+                id: node.id,
+                title: node.title.clone(),
+                url: node.url.clone(),
+                favicon_uri: node.favicon_uri.clone(),
+                is_expanded: node.is_expanded,
+                is_active: state.active == Some(node.id),
+                is_pinned: node.is_pinned,
+                is_muted: node.is_muted,
+                is_suspended: node.is_suspended,
+                is_group: node.is_group,
+                is_private: node.is_private,
+                children,
+            }
+        }
+
+        self.ordered_children(&self.roots)
+            .iter()
+            .map(|id| build_node(self, *id))
+            .collect()
+    }
+}
+
+/// Normalizes `url` for duplicate detection the way the address bar does
+/// for urls with a scheme, then drops the fragment and a trailing slash.
+/// The query string is kept, since it usually selects different content.
+fn duplicate_key(url: &str) -> String {
+    let trimmed = url.trim();
+    let Ok(mut parsed) = Url::parse(trimmed) else {
+        return trimmed.trim_end_matches('/').to_string();
+    };
+    parsed.set_fragment(None);
+    let mut key = parsed.to_string();
+    if parsed.query().is_none() && key.ends_with('/') {
+        key.pop();
+    }
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state_with_tabs(count: usize) -> (BrowserState, Vec<u64>) {
+        let mut state = BrowserState::new();
+        let existing: Vec<u64> = state.tabs.keys().copied().collect();
+        for id in existing {
+            state.remove_tab(id);
+        }
+        let ids = (0..count)
+            .map(|index| state.create_tab(None, "Tab", &format!("https://example.com/{index}")))
+            .collect();
+        (state, ids)
+    }
+
+    #[test]
+    fn duplicates_groups_with_their_subtree_after_the_original() {
+        let (mut state, ids) = state_with_tabs(2);
+        let group = state.create_group("Research");
+        let child = state.create_tab(Some(group), "Paper", "https://example.com/paper");
+        state.toggle_pin(child);
+        state.set_favicon_for_url("https://example.com/paper", Some("data:icon".to_string()));
+        // Keep the group between the two tabs.
+        state.roots = vec![ids[0], group, ids[1]];
+
+        let copy = state.duplicate_tab(group).expect("group exists");
+
+        assert_eq!(state.roots, vec![ids[0], group, copy, ids[1]]);
+        let copied_children = state.tabs[&copy].children.clone();
+        assert_eq!(copied_children.len(), 1);
+        let copied_child = &state.tabs[&copied_children[0]];
+        assert_ne!(copied_child.id, child);
+        assert_eq!(copied_child.parent, Some(copy));
+        assert_eq!(copied_child.url, "https://example.com/paper");
+        assert!(copied_child.is_pinned);
+        assert_eq!(copied_child.favicon_uri, None);
+        assert_eq!(state.tabs[&group].children, vec![child]);
+
+        assert_eq!(state.duplicate_tab(9999), None);
+    }
+
+    #[test]
+    fn set_all_expanded_only_touches_nodes_with_children() {
+        let (mut state, ids) = state_with_tabs(1);
+        let group = state.create_group("Research");
+        let empty_group = state.create_group("Empty");
+        let parent = state.create_tab(Some(group), "Parent", "https://example.com/a");
+        state.create_tab(Some(parent), "Child", "https://example.com/b");
+
+        assert!(state.set_all_expanded(false));
+        assert!(!state.tabs[&group].is_expanded);
+        assert!(!state.tabs[&parent].is_expanded);
+        assert!(state.tabs[&empty_group].is_expanded);
+        assert!(state.tabs[&ids[0]].is_expanded);
+        assert!(!state.set_all_expanded(false));
+
+        assert!(state.set_all_expanded(true));
+        assert!(state.tabs[&group].is_expanded && state.tabs[&parent].is_expanded);
+    }
+
+    #[test]
+    fn group_mute_cascades_to_descendants() {
+        let (mut state, ids) = state_with_tabs(1);
+        let group = state.create_group("Research");
+        let first = state.create_tab(Some(group), "A", "https://example.com/a");
+        let nested = state.create_tab(Some(first), "B", "https://example.com/b");
+        let muted = state.create_tab(Some(group), "C", "https://example.com/c");
+        state.set_muted_recursive(muted, true);
+
+        assert_eq!(state.set_muted_recursive(group, true), vec![group, first, nested]);
+        assert!(!state.tabs[&ids[0]].is_muted);
+        assert_eq!(
+            state.set_muted_recursive(group, false),
+            vec![group, first, nested, muted]
+        );
+
+        assert_eq!(state.set_suspended_recursive(first, true), vec![first, nested]);
+        assert!(state.set_suspended_recursive(9999, true).is_empty());
+    }
+
+    #[test]
+    fn suspends_least_recently_used_tabs_over_cap() {
+        let (mut state, ids) = state_with_tabs(5);
+        // Selection order, oldest first: 2, 0, 4, 1, 3.
+        for index in [2, 0, 4, 1, 3] {
+            state.set_active(ids[index]);
+        }
+
+        assert_eq!(state.suspend_over_cap(3), vec![ids[2], ids[0]]);
+        assert!(state.tabs[&ids[2]].is_suspended);
+        assert!(state.suspend_over_cap(3).is_empty());
+    }
+
+    #[test]
+    fn cap_skips_active_pinned_and_audible_tabs() {
+        let (mut state, ids) = state_with_tabs(5);
+        for id in &ids {
+            state.set_active(*id);
+        }
+        state.set_active(ids[0]);
+        state.toggle_pin(ids[1]);
+        state.tabs.get_mut(&ids[2]).unwrap().is_audible = true;
+
+        assert_eq!(state.suspend_over_cap(1), vec![ids[3], ids[4]]);
+        assert!(!state.tabs[&ids[0]].is_suspended);
+    }
+
+    #[test]
+    fn suspend_all_spares_active_pinned_and_audible_tabs() {
+        let (mut state, ids) = state_with_tabs(4);
+        state.set_active(ids[0]);
+        state.toggle_pin(ids[1]);
+        state.tabs.get_mut(&ids[2]).unwrap().is_audible = true;
+
+        assert_eq!(state.suspend_all_background(), vec![ids[3]]);
+        assert!(state.suspend_all_background().is_empty());
+        assert_eq!(state.wake_all(), vec![ids[3]]);
+        assert!(!state.tabs[&ids[3]].is_suspended);
+    }
+
+    #[test]
+    fn search_matches_groups_and_leaves() {
+        let (mut state, ids) = state_with_tabs(2);
+        let group = state.create_group("Example docs");
+        let child = state.create_tab(Some(group), "Rust book", "https://doc.rust-lang.org/book/");
+
+        assert_eq!(state.search("EXAMPLE"), vec![ids[0], ids[1], group]);
+        assert_eq!(state.search("rust"), vec![child]);
+        assert!(state.search("group").is_empty());
+        assert!(state.search("gnome").is_empty());
+    }
+
+    #[test]
+    fn empty_search_returns_every_node() {
+        let state = BrowserState::new();
+        let mut all = state.search("  ");
+        all.sort_unstable();
+        let mut expected: Vec<u64> = state.tabs.keys().copied().collect();
+        expected.sort_unstable();
+        assert_eq!(all, expected);
+    }
+
+    #[test]
+    fn duplicates_ignore_fragment_and_trailing_slash() {
+        let (mut state, _) = state_with_tabs(0);
+        let first = state.create_tab(None, "Docs", "https://Example.com/docs/");
+        let second = state.create_tab(None, "Docs", "https://example.com/docs#install");
+        let pinned = state.create_tab(None, "Docs", "https://example.com/docs");
+        state.toggle_pin(pinned);
+        state.create_tab(None, "Other", "https://example.com/other");
+
+        assert_eq!(state.find_duplicates(), vec![vec![pinned, first, second]]);
+    }
+
+    #[test]
+    fn duplicates_keep_query_strings_distinct() {
+        let (mut state, _) = state_with_tabs(0);
+        let first = state.create_tab(None, "A", "https://example.com/search?q=owl");
+        state.create_tab(None, "B", "https://example.com/search?q=gtk");
+        let same = state.create_tab(None, "C", "https://example.com/search?q=owl#top");
+
+        assert_eq!(state.find_duplicates(), vec![vec![first, same]]);
+    }
+
+    #[test]
+    fn private_tabs_leave_no_closed_tab_record() {
+        let (mut state, _) = state_with_tabs(1);
+        let private = state.create_private_tab("Private Tab", "https://example.org");
+        state.set_active(private);
+        assert!(state.active_is_private());
+
+        let closed = state.recently_closed.len();
+        state.remove_tab(private);
+        assert!(!state.has_private_tabs());
+        assert_eq!(state.recently_closed.len(), closed);
+    }
+
+    #[test]
+    fn zoom_steps_through_presets_and_stops_at_the_ends() {
+        let (mut state, ids) = state_with_tabs(1);
+        let tab = ids[0];
+        assert_eq!(state.step_zoom(tab, 1), Some(110));
+        assert_eq!(state.step_zoom(tab, -2), Some(90));
+        assert_eq!(state.step_zoom(tab, 20), Some(300));
+        assert_eq!(state.step_zoom(tab, 1), Some(300));
+        assert_eq!(state.step_zoom(tab, 0), Some(100));
+        assert_eq!(state.step_zoom(tab, i32::MAX), Some(300));
+        assert_eq!(state.step_zoom(tab, i32::MIN), Some(30));
+        assert_eq!(state.step_zoom(tab, i32::MIN), Some(30));
+
+        state.tabs.get_mut(&tab).unwrap().zoom_percent = 115;
+        assert_eq!(state.step_zoom(tab, 1), Some(125));
+        state.tabs.get_mut(&tab).unwrap().zoom_percent = 115;
+        assert_eq!(state.step_zoom(tab, -1), Some(110));
+
+        let group = state.create_group("Group");
+        assert_eq!(state.step_zoom(group, 1), None);
+        assert_eq!(state.step_zoom(999, 1), None);
+    }
+
+    #[test]
+    fn structural_eq_ignores_allocator_favicons_and_timestamps() {
+        let (mut before, ids) = state_with_tabs(2);
+        before.set_active(ids[0]);
+        let mut after = before.clone();
+        let private = after.create_private_tab("Private", "https://example.com/private");
+        after.remove_tab(private);
+        after.set_favicon_for_url("https://example.com/0", Some("owl://favicon/0".to_string()));
+        after.set_active(ids[1]);
+        after.set_active(ids[0]);
+
+        assert!(before.structural_eq(&after));
+        assert!(!before.structural_eq_with(
+            &after,
+            StructuralCompare {
+                favicons: true,
+                ..StructuralCompare::default()
+            }
+        ));
+        assert!(!before.structural_eq_with(
+            &after,
+            StructuralCompare {
+                timestamps: true,
+                ..StructuralCompare::default()
+            }
+        ));
+    }
+
+    #[test]
+    fn structural_eq_detects_tree_changes() {
+        let (before, ids) = state_with_tabs(2);
+
+        let mut renamed = before.clone();
+        renamed.update_tab(ids[0], Some("Renamed"), None);
+        assert!(!before.structural_eq(&renamed));
+
+        let mut pinned = before.clone();
+        pinned.toggle_pin(ids[1]);
+        assert!(!before.structural_eq(&pinned));
+
+        let mut nested = before.clone();
+        nested.create_tab(Some(ids[0]), "Child", "https://example.com/child");
+        assert!(!before.structural_eq(&nested));
+
+        let mut closed = before.clone();
+        closed.remove_tab(ids[1]);
+        assert!(!before.structural_eq(&closed));
+    }
+
+    #[test]
+    fn structural_eq_compares_zoom() {
+        let (before, ids) = state_with_tabs(1);
+        let mut zoomed = before.clone();
+        zoomed.step_zoom(ids[0], 1);
+        assert!(!before.structural_eq(&zoomed));
+
+        zoomed.step_zoom(ids[0], -1);
+        assert!(before.structural_eq(&zoomed));
+    }
+}
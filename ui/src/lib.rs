@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+pub mod suggest;
+
 /// Message exchanged over the UI IPC bridge.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UiMessage {
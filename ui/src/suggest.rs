@@ -0,0 +1,212 @@
+use serde::Serialize;
+use std::collections::HashSet;
+use url::Url;
+
+/// Maximum number of suggestions returned for one query.
+pub const SUGGESTION_LIMIT: usize = 8;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    Tab,
+    History,
+    Session,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub title: String,
+    pub url: String,
+    /// `url` without its scheme, for display.
+    pub display_url: String,
+    pub kind: SuggestionKind,
+}
+
+/// How well a candidate matches; lower ranks first.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+enum MatchRank {
+    HostPrefix,
+    Substring,
+}
+
+/// Ranks `candidates` against `query`.
+///
+/// Host prefix matches (ignoring a leading `www.`) come before substring
+/// matches on the title or url; within a rank the candidate order is kept,
+/// so callers pass their sources in order of preference. Matching is
+/// case-insensitive, and a url seen earlier hides later duplicates.
+pub fn rank(
+    query: &str,
+    candidates: impl IntoIterator<Item = (String, String, SuggestionKind)>,
+    limit: usize,
+) -> Vec<Suggestion> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    let mut seen = HashSet::new();
+    let mut ranked: Vec<(MatchRank, Suggestion)> = candidates
+        .into_iter()
+        .filter_map(|(title, url, kind)| {
+            let rank = match_rank(&query, &title, &url)?;
+            let display_url = strip_scheme(&url).to_string();
+            Some((
+                rank,
+                Suggestion {
+                    title,
+                    url,
+                    display_url,
+                    kind,
+                },
+            ))
+        })
+        .filter(|(_, suggestion)| seen.insert(suggestion.url.clone()))
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+    ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, suggestion)| suggestion)
+        .collect()
+}
+
+fn match_rank(query: &str, title: &str, url: &str) -> Option<MatchRank> {
+    let host = Url::parse(url)
+        .ok()
+        .and_then(|parsed| parsed.host_str().map(str::to_lowercase));
+    if let Some(host) = host {
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        if host.starts_with(query) {
+            return Some(MatchRank::HostPrefix);
+        }
+    }
+
+    if title.to_lowercase().contains(query) || strip_scheme(url).to_lowercase().contains(query) {
+        Some(MatchRank::Substring)
+    } else {
+        None
+    }
+}
+
+fn strip_scheme(url: &str) -> &str {
+    match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(title: &str, url: &str, kind: SuggestionKind) -> (String, String, SuggestionKind) {
+        (title.to_string(), url.to_string(), kind)
+    }
+
+    fn urls(suggestions: &[Suggestion]) -> Vec<&str> {
+        suggestions
+            .iter()
+            .map(|suggestion| suggestion.url.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn host_prefix_matches_rank_before_substring_matches() {
+        let suggestions = rank(
+            "Git",
+            vec![
+                candidate(
+                    "Legit news",
+                    "https://news.example.com/",
+                    SuggestionKind::Tab,
+                ),
+                candidate(
+                    "Forge",
+                    "https://www.GitHub.com/owl",
+                    SuggestionKind::History,
+                ),
+                candidate(
+                    "Docs",
+                    "https://docs.example.com/git",
+                    SuggestionKind::History,
+                ),
+                candidate("Lab", "https://gitlab.com/", SuggestionKind::Session),
+                candidate("Unrelated", "https://example.org/", SuggestionKind::Tab),
+            ],
+            SUGGESTION_LIMIT,
+        );
+
+        assert_eq!(
+            urls(&suggestions),
+            [
+                "https://www.GitHub.com/owl",
+                "https://gitlab.com/",
+                "https://news.example.com/",
+                "https://docs.example.com/git",
+            ]
+        );
+    }
+
+    #[test]
+    fn earlier_candidates_hide_later_duplicates() {
+        let suggestions = rank(
+            "example",
+            vec![
+                candidate("Open tab", "https://example.com/", SuggestionKind::Tab),
+                candidate("Visited", "https://example.com/", SuggestionKind::History),
+                candidate(
+                    "Other",
+                    "https://example.com/other",
+                    SuggestionKind::History,
+                ),
+            ],
+            SUGGESTION_LIMIT,
+        );
+
+        assert_eq!(
+            urls(&suggestions),
+            ["https://example.com/", "https://example.com/other"]
+        );
+        assert_eq!(suggestions[0].kind, SuggestionKind::Tab);
+        assert_eq!(suggestions[0].title, "Open tab");
+    }
+
+    #[test]
+    fn display_url_drops_the_scheme() {
+        let suggestions = rank(
+            "example",
+            vec![
+                candidate("Secure", "https://example.com/a", SuggestionKind::Tab),
+                candidate("Internal", "owl://example", SuggestionKind::Tab),
+                candidate("Example", "example.net", SuggestionKind::History),
+            ],
+            SUGGESTION_LIMIT,
+        );
+
+        let display: Vec<&str> = suggestions
+            .iter()
+            .map(|suggestion| suggestion.display_url.as_str())
+            .collect();
+        assert_eq!(display, ["example.com/a", "example", "example.net"]);
+    }
+
+    #[test]
+    fn blank_queries_and_the_limit_bound_the_results() {
+        let candidates: Vec<_> = (0..SUGGESTION_LIMIT + 2)
+            .map(|index| {
+                candidate(
+                    "Page",
+                    &format!("https://example.com/{index}"),
+                    SuggestionKind::History,
+                )
+            })
+            .collect();
+
+        assert!(rank("  ", candidates.clone(), SUGGESTION_LIMIT).is_empty());
+        assert_eq!(
+            rank("page", candidates, SUGGESTION_LIMIT).len(),
+            SUGGESTION_LIMIT
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use std::cell::Cell;
 
+pub mod metrics;
+
 /// Simple monotonic id generator for in-process identifiers.
 #[derive(Debug)]
 pub struct IdGenerator {
@@ -20,6 +22,18 @@ impl IdGenerator {
         self.next.set(id + 1);
         id
     }
+
+    /// Returns the id that the next call to [`IdGenerator::next`] will yield.
+    pub fn peek(&self) -> u64 {
+        self.next.get()
+    }
+
+    /// Resets the generator so the next id is `to`.
+    ///
+    /// Useful for tests and for seeding after a session load.
+    pub fn reset(&self, to: u64) {
+        self.next.set(to);
+    }
 }
 
 impl Default for IdGenerator {
@@ -27,3 +41,29 @@ impl Default for IdGenerator {
         Self::new(1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peek_matches_next() {
+        let ids = IdGenerator::new(5);
+        assert_eq!(ids.peek(), 5);
+        assert_eq!(ids.peek(), 5);
+        assert_eq!(ids.next(), 5);
+        assert_eq!(ids.peek(), 6);
+        assert_eq!(ids.next(), 6);
+    }
+
+    #[test]
+    fn reset_sets_next_id() {
+        let ids = IdGenerator::default();
+        ids.next();
+        ids.next();
+        ids.reset(10);
+        assert_eq!(ids.peek(), 10);
+        assert_eq!(ids.next(), 10);
+        assert_eq!(ids.next(), 11);
+    }
+}
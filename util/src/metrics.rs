@@ -0,0 +1,106 @@
+//! Process-local activity counters for diagnosing performance.
+//!
+//! Counting is compiled in only with the `metrics` feature; without it
+//! [`increment`] is a no-op and every snapshot reads zero. Nothing here ever
+//! leaves the process.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A counted event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Counter {
+    TabsCreated,
+    TabsClosed,
+    Reconciles,
+    PressureTransitions,
+    FaviconFetches,
+    IpcMessagesSent,
+}
+
+impl Counter {
+    /// Every counter, in snapshot order.
+    pub const ALL: [Counter; 6] = [
+        Counter::TabsCreated,
+        Counter::TabsClosed,
+        Counter::Reconciles,
+        Counter::PressureTransitions,
+        Counter::FaviconFetches,
+        Counter::IpcMessagesSent,
+    ];
+
+    /// Stable snake_case name, suitable as a serialized key.
+    pub fn name(self) -> &'static str {
+        match self {
+            Counter::TabsCreated => "tabs_created",
+            Counter::TabsClosed => "tabs_closed",
+            Counter::Reconciles => "reconciles",
+            Counter::PressureTransitions => "pressure_transitions",
+            Counter::FaviconFetches => "favicon_fetches",
+            Counter::IpcMessagesSent => "ipc_messages_sent",
+        }
+    }
+}
+
+static COUNTERS: [AtomicU64; Counter::ALL.len()] =
+    [const { AtomicU64::new(0) }; Counter::ALL.len()];
+
+/// Returns whether counters are compiled in.
+pub const fn enabled() -> bool {
+    cfg!(feature = "metrics")
+}
+
+/// Counts one occurrence of `counter`.
+#[inline]
+pub fn increment(counter: Counter) {
+    if enabled() {
+        COUNTERS[counter as usize].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Point-in-time copy of all counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    values: [u64; Counter::ALL.len()],
+}
+
+impl MetricsSnapshot {
+    /// Returns the value of `counter` at the time of the snapshot.
+    pub fn get(&self, counter: Counter) -> u64 {
+        self.values[counter as usize]
+    }
+
+    /// Iterates over all counters and their values.
+    pub fn iter(&self) -> impl Iterator<Item = (Counter, u64)> + '_ {
+        Counter::ALL
+            .iter()
+            .map(move |counter| (*counter, self.get(*counter)))
+    }
+}
+
+/// Reads all counters. Counters are read independently, so a snapshot taken
+/// while other threads count is not atomic as a whole.
+pub fn snapshot() -> MetricsSnapshot {
+    MetricsSnapshot {
+        values: std::array::from_fn(|index| COUNTERS[index].load(Ordering::Relaxed)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_only_when_enabled() {
+        let before = snapshot();
+        increment(Counter::FaviconFetches);
+        increment(Counter::FaviconFetches);
+
+        let after = snapshot();
+        let expected = if enabled() { 2 } else { 0 };
+        assert_eq!(
+            after.get(Counter::FaviconFetches) - before.get(Counter::FaviconFetches),
+            expected
+        );
+        assert_eq!(after.iter().count(), Counter::ALL.len());
+    }
+}